@@ -0,0 +1,53 @@
+//! Benchmark of resolving command paths, with and without the lookup cache.
+//!
+//! Run with `cargo +nightly bench --bench resolve`.
+
+#![feature(test)]
+
+extern crate test;
+
+use riveting_bot::commands::builder::*;
+use riveting_bot::commands::function::mock;
+use riveting_bot::commands::{Commands, CommandsBuilder};
+use test::Bencher;
+
+const PATH: &[&str] = &["roles", "edit", "mappings"];
+
+fn commands() -> Commands {
+    let mut commands = CommandsBuilder::new();
+    for i in 0..20 {
+        let name: &'static str = Box::leak(format!("command{i}").into_boxed_str());
+        commands.bind(command(name, "description").attach(mock::classic));
+    }
+    commands.bind(
+        command("roles", "description")
+            .option(sub("setup", "description").attach(mock::classic))
+            .option(
+                group("edit", "description")
+                    .option(sub("message", "description").attach(mock::classic))
+                    .option(sub("mappings", "description").attach(mock::classic)),
+            ),
+    );
+    commands.build()
+}
+
+#[bench]
+fn resolve_uncached(b: &mut Bencher) {
+    let commands = commands();
+    b.iter(|| {
+        commands.clear_lookups();
+        commands.resolve(test::black_box(PATH))
+    });
+}
+
+#[bench]
+fn resolve_cached(b: &mut Bencher) {
+    let commands = commands();
+    b.iter(|| commands.resolve(test::black_box(PATH)));
+}
+
+#[bench]
+fn clear_only(b: &mut Bencher) {
+    let commands = commands();
+    b.iter(|| commands.clear_lookups());
+}
@@ -1,6 +1,8 @@
+use riveting_bot::commands::handle;
 use riveting_bot::commands::prelude::*;
 use riveting_bot::utils::prelude::*;
-use twilight_model::id::marker::{ChannelMarker, GuildMarker};
+use twilight_model::guild::Permissions;
+use twilight_model::id::marker::{ChannelMarker, GuildMarker, RoleMarker, UserMarker};
 use twilight_model::id::Id;
 
 /// Command: Create or edit bot messages.
@@ -18,7 +20,8 @@ impl Bot {
                 sub("say", "Post a message by the bot.")
                     .attach(Say::classic)
                     .attach(Say::slash)
-                    .option(string("text", "What to say.").required()),
+                    .option(string("text", "What to say.").required())
+                    .option(channel("channel", "Where to say it, defaults to here.")),
             )
             .option(
                 sub("edit", "Edit an existing bot message.")
@@ -46,9 +49,23 @@ impl Say {
         args: &Args,
         guild_id: Option<Id<GuildMarker>>,
         channel_id: Id<ChannelMarker>,
+        user_id: Id<UserMarker>,
+        roles: &[Id<RoleMarker>],
     ) -> CommandResult<()> {
-        if guild_id.is_none() {
+        let Some(guild_id) = guild_id else {
             return Err(CommandError::Disabled);
+        };
+
+        let channel_id = match args.channel("channel") {
+            Ok(chan) => ctx.guild_channel_from(guild_id, chan.id()).await?.id,
+            Err(_) => channel_id,
+        };
+
+        // Only where the user could send the message themselves.
+        let perms =
+            handle::member_channel_permissions(ctx, guild_id, channel_id, user_id, roles).await?;
+        if !perms.contains(Permissions::VIEW_CHANNEL | Permissions::SEND_MESSAGES) {
+            return Err(CommandError::AccessDenied);
         }
 
         let text = args.string("text")?;
@@ -68,11 +85,14 @@ impl Say {
     }
 
     async fn classic(ctx: Context, req: ClassicRequest) -> CommandResponse {
+        let roles = req.message.member.as_ref().map_or(&[][..], |m| &m.roles);
         Self::uber(
             &ctx,
             &req.args,
             req.message.guild_id,
             req.message.channel_id,
+            req.message.author.id,
+            roles,
         )
         .await?;
 
@@ -84,7 +104,24 @@ impl Say {
             return Err(CommandError::MissingArgs);
         };
 
-        Self::uber(&ctx, &req.args, req.interaction.guild_id, channel.id).await?;
+        let Some(user_id) = req.interaction.author_id() else {
+            return Err(CommandError::MissingArgs);
+        };
+        let roles = req
+            .interaction
+            .member
+            .as_ref()
+            .map_or(&[][..], |m| &m.roles);
+
+        Self::uber(
+            &ctx,
+            &req.args,
+            req.interaction.guild_id,
+            channel.id,
+            user_id,
+            roles,
+        )
+        .await?;
 
         Ok(Response::clear(ctx, req))
     }
@@ -0,0 +1,195 @@
+use riveting_bot::commands::checks;
+use riveting_bot::commands::prelude::*;
+use riveting_bot::utils::prelude::*;
+use twilight_mention::Mention;
+use twilight_model::id::marker::{ChannelMarker, GuildMarker};
+use twilight_model::id::Id;
+
+/// Command: Manage channels where commands are disabled.
+pub struct Channels;
+
+impl Channels {
+    pub fn command() -> impl Into<BaseCommand> {
+        use riveting_bot::commands::builder::*;
+
+        command("channels", "Manage channels where commands are disabled.")
+            .attach(Self::classic)
+            .attach(Self::slash)
+            .permissions(Permissions::MANAGE_CHANNELS)
+            .check(checks::guild_only())
+            .option(
+                sub("disabled", "List channels where commands are disabled.")
+                    .attach(Disabled::classic)
+                    .attach(Disabled::slash),
+            )
+            .option(
+                sub("disable", "Disable commands in a channel.")
+                    .attach(Toggle::disable_classic)
+                    .attach(Toggle::disable_slash)
+                    .option(channel(
+                        "channel",
+                        "Channel to disable, this one if not given.",
+                    )),
+            )
+            .option(
+                sub("enable", "Enable commands in a channel again.")
+                    .attach(Toggle::enable_classic)
+                    .attach(Toggle::enable_slash)
+                    .option(channel(
+                        "channel",
+                        "Channel to enable, this one if not given.",
+                    )),
+            )
+    }
+
+    async fn classic(_ctx: Context, _req: ClassicRequest) -> CommandResponse {
+        Err(CommandError::MissingArgs)
+    }
+
+    async fn slash(_ctx: Context, _req: SlashRequest) -> CommandResponse {
+        Err(CommandError::MissingArgs)
+    }
+}
+
+/// Command: List channels where commands are disabled.
+struct Disabled;
+
+impl Disabled {
+    fn uber(ctx: &Context, guild_id: Option<Id<GuildMarker>>) -> CommandResult<String> {
+        let Some(guild_id) = guild_id else {
+            return Err(CommandError::Disabled);
+        };
+
+        let mut channels: Vec<_> = ctx
+            .config
+            .guild(guild_id)
+            .disabled_channels()?
+            .into_iter()
+            .collect();
+
+        if channels.is_empty() {
+            return Ok("Commands are not disabled in any channel.".to_string());
+        }
+
+        channels.sort_unstable();
+        let list = channels
+            .iter()
+            .map(|id| format!("- {}", id.mention()))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        Ok(format!("Commands are disabled in:\n{list}"))
+    }
+
+    async fn classic(ctx: Context, req: ClassicRequest) -> CommandResponse {
+        let content = Self::uber(&ctx, req.message.guild_id)?;
+
+        ctx.http
+            .create_message(req.message.channel_id)
+            .reply(req.message.id)
+            .content(&content)?
+            .await?;
+
+        Ok(Response::none())
+    }
+
+    async fn slash(ctx: Context, req: SlashRequest) -> CommandResponse {
+        let content = Self::uber(&ctx, req.interaction.guild_id)?;
+
+        ctx.update_response(&req.interaction.token, &content)
+            .await?;
+
+        Ok(Response::none())
+    }
+}
+
+/// Command: Disable or enable commands in a channel.
+struct Toggle;
+
+impl Toggle {
+    fn uber(
+        ctx: &Context,
+        guild_id: Option<Id<GuildMarker>>,
+        current: Id<ChannelMarker>,
+        args: &Args,
+        disabled: bool,
+    ) -> CommandResult<String> {
+        let Some(guild_id) = guild_id else {
+            return Err(CommandError::Disabled);
+        };
+
+        let channel_id = args.channel("channel").map_or(current, |c| c.id());
+        let changed = ctx
+            .config
+            .guild(guild_id)
+            .set_channel_disabled(channel_id, disabled)?;
+
+        info!(
+            "Commands {} in channel '{channel_id}' of guild '{guild_id}'",
+            if disabled { "disabled" } else { "enabled" }
+        );
+
+        Ok(match (changed, disabled) {
+            (true, true) => format!(
+                "Commands disabled in {}, they can be enabled again from another channel.",
+                channel_id.mention()
+            ),
+            (true, false) => format!("Commands enabled in {}.", channel_id.mention()),
+            (false, true) => format!("Commands are already disabled in {}.", channel_id.mention()),
+            (false, false) => format!("Commands are not disabled in {}.", channel_id.mention()),
+        })
+    }
+
+    async fn classic(ctx: Context, req: ClassicRequest, disabled: bool) -> CommandResponse {
+        let content = Self::uber(
+            &ctx,
+            req.message.guild_id,
+            req.message.channel_id,
+            &req.args,
+            disabled,
+        )?;
+
+        ctx.http
+            .create_message(req.message.channel_id)
+            .reply(req.message.id)
+            .content(&content)?
+            .await?;
+
+        Ok(Response::none())
+    }
+
+    async fn slash(ctx: Context, req: SlashRequest, disabled: bool) -> CommandResponse {
+        let Some(channel) = req.interaction.channel.as_ref() else {
+            return Err(CommandError::MissingArgs);
+        };
+
+        let content = Self::uber(
+            &ctx,
+            req.interaction.guild_id,
+            channel.id,
+            &req.args,
+            disabled,
+        )?;
+
+        ctx.update_response(&req.interaction.token, &content)
+            .await?;
+
+        Ok(Response::none())
+    }
+
+    async fn disable_classic(ctx: Context, req: ClassicRequest) -> CommandResponse {
+        Self::classic(ctx, req, true).await
+    }
+
+    async fn disable_slash(ctx: Context, req: SlashRequest) -> CommandResponse {
+        Self::slash(ctx, req, true).await
+    }
+
+    async fn enable_classic(ctx: Context, req: ClassicRequest) -> CommandResponse {
+        Self::classic(ctx, req, false).await
+    }
+
+    async fn enable_slash(ctx: Context, req: SlashRequest) -> CommandResponse {
+        Self::slash(ctx, req, false).await
+    }
+}
@@ -0,0 +1,198 @@
+use std::time::Duration;
+
+use riveting_bot::commands::arg::Ref;
+use riveting_bot::commands::prelude::*;
+use riveting_bot::config::GuildSettings;
+use riveting_bot::utils;
+use riveting_bot::utils::prelude::*;
+use twilight_model::id::marker::{ChannelMarker, GuildMarker, UserMarker};
+use twilight_model::id::Id;
+
+/// Largest settings file accepted for import.
+const MAX_IMPORT_BYTES: usize = 1024 * 1024;
+
+/// Time to wait for the import to be confirmed.
+const CONFIRM_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Command: Export or import guild settings.
+pub struct Config;
+
+impl Config {
+    pub fn command() -> impl Into<BaseCommand> {
+        use riveting_bot::commands::builder::*;
+
+        command("config", "Export or import the settings of this server.")
+            .attach(Self::classic)
+            .attach(Self::slash)
+            .permissions(Permissions::MANAGE_GUILD)
+            .option(
+                sub("export", "Get the server settings as a json file.")
+                    .attach(Export::classic)
+                    .attach(Export::slash),
+            )
+            .option(
+                sub("import", "Replace the server settings with a json file.")
+                    .attach(Import::classic)
+                    .attach(Import::slash)
+                    .option(attachment("file", "Settings json file.").required()),
+            )
+    }
+
+    async fn classic(_ctx: Context, _req: ClassicRequest) -> CommandResponse {
+        Err(CommandError::MissingArgs)
+    }
+
+    async fn slash(_ctx: Context, _req: SlashRequest) -> CommandResponse {
+        Err(CommandError::MissingArgs)
+    }
+}
+
+/// Command: Export guild settings as a file.
+struct Export;
+
+impl Export {
+    fn uber(ctx: &Context, guild_id: Option<Id<GuildMarker>>) -> CommandResult<(String, Vec<u8>)> {
+        let Some(guild_id) = guild_id else {
+            return Err(CommandError::Disabled);
+        };
+
+        let settings = ctx.config.guild(guild_id).settings()?.to_owned();
+        let json = serde_json::to_vec_pretty(&settings).context("Failed to serialize settings")?;
+
+        Ok((format!("guild-{guild_id}.json"), json))
+    }
+
+    async fn classic(ctx: Context, req: ClassicRequest) -> CommandResponse {
+        let (filename, bytes) = Self::uber(&ctx, req.message.guild_id)?;
+        Ok(Response::file(ctx, req, filename, bytes))
+    }
+
+    async fn slash(ctx: Context, req: SlashRequest) -> CommandResponse {
+        let (filename, bytes) = Self::uber(&ctx, req.interaction.guild_id)?;
+        Ok(Response::file(ctx, req, filename, bytes))
+    }
+}
+
+/// Command: Replace guild settings from a file.
+struct Import;
+
+impl Import {
+    async fn uber(
+        ctx: &Context,
+        args: &Args,
+        guild_id: Option<Id<GuildMarker>>,
+        channel_id: Id<ChannelMarker>,
+        user_id: Id<UserMarker>,
+    ) -> CommandResult<String> {
+        let Some(guild_id) = guild_id else {
+            return Err(CommandError::Disabled);
+        };
+
+        let Ref::Obj(file) = args.attachment("file")? else {
+            return Err(CommandError::MissingArgs);
+        };
+
+        let json = match utils::download(&file.url, MAX_IMPORT_BYTES).await {
+            Ok(json) => json,
+            Err(e) => return Ok(format!("Could not get the file: {}", e.oneliner())),
+        };
+
+        let settings = match GuildSettings::from_json_strict(&json) {
+            Ok(settings) => settings,
+            Err(e) => return Ok(format!("Invalid settings file: {}", e.oneliner())),
+        };
+
+        // Settings of another server would point to channels and roles that are not here.
+        let mut unknown = Vec::new();
+        for channel_id in settings.channel_ids() {
+            if let Err(e) = ctx.guild_channel_from(guild_id, channel_id).await {
+                debug!("Imported channel '{channel_id}' is not usable: {e}");
+                unknown.push(format!("channel {channel_id}"));
+            }
+        }
+
+        // Fetched, since cached roles could be of any guild.
+        let role_ids = settings.role_ids();
+        if !role_ids.is_empty() {
+            let roles = ctx.fetch_roles_from(guild_id, &role_ids).await?;
+            unknown.extend(
+                role_ids
+                    .iter()
+                    .filter(|id| !roles.iter().any(|r| r.id == **id))
+                    .map(|id| format!("role {id}")),
+            );
+        }
+
+        if !unknown.is_empty() {
+            return Ok(format!(
+                "Not found in this server: {}",
+                utils::nice_list(&unknown)
+            ));
+        }
+
+        let prompt = "Replace all settings of this server?";
+        if !ctx
+            .confirm_buttons(channel_id, prompt, user_id, CONFIRM_TIMEOUT)
+            .await?
+        {
+            return Ok("Import cancelled.".to_string());
+        }
+
+        ctx.config.guild_settings_with(guild_id, |s| {
+            *s = settings.clone();
+            Ok(())
+        })?;
+
+        info!("Settings of guild '{guild_id}' imported by user '{user_id}'");
+
+        Ok("Settings imported.".to_string())
+    }
+
+    async fn classic(ctx: Context, req: ClassicRequest) -> CommandResponse {
+        let content = Self::uber(
+            &ctx,
+            &req.args,
+            req.message.guild_id,
+            req.message.channel_id,
+            req.message.author.id,
+        )
+        .await?;
+
+        ctx.http
+            .create_message(req.message.channel_id)
+            .reply(req.message.id)
+            .content(&content)?
+            .await?;
+
+        Ok(Response::none())
+    }
+
+    async fn slash(ctx: Context, req: SlashRequest) -> CommandResponse {
+        let channel_id = req
+            .interaction
+            .channel
+            .as_ref()
+            .map(|c| c.id)
+            .ok_or(CommandError::MissingArgs)?;
+        let user_id = req
+            .interaction
+            .author_id()
+            .ok_or(CommandError::MissingArgs)?;
+
+        let content = Self::uber(
+            &ctx,
+            &req.args,
+            req.interaction.guild_id,
+            channel_id,
+            user_id,
+        )
+        .await?;
+
+        ctx.interaction()
+            .update_response(&req.interaction.token)
+            .content(Some(&content))?
+            .await?;
+
+        Ok(Response::none())
+    }
+}
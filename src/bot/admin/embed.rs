@@ -0,0 +1,105 @@
+use riveting_bot::commands::checks;
+use riveting_bot::commands::prelude::*;
+use riveting_bot::components::{self, ComponentError};
+use riveting_bot::modals::{Modal, ModalRequest};
+use riveting_bot::utils::prelude::*;
+use twilight_model::channel::message::component::TextInputStyle;
+use twilight_util::builder::embed::EmbedBuilder;
+
+/// Custom ids of the modal inputs.
+const TITLE: &str = "title";
+const DESCRIPTION: &str = "description";
+const COLOR: &str = "color";
+
+/// Color of the embed if none is given.
+const DEFAULT_COLOR: u32 = 0x5865F2;
+
+/// Command: Write an embed in a modal and post it to the channel.
+pub struct PostEmbed;
+
+impl PostEmbed {
+    pub fn command() -> impl Into<BaseCommand> {
+        use riveting_bot::commands::builder::*;
+
+        command("embed", "Write an embed and post it to this channel.")
+            .attach(Self::slash)
+            .permissions(Permissions::MANAGE_MESSAGES)
+            .bot_permissions(Permissions::SEND_MESSAGES | Permissions::EMBED_LINKS)
+            .check(checks::guild_only())
+            .modal()
+            .ephemeral()
+    }
+
+    fn form() -> Result<Modal, ComponentError> {
+        let mut title = components::text_input(TITLE, "Title", TextInputStyle::Short);
+        title.max_length = Some(256);
+        title.required = Some(false);
+
+        let mut description =
+            components::text_input(DESCRIPTION, "Description", TextInputStyle::Paragraph);
+        description.max_length = Some(4000);
+
+        let mut color = components::text_input(COLOR, "Color", TextInputStyle::Short);
+        color.placeholder = Some(format!("#{DEFAULT_COLOR:06X}"));
+        color.max_length = Some(7);
+        color.required = Some(false);
+
+        Modal::new("Embed builder")
+            .input(title)?
+            .input(description)?
+            .input(color)
+    }
+
+    async fn slash(ctx: Context, req: SlashRequest) -> CommandResponse {
+        let form = Self::form().context("Failed to create embed form")?;
+        Ok(Response::modal(ctx, req, form, Self::submit))
+    }
+
+    async fn submit(ctx: Context, req: ModalRequest) -> CommandResponse {
+        let Some(channel) = req.interaction.channel.as_ref() else {
+            return Err(CommandError::MissingArgs);
+        };
+
+        let color = match req.value(COLOR).map(parse_color) {
+            None => DEFAULT_COLOR,
+            Some(Some(color)) => color,
+            Some(None) => {
+                ctx.interaction()
+                    .update_response(&req.interaction.token)
+                    .content(Some("Expected a hex color, eg. `#5865F2`."))?
+                    .await?;
+
+                return Ok(Response::none());
+            },
+        };
+
+        let mut embed = EmbedBuilder::new()
+            .description(req.value(DESCRIPTION).unwrap_or_default())
+            .color(color);
+        if let Some(title) = req.value(TITLE) {
+            embed = embed.title(title);
+        }
+
+        ctx.http
+            .create_message(channel.id)
+            .embeds(&[embed.build()])?
+            .await
+            .context("Failed to post embed")?;
+
+        ctx.interaction()
+            .update_response(&req.interaction.token)
+            .content(Some("Embed posted."))?
+            .await?;
+
+        Ok(Response::none())
+    }
+}
+
+/// Parse a hex color like `#5865F2`, with or without the `#`.
+fn parse_color(text: &str) -> Option<u32> {
+    let hex = text.trim().trim_start_matches('#');
+    if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    u32::from_str_radix(hex, 16).ok()
+}
@@ -0,0 +1,137 @@
+use riveting_bot::commands::arg::Ref;
+use riveting_bot::commands::prelude::*;
+use riveting_bot::utils;
+use riveting_bot::utils::prelude::*;
+use twilight_model::id::marker::{EmojiMarker, GuildMarker};
+use twilight_model::id::Id;
+
+/// Largest image allowed for an emoji.
+const MAX_EMOJI_BYTES: usize = 256 * 1024;
+
+/// Command: Add a custom emoji or an image as an emoji of the guild.
+pub struct StealEmoji;
+
+impl StealEmoji {
+    pub fn command() -> impl Into<BaseCommand> {
+        use riveting_bot::commands::builder::*;
+
+        command("steal", "Add a custom emoji or an image as an emoji here.")
+            .attach(Self::classic)
+            .attach(Self::slash)
+            .permissions(Permissions::MANAGE_GUILD_EXPRESSIONS)
+            .bot_permissions(Permissions::MANAGE_GUILD_EXPRESSIONS)
+            .option(string("name", "Name of the new emoji.").required())
+            .option(
+                attachment("image", "Image to upload.")
+                    .max_size(MAX_EMOJI_BYTES as u64)
+                    .content_types(["image/png", "image/jpeg", "image/gif", "image/webp"]),
+            )
+            .option(string(
+                "emoji",
+                "Custom emoji or Discord image URL to copy.",
+            ))
+            .example("!steal party <:party:123456789012345678>")
+            .example("/steal name: cat image: cat.png")
+    }
+
+    async fn uber(
+        ctx: &Context,
+        args: &Args,
+        guild_id: Option<Id<GuildMarker>>,
+    ) -> CommandResult<String> {
+        let Some(guild_id) = guild_id else {
+            return Err(CommandError::Disabled);
+        };
+
+        let name = args.string("name")?;
+        if !(2..=32).contains(&name.len())
+            || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        {
+            return Ok("Emoji name must be 2 to 32 letters, numbers or underscores.".to_string());
+        }
+
+        let url = match (args.attachment("image"), args.string("emoji")) {
+            (Ok(image), _) => match image {
+                Ref::Obj(a) => a.url.to_owned(),
+                Ref::Id(_) => return Err(CommandError::MissingArgs),
+            },
+            (_, Ok(emoji)) => match emoji_url(&emoji) {
+                Some(url) => url,
+                None => {
+                    return Ok("Expected a custom emoji or an image URL from Discord.".to_string())
+                },
+            },
+            _ => return Err(CommandError::MissingArgs),
+        };
+
+        let data = match utils::download(&url, MAX_EMOJI_BYTES).await {
+            Ok(data) => data,
+            Err(e) => return Ok(format!("Could not get the image: {}", e.oneliner())),
+        };
+
+        let Some(image) = utils::image_data_uri(&data) else {
+            return Ok("Image must be a png, jpeg, gif or webp.".to_string());
+        };
+
+        let emoji = match ctx.http.create_emoji(guild_id, &name, &image).send().await {
+            Ok(emoji) => emoji,
+            Err(e) => {
+                warn!("Failed to create emoji in guild '{guild_id}': {e}");
+                return Ok(format!("Failed to create emoji: {e}"));
+            },
+        };
+
+        info!("Emoji '{}' created in guild '{guild_id}'", emoji.id);
+
+        Ok(format!(
+            "Added <{}:{}:{}>",
+            if emoji.animated { "a" } else { "" },
+            emoji.name,
+            emoji.id
+        ))
+    }
+
+    async fn classic(ctx: Context, req: ClassicRequest) -> CommandResponse {
+        let content = Self::uber(&ctx, &req.args, req.message.guild_id).await?;
+
+        ctx.http
+            .create_message(req.message.channel_id)
+            .reply(req.message.id)
+            .content(&content)?
+            .await?;
+
+        Ok(Response::none())
+    }
+
+    async fn slash(ctx: Context, req: SlashRequest) -> CommandResponse {
+        let content = Self::uber(&ctx, &req.args, req.interaction.guild_id).await?;
+
+        ctx.interaction()
+            .update_response(&req.interaction.token)
+            .content(Some(&content))?
+            .await?;
+
+        Ok(Response::none())
+    }
+}
+
+/// Image URL of a custom emoji like `<:name:id>` or `<a:name:id>`, or a plain Discord CDN URL.
+fn emoji_url(text: &str) -> Option<String> {
+    let text = text.trim();
+    if utils::is_discord_cdn(text) {
+        return Some(text.to_string());
+    }
+
+    let inner = text.strip_prefix('<')?.strip_suffix('>')?;
+    let (animated, rest) = match inner.strip_prefix("a:") {
+        Some(rest) => (true, rest),
+        None => (false, inner.strip_prefix(':')?),
+    };
+    let (_, id) = rest.split_once(':')?;
+    let id: Id<EmojiMarker> = id.parse().ok()?;
+
+    Some(format!(
+        "https://cdn.discordapp.com/emojis/{id}.{}",
+        if animated { "gif" } else { "png" }
+    ))
+}
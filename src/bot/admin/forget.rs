@@ -0,0 +1,57 @@
+use riveting_bot::commands::prelude::*;
+use riveting_bot::utils::prelude::*;
+use twilight_model::id::marker::GuildMarker;
+use twilight_model::id::Id;
+
+/// Command: Clear the recent messages buffered by the bot in the guild.
+pub struct Forget;
+
+impl Forget {
+    pub fn command() -> impl Into<BaseCommand> {
+        use riveting_bot::commands::builder::*;
+
+        command(
+            "forget",
+            "Clear the recent messages kept by the bot in this server.",
+        )
+        .attach(Self::classic)
+        .attach(Self::slash)
+        .permissions(Permissions::MANAGE_MESSAGES)
+        .ephemeral()
+    }
+
+    async fn uber(ctx: &Context, guild_id: Option<Id<GuildMarker>>) -> CommandResult<String> {
+        let Some(guild_id) = guild_id else {
+            return Err(CommandError::Disabled);
+        };
+
+        let removed = ctx.snipes.forget(&ctx.cache, guild_id);
+
+        info!("Cleared {removed} buffered messages in guild '{guild_id}'");
+
+        Ok(format!("Forgot {removed} buffered messages."))
+    }
+
+    async fn classic(ctx: Context, req: ClassicRequest) -> CommandResponse {
+        let content = Self::uber(&ctx, req.message.guild_id).await?;
+
+        ctx.http
+            .create_message(req.message.channel_id)
+            .reply(req.message.id)
+            .content(&content)?
+            .await?;
+
+        Ok(Response::none())
+    }
+
+    async fn slash(ctx: Context, req: SlashRequest) -> CommandResponse {
+        let content = Self::uber(&ctx, req.interaction.guild_id).await?;
+
+        ctx.interaction()
+            .update_response(&req.interaction.token)
+            .content(Some(&content))?
+            .await?;
+
+        Ok(Response::none())
+    }
+}
@@ -1,3 +1,13 @@
 pub mod bot;
+pub mod channels;
+pub mod config;
+pub mod embed;
+pub mod emoji;
+pub mod forget;
+pub mod nick;
+pub mod perms;
 pub mod roles;
+pub mod setup;
 pub mod silence;
+pub mod slowmode;
+pub mod warn;
@@ -0,0 +1,72 @@
+use riveting_bot::commands::prelude::*;
+use riveting_bot::utils::prelude::*;
+use twilight_model::id::marker::GuildMarker;
+use twilight_model::id::Id;
+
+/// Command: Set the bot's nickname in the guild.
+pub struct SetNick;
+
+impl SetNick {
+    pub fn command() -> impl Into<BaseCommand> {
+        use riveting_bot::commands::builder::*;
+
+        command("setnick", "Set the bot's nickname in this server.")
+            .attach(Self::classic)
+            .attach(Self::slash)
+            .permissions(Permissions::MANAGE_NICKNAMES)
+            .bot_permissions(Permissions::CHANGE_NICKNAME)
+            .option(string("name", "New nickname, leave empty to clear.").rest())
+    }
+
+    async fn uber(
+        ctx: &Context,
+        args: &Args,
+        guild_id: Option<Id<GuildMarker>>,
+    ) -> CommandResult<String> {
+        let Some(guild_id) = guild_id else {
+            return Err(CommandError::Disabled);
+        };
+
+        let name = args.string("name").unwrap_or_default();
+        let name = Some(name.trim()).filter(|n| !n.is_empty());
+
+        let result = ctx.http.update_current_member(guild_id).nick(name)?.await;
+
+        if let Err(e) = result {
+            warn!("Failed to set nickname in guild '{guild_id}': {e}");
+            return Ok(format!("Failed to set nickname: {e}"));
+        }
+
+        ctx.config
+            .guild(guild_id)
+            .set_nickname(name.map(String::from))?;
+
+        Ok(match name {
+            Some(name) => format!("Nickname set to '{name}'."),
+            None => "Nickname cleared.".to_string(),
+        })
+    }
+
+    async fn classic(ctx: Context, req: ClassicRequest) -> CommandResponse {
+        let content = Self::uber(&ctx, &req.args, req.message.guild_id).await?;
+
+        ctx.http
+            .create_message(req.message.channel_id)
+            .reply(req.message.id)
+            .content(&content)?
+            .await?;
+
+        Ok(Response::none())
+    }
+
+    async fn slash(ctx: Context, req: SlashRequest) -> CommandResponse {
+        let content = Self::uber(&ctx, &req.args, req.interaction.guild_id).await?;
+
+        ctx.interaction()
+            .update_response(&req.interaction.token)
+            .content(Some(&content))?
+            .await?;
+
+        Ok(Response::none())
+    }
+}
@@ -0,0 +1,127 @@
+use std::fmt::Write;
+
+use riveting_bot::commands::handle::{self, Access};
+use riveting_bot::commands::prelude::*;
+use riveting_bot::utils::prelude::*;
+use twilight_mention::Mention;
+use twilight_model::id::marker::{ChannelMarker, GuildMarker};
+use twilight_model::id::Id;
+
+/// Command: Inspect command permissions.
+pub struct Perms;
+
+impl Perms {
+    pub fn command() -> impl Into<BaseCommand> {
+        use riveting_bot::commands::builder::*;
+
+        command("perms", "Inspect command permissions.")
+            .attach(Self::classic)
+            .attach(Self::slash)
+            .permissions(Permissions::MANAGE_GUILD)
+            .option(
+                sub(
+                    "check",
+                    "Check if a user can use a command in this channel.",
+                )
+                .attach(Check::classic)
+                .attach(Check::slash)
+                .option(user("user", "User to check.").required())
+                .option(string("command", "Command to check.").required().rest()),
+            )
+    }
+
+    async fn classic(_ctx: Context, _req: ClassicRequest) -> CommandResponse {
+        Err(CommandError::MissingArgs)
+    }
+
+    async fn slash(_ctx: Context, _req: SlashRequest) -> CommandResponse {
+        Err(CommandError::MissingArgs)
+    }
+}
+
+/// Command: Check if a user can use a command.
+struct Check;
+
+impl Check {
+    async fn uber(
+        ctx: &Context,
+        args: &Args,
+        guild_id: Option<Id<GuildMarker>>,
+        channel_id: Id<ChannelMarker>,
+    ) -> CommandResult<String> {
+        let Some(guild_id) = guild_id else {
+            return Err(CommandError::Disabled);
+        };
+
+        let user_id = args.user("user")?.id();
+        let command = args.string("command")?;
+        let path: Vec<_> = command.split_whitespace().collect();
+
+        let Some(resolved) = ctx.commands.resolve(&path) else {
+            return Err(CommandError::NotFound(format!(
+                "Command '{command}' does not exist"
+            )));
+        };
+
+        let member = ctx.http.guild_member(guild_id, user_id).send().await?;
+        let check = handle::check_access(
+            ctx,
+            &resolved.base,
+            Some(guild_id),
+            channel_id,
+            user_id,
+            &member.roles,
+        )
+        .await?;
+
+        let mut content = format!(
+            "Can {} use `{}` in {}?\n",
+            user_id.mention(),
+            resolved.path().join(" "),
+            channel_id.mention()
+        );
+        for (i, step) in check.trace.iter().enumerate() {
+            writeln!(content, "{}. {step}", i + 1)?;
+        }
+        content.push_str(match check.access {
+            Access::Allowed => "✅ Allowed",
+            Access::Disabled => "🚫 Disabled",
+            Access::Denied => "⛔ Denied",
+        });
+
+        Ok(content)
+    }
+
+    async fn classic(ctx: Context, req: ClassicRequest) -> CommandResponse {
+        let content = Self::uber(
+            &ctx,
+            &req.args,
+            req.message.guild_id,
+            req.message.channel_id,
+        )
+        .await?;
+
+        ctx.http
+            .create_message(req.message.channel_id)
+            .reply(req.message.id)
+            .content(&content)?
+            .await?;
+
+        Ok(Response::none())
+    }
+
+    async fn slash(ctx: Context, req: SlashRequest) -> CommandResponse {
+        let Some(channel) = req.interaction.channel.as_ref() else {
+            return Err(CommandError::MissingArgs);
+        };
+
+        let content = Self::uber(&ctx, &req.args, req.interaction.guild_id, channel.id).await?;
+
+        ctx.interaction()
+            .update_response(&req.interaction.token)
+            .content(Some(&content))?
+            .await?;
+
+        Ok(Response::none())
+    }
+}
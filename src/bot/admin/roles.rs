@@ -1,14 +1,16 @@
 use riveting_bot::commands::prelude::*;
 use riveting_bot::config::ReactionRole;
-use riveting_bot::utils;
 use riveting_bot::utils::prelude::*;
+use riveting_bot::{can_manage_role, utils};
 use twilight_gateway::Event;
 use twilight_http::request::channel::reaction::RequestReactionType;
+use twilight_mention::timestamp::{Timestamp, TimestampStyle};
+use twilight_mention::Mention;
 use twilight_model::application::interaction::{Interaction, InteractionData};
 use twilight_model::channel::message::component::{
     ActionRow, Button, ButtonStyle, SelectMenu, SelectMenuOption,
 };
-use twilight_model::channel::message::{Component, MessageFlags, ReactionType};
+use twilight_model::channel::message::{Component, Embed, MessageFlags, ReactionType};
 use twilight_model::channel::Message;
 use twilight_model::gateway::payload::incoming::RoleUpdate;
 use twilight_model::guild::Permissions;
@@ -19,6 +21,7 @@ use twilight_model::id::marker::{
     ChannelMarker, GuildMarker, MessageMarker, RoleMarker, UserMarker,
 };
 use twilight_model::id::Id;
+use twilight_util::builder::embed::{EmbedBuilder, EmbedFieldBuilder};
 use twilight_util::builder::InteractionResponseDataBuilder;
 
 /// Command: Manage reaction-roles.
@@ -32,16 +35,27 @@ impl Roles {
             .attach(Self::classic)
             .attach(Self::slash)
             .permissions(Permissions::ADMINISTRATOR)
+            .intents(Intents::GUILD_MESSAGE_REACTIONS)
+            .example("/roles setup")
+            .example("!roles edit (as a reply to the reaction-roles message)")
             .option(
                 sub("setup", "Setup a new reaction-roles message.")
+                    .bot_permissions(Permissions::MANAGE_ROLES | Permissions::ADD_REACTIONS)
                     .attach(Setup::classic)
                     .attach(Setup::slash),
             )
             .option(
                 sub("edit", "Edit an existing reaction-roles message.")
+                    .bot_permissions(Permissions::MANAGE_ROLES | Permissions::ADD_REACTIONS)
                     .attach(Edit::classic)
                     .option(message("message", "Reaction-roles message to edit.").required()),
             )
+            .option(
+                sub("info", "Show information about a role.")
+                    .attach(Info::classic)
+                    .attach(Info::slash)
+                    .option(role("role", "Role to show.").required()),
+            )
     }
 
     async fn classic(_ctx: Context, _req: ClassicRequest) -> CommandResponse {
@@ -178,6 +192,91 @@ impl Edit {
     }
 }
 
+/// Command: Show information about a role.
+struct Info;
+
+impl Info {
+    async fn uber(
+        ctx: &Context,
+        args: &Args,
+        guild_id: Option<Id<GuildMarker>>,
+    ) -> CommandResult<Embed> {
+        let Some(guild_id) = guild_id else {
+            return Err(CommandError::Disabled);
+        };
+
+        let role_id = args.role("role")?.id();
+        let role = ctx
+            .roles_from(guild_id, &[role_id])
+            .await?
+            .pop()
+            .ok_or_else(|| CommandError::UnknownResource(format!("Role '{role_id}'")))?;
+
+        // Everyone has the `@everyone` role, which has the id of the guild.
+        let members = ctx.cache.guild_members(guild_id).map_or(0, |ids| {
+            ids.iter()
+                .filter(|&&user_id| {
+                    role.id == guild_id.cast()
+                        || ctx
+                            .cache
+                            .member(guild_id, user_id)
+                            .is_some_and(|m| m.roles().contains(&role.id))
+                })
+                .count()
+        });
+
+        let yes_no = |b: bool| if b { "Yes" } else { "No" };
+        let created = Timestamp::new(
+            utils::snowflake_secs(role.id),
+            Some(TimestampStyle::LongDate),
+        );
+
+        let embed = EmbedBuilder::new()
+            .title(&role.name)
+            .color(role.color)
+            .field(EmbedFieldBuilder::new("Role", role.id.mention().to_string()).inline())
+            .field(EmbedFieldBuilder::new("Color", format!("#{:06X}", role.color)).inline())
+            .field(EmbedFieldBuilder::new("Position", role.position.to_string()).inline())
+            .field(EmbedFieldBuilder::new("Members", members.to_string()).inline())
+            .field(EmbedFieldBuilder::new("Mentionable", yes_no(role.mentionable)).inline())
+            .field(EmbedFieldBuilder::new("Hoisted", yes_no(role.hoist)).inline())
+            .field(EmbedFieldBuilder::new(
+                "Created",
+                created.mention().to_string(),
+            ))
+            .field(EmbedFieldBuilder::new(
+                "Permissions",
+                utils::notable_permissions(role.permissions),
+            ))
+            .build();
+
+        Ok(embed)
+    }
+
+    async fn classic(ctx: Context, req: ClassicRequest) -> CommandResponse {
+        let embed = Self::uber(&ctx, &req.args, req.message.guild_id).await?;
+
+        ctx.http
+            .create_message(req.message.channel_id)
+            .reply(req.message.id)
+            .embeds(&[embed])?
+            .await?;
+
+        Ok(Response::none())
+    }
+
+    async fn slash(ctx: Context, req: SlashRequest) -> CommandResponse {
+        let embed = Self::uber(&ctx, &req.args, req.interaction.guild_id).await?;
+
+        ctx.interaction()
+            .update_response(&req.interaction.token)
+            .embeds(Some(&[embed]))?
+            .await?;
+
+        Ok(Response::none())
+    }
+}
+
 /// Content to show on the final message.
 async fn output_message_content(
     ctx: &Context,
@@ -276,6 +375,7 @@ async fn roles_setup_process(
             biased;
             event = reaction_fut => event?, // Proceed with the reaction event.
             mci = controller_fut => break mci?, // Exit loop with button interaction.
+            () = ctx.delayed.cancelled() => return Ok(None), // Shutting down.
         };
 
         match event {
@@ -290,7 +390,8 @@ async fn roles_setup_process(
                     continue;
                 }
 
-                let components = dropdown_components(ctx, guild_id, &added.emoji).await?;
+                let components =
+                    dropdown_components(ctx, guild_id, author_id, &added.emoji).await?;
 
                 // Gray out controller buttons.
                 update_controller(ctx, &mut controller, None, false).await?;
@@ -308,8 +409,11 @@ async fn roles_setup_process(
                     .standby
                     .wait_for_component(dropdown.id, move |event: &Interaction| {
                         event.author_id() == Some(author_id)
-                    })
-                    .await?;
+                    });
+                let Some(list_mci) = ctx.until_shutdown(list_mci).await else {
+                    return Ok(None); // Shutting down.
+                };
+                let list_mci = list_mci?;
 
                 let resp = InteractionResponse {
                     kind: InteractionResponseType::DeferredUpdateMessage,
@@ -349,6 +453,16 @@ async fn roles_setup_process(
                 }
 
                 match choice.parse::<Id<RoleMarker>>() {
+                    // The choice comes from the client, so check it again.
+                    Ok(role_id)
+                        if !ctx.manageable_role(guild_id, ctx.user.id, role_id).await?
+                            || !ctx.manageable_role(guild_id, author_id, role_id).await? =>
+                    {
+                        warn!("Role choice '{role_id}' is not manageable in guild '{guild_id}'");
+
+                        // Update the controller message and re-enable controller buttons.
+                        update_controller(ctx, &mut controller, None, true).await?;
+                    },
                     Ok(role_id) => {
                         // Save the choice.
                         mappings.push(ReactionRole::new(added.emoji.to_owned(), role_id));
@@ -461,6 +575,7 @@ async fn add_reactions_to_message(
 async fn dropdown_components(
     ctx: &Context,
     guild_id: Id<GuildMarker>,
+    author_id: Id<UserMarker>,
     emoji: &ReactionType,
 ) -> AnyResult<Vec<Component>> {
     // Get all available roles. Try cache, otherwise fetch.
@@ -473,33 +588,24 @@ async fn dropdown_components(
     };
 
     // Find the highest role that the bot has.
-    let bot_role = {
-        let bot_roles = match ctx.cache.member(guild_id, ctx.user.id) {
-            Some(m) => m.value().roles().to_vec(),
-            None => {
-                ctx.http
-                    .guild_member(guild_id, ctx.user.id)
-                    .send()
-                    .await?
-                    .roles
-            },
-        };
+    let bot_role = ctx
+        .member_top_role(guild_id, ctx.user.id)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("Could not find maximum role for bot"))?;
 
-        roles
-            .iter()
-            .filter(|r| bot_roles.contains(&r.id))
-            .max()
-            .cloned()
-            .ok_or_else(|| anyhow::anyhow!("Could not find maximum role for bot"))?
-    };
+    // Find the highest role of the user, who should not be able to hand out roles above it.
+    let author_is_owner = ctx.guild_owner(guild_id).await? == author_id;
+    let author_role = ctx.member_top_role(guild_id, author_id).await?;
 
     // Role options to display.
     let role_opts = roles
         .into_iter()
-        // Filter out `@everyone` role.
-        .filter(|r| r.id != guild_id.cast())
-        // Filter out roles that are higher or same as the bot's role.
-        .filter(|r| *r < bot_role)
+        // Filter out `@everyone` role and roles that are higher or same as the bot's role.
+        .filter(|r| can_manage_role(guild_id, false, Some(&bot_role), r))
+        // Filter out roles that are higher or same as the user's role.
+        .filter(|r| {
+            can_manage_role(guild_id, author_is_owner, author_role.as_ref(), r)
+        })
         // Filter out roles that are integration managed.
         .filter(|r| !r.managed)
         // Filter out roles with admin permissions, as a precaution.
@@ -622,7 +728,7 @@ async fn display_emoji_roles(
     Ok(emoji_roles_msg)
 }
 
-fn request_from_emoji(r: &ReactionType) -> RequestReactionType {
+fn request_from_emoji(r: &ReactionType) -> RequestReactionType<'_> {
     match r {
         ReactionType::Custom { id, name, .. } => RequestReactionType::Custom {
             id: *id,
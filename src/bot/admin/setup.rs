@@ -0,0 +1,293 @@
+use std::time::Duration;
+
+use riveting_bot::commands::checks;
+use riveting_bot::commands::prelude::*;
+use riveting_bot::config::Prefix;
+use riveting_bot::utils;
+use riveting_bot::utils::prelude::*;
+use twilight_mention::{Mention, ParseMention};
+use twilight_model::gateway::payload::incoming::MessageCreate;
+use twilight_model::id::marker::{ChannelMarker, GuildMarker, RoleMarker, UserMarker};
+use twilight_model::id::Id;
+
+/// Number of setup steps.
+const STEPS: usize = 4;
+
+/// Time to wait for an answer to each step.
+const STEP_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Time to wait for the settings to be confirmed.
+const CONFIRM_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Answer that keeps the current setting.
+const SKIP: &str = "skip";
+
+/// Answer that stops the setup.
+const CANCEL: &str = "cancel";
+
+/// Command: Configure the essential server settings step by step.
+pub struct Setup;
+
+impl Setup {
+    pub fn command() -> impl Into<BaseCommand> {
+        use riveting_bot::commands::builder::*;
+
+        command("setup", "Configure the essential settings of this server.")
+            .attach(Self::classic)
+            .attach(Self::slash)
+            .permissions(Permissions::MANAGE_GUILD)
+            .check(checks::guild_only())
+    }
+
+    async fn uber(
+        ctx: &Context,
+        guild_id: Option<Id<GuildMarker>>,
+        channel_id: Id<ChannelMarker>,
+        user_id: Id<UserMarker>,
+    ) -> CommandResult<String> {
+        let Some(guild_id) = guild_id else {
+            return Err(CommandError::Disabled);
+        };
+
+        let cancelled = || Ok("Setup cancelled, nothing was changed.".to_string());
+        let mut prompt = Prompt {
+            ctx,
+            guild_id,
+            channel_id,
+            user_id,
+            step: 0,
+        };
+
+        let Some(prefix) = prompt
+            .ask::<Prefix>("What should the prefix of classic commands be? Eg. `!`")
+            .await?
+        else {
+            return cancelled();
+        };
+
+        let Some(mod_log) = prompt
+            .ask::<Id<ChannelMarker>>("Which channel should moderation actions be logged to?")
+            .await?
+        else {
+            return cancelled();
+        };
+
+        let Some(welcome_channel) = prompt
+            .ask::<Id<ChannelMarker>>("Which channel should new members be welcomed in?")
+            .await?
+        else {
+            return cancelled();
+        };
+
+        let Some(moderator_role) = prompt
+            .ask::<Id<RoleMarker>>("Which role do the moderators of this server have?")
+            .await?
+        else {
+            return cancelled();
+        };
+
+        let mut summary = Vec::new();
+        if let Some(prefix) = &prefix {
+            summary.push(format!("- Prefix: `{prefix}`"));
+        }
+        if let Some(channel_id) = mod_log {
+            summary.push(format!("- Moderation log: {}", channel_id.mention()));
+        }
+        if let Some(channel_id) = welcome_channel {
+            summary.push(format!("- Welcome channel: {}", channel_id.mention()));
+        }
+        if let Some(role_id) = moderator_role {
+            summary.push(format!("- Moderator role: {}", role_id.mention()));
+        }
+
+        if summary.is_empty() {
+            return Ok("Setup finished, every step was skipped.".to_string());
+        }
+
+        let summary = summary.join("\n");
+        let confirm = format!("Save these settings?\n{summary}");
+        if !ctx
+            .confirm_buttons(channel_id, &confirm, user_id, CONFIRM_TIMEOUT)
+            .await?
+        {
+            return cancelled();
+        }
+
+        ctx.config.guild_settings_with(guild_id, |s| {
+            if let Some(prefix) = &prefix {
+                s.prefix = prefix.to_owned();
+            }
+            s.mod_log = mod_log.or(s.mod_log);
+            s.welcome_channel = welcome_channel.or(s.welcome_channel);
+            s.moderator_role = moderator_role.or(s.moderator_role);
+            Ok(())
+        })?;
+
+        info!("Setup of guild '{guild_id}' done by user '{user_id}'");
+
+        Ok(format!(
+            "Setup finished, these settings were saved:\n{summary}"
+        ))
+    }
+
+    async fn classic(ctx: Context, req: ClassicRequest) -> CommandResponse {
+        let content = Self::uber(
+            &ctx,
+            req.message.guild_id,
+            req.message.channel_id,
+            req.message.author.id,
+        )
+        .await?;
+
+        ctx.http
+            .create_message(req.message.channel_id)
+            .reply(req.message.id)
+            .allowed_mentions(Some(&utils::default_allowed_mentions()))
+            .content(&content)?
+            .await?;
+
+        Ok(Response::none())
+    }
+
+    async fn slash(ctx: Context, req: SlashRequest) -> CommandResponse {
+        let (Some(channel), Some(user_id)) = (
+            req.interaction.channel.as_ref(),
+            req.interaction.author_id(),
+        ) else {
+            return Err(CommandError::MissingArgs);
+        };
+
+        ctx.update_response(
+            &req.interaction.token,
+            "Setup started, answer the questions below.",
+        )
+        .await?;
+
+        // The steps can take longer than the interaction stays valid, so this is a new message.
+        let content = Self::uber(&ctx, req.interaction.guild_id, channel.id, user_id).await?;
+
+        ctx.http
+            .create_message(channel.id)
+            .allowed_mentions(Some(&utils::default_allowed_mentions()))
+            .content(&content)?
+            .await?;
+
+        Ok(Response::none())
+    }
+}
+
+/// Questions of the setup, answered by replying in the channel.
+struct Prompt<'a> {
+    ctx: &'a Context,
+    guild_id: Id<GuildMarker>,
+    channel_id: Id<ChannelMarker>,
+    user_id: Id<UserMarker>,
+    step: usize,
+}
+
+impl Prompt<'_> {
+    /// Ask the next question until the answer is a valid setting.
+    /// Returns `Some(None)` if the step was skipped, or `None` if the setup was cancelled.
+    async fn ask<T: Setting>(&mut self, question: &str) -> AnyResult<Option<Option<T>>> {
+        self.step += 1;
+
+        let mut content = format!(
+            "**Step {}/{STEPS}:** {question}\nReply `{SKIP}` to keep the current setting, or \
+             `{CANCEL}` to stop.",
+            self.step
+        );
+
+        loop {
+            self.ctx
+                .http
+                .create_message(self.channel_id)
+                .content(&content)?
+                .await?;
+
+            let user_id = self.user_id;
+            let reply = self
+                .ctx
+                .standby
+                .wait_for_message(self.channel_id, move |m: &MessageCreate| {
+                    m.author.id == user_id
+                });
+
+            let reply = self
+                .ctx
+                .until_shutdown(tokio::time::timeout(STEP_TIMEOUT, reply));
+            let Some(Ok(Ok(reply))) = reply.await else {
+                return Ok(None); // Timed out or shutting down.
+            };
+
+            let text = reply.content.trim();
+            if text.eq_ignore_ascii_case(SKIP) {
+                return Ok(Some(None));
+            }
+            if text.eq_ignore_ascii_case(CANCEL) {
+                return Ok(None);
+            }
+
+            match T::parse(self.ctx, self.guild_id, text).await {
+                Ok(value) => return Ok(Some(Some(value))),
+                Err(e) => content = format!("{e} Try again, or reply `{SKIP}` or `{CANCEL}`."),
+            }
+        }
+    }
+}
+
+/// A setting that can be given as an answer.
+#[async_trait]
+trait Setting: Sized {
+    /// Parse the setting from text, or give the reason why it is not valid.
+    async fn parse(
+        ctx: &Context,
+        guild_id: Id<GuildMarker>,
+        text: &str,
+    ) -> Result<Self, &'static str>;
+}
+
+#[async_trait]
+impl Setting for Prefix {
+    async fn parse(_: &Context, _: Id<GuildMarker>, text: &str) -> Result<Self, &'static str> {
+        Self::new(text).ok_or("A prefix cannot be empty or contain spaces.")
+    }
+}
+
+#[async_trait]
+impl Setting for Id<ChannelMarker> {
+    async fn parse(
+        ctx: &Context,
+        guild_id: Id<GuildMarker>,
+        text: &str,
+    ) -> Result<Self, &'static str> {
+        let id = parse_id(text).ok_or("Expected a channel mention or id.")?;
+        match ctx.guild_channel_from(guild_id, id).await {
+            Ok(chan) => Ok(chan.id),
+            Err(_) => Err("That channel is not in this server."),
+        }
+    }
+}
+
+#[async_trait]
+impl Setting for Id<RoleMarker> {
+    async fn parse(
+        ctx: &Context,
+        guild_id: Id<GuildMarker>,
+        text: &str,
+    ) -> Result<Self, &'static str> {
+        let id: Self = parse_id(text).ok_or("Expected a role mention or id.")?;
+        match ctx.fetch_roles_from(guild_id, &[id]).await {
+            // The everyone role has the id of the guild.
+            Ok(roles) if !roles.is_empty() && id.cast() != guild_id => Ok(id),
+            _ => Err("That role is not in this server."),
+        }
+    }
+}
+
+/// Parse an id from a mention or as is.
+fn parse_id<T>(text: &str) -> Option<Id<T>>
+where
+    Id<T>: ParseMention,
+{
+    ParseMention::parse(text).ok().or_else(|| text.parse().ok())
+}
@@ -1,8 +1,12 @@
 use riveting_bot::commands::prelude::*;
+use riveting_bot::utils;
 use riveting_bot::utils::prelude::*;
 use twilight_gateway::Event;
+use twilight_http::request::AuditLogReason;
+use twilight_mention::Mention;
 use twilight_model::id::marker::{GuildMarker, UserMarker};
 use twilight_model::id::Id;
+use twilight_model::user::User;
 
 const DEFAULT_MUTE: u64 = 60;
 
@@ -18,6 +22,8 @@ impl Mute {
             .attach(Self::slash)
             .attach(Self::user)
             .permissions(Permissions::ADMINISTRATOR)
+            .bot_permissions(Permissions::MUTE_MEMBERS)
+            .intents(Intents::GUILD_VOICE_STATES)
             .option(user("user", "Who to mute.").required())
             .option(integer("seconds", "Duration of the mute.").min(0))
     }
@@ -56,14 +62,18 @@ impl Mute {
             }
 
             // Otherwise, try again later when they trigger a voice channel event.
-            ctx.standby
+            let update = ctx
+                .standby
                 .wait_for(guild_id, move |event: &Event| match event {
                     Event::VoiceStateUpdate(data) => {
-                        data.member.as_ref().map_or(false, |m| m.user.id == user_id)
+                        data.member.as_ref().is_some_and(|m| m.user.id == user_id)
                     },
                     _ => false,
-                })
-                .await?;
+                });
+            let Some(update) = ctx.until_shutdown(update).await else {
+                break; // Shutting down.
+            };
+            update?;
         }
 
         Ok(())
@@ -108,3 +118,92 @@ impl Mute {
         .map(|_| Response::none())
     }
 }
+
+/// Command: Lift a timeout of a member.
+pub struct Untimeout;
+
+impl Untimeout {
+    pub fn command() -> impl Into<BaseCommand> {
+        use riveting_bot::commands::builder::*;
+
+        command("untimeout", "Lift a timeout of a member.")
+            .attach(Self::classic)
+            .attach(Self::slash)
+            .permissions(Permissions::MODERATE_MEMBERS)
+            .bot_permissions(Permissions::MODERATE_MEMBERS)
+            .option(user("user", "Whose timeout to lift.").required())
+            .option(string("reason", "Reason for lifting the timeout.").rest())
+    }
+
+    async fn uber(
+        ctx: &Context,
+        guild_id: Option<Id<GuildMarker>>,
+        moderator: &User,
+        args: &Args,
+    ) -> CommandResult<String> {
+        let Some(guild_id) = guild_id else {
+            return Err(CommandError::Disabled);
+        };
+
+        let user_id = args.user("user")?.id();
+        let reason = args.string("reason").ok();
+
+        let member = ctx.http.guild_member(guild_id, user_id).send().await?;
+        let now = chrono::Utc::now().timestamp();
+        if member
+            .communication_disabled_until
+            .is_none_or(|until| until.as_secs() <= now)
+        {
+            return Ok(format!("{} is not timed out.", user_id.mention()));
+        }
+
+        let update = ctx
+            .http
+            .update_guild_member(guild_id, user_id)
+            .communication_disabled_until(None)?;
+        match reason.as_deref() {
+            Some(reason) => update.reason(reason)?.await?,
+            None => update.await?,
+        };
+
+        info!("Timeout of user '{user_id}' lifted in guild '{guild_id}'");
+
+        ctx.mod_log(
+            guild_id,
+            &format!(
+                "🔊 Timeout of **{}** (`{user_id}`) lifted by **{}**{}",
+                utils::display_name(&member.user),
+                utils::display_name(moderator),
+                reason.map(|r| format!(": {r}")).unwrap_or_default()
+            ),
+        )
+        .await?;
+
+        Ok(format!("Timeout of {} lifted.", user_id.mention()))
+    }
+
+    async fn classic(ctx: Context, req: ClassicRequest) -> CommandResponse {
+        let content =
+            Self::uber(&ctx, req.message.guild_id, &req.message.author, &req.args).await?;
+
+        ctx.http
+            .create_message(req.message.channel_id)
+            .reply(req.message.id)
+            .content(&content)?
+            .await?;
+
+        Ok(Response::none())
+    }
+
+    async fn slash(ctx: Context, req: SlashRequest) -> CommandResponse {
+        let author = req.interaction.author().ok_or(CommandError::MissingArgs)?;
+        let content = Self::uber(&ctx, req.interaction.guild_id, author, &req.args).await?;
+
+        ctx.interaction()
+            .update_response(&req.interaction.token)
+            .content(Some(&content))?
+            .await?;
+
+        Ok(Response::none())
+    }
+}
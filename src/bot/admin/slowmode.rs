@@ -0,0 +1,122 @@
+use riveting_bot::commands::prelude::*;
+use riveting_bot::time;
+use riveting_bot::utils::prelude::*;
+use twilight_mention::Mention;
+use twilight_model::id::marker::{ChannelMarker, GuildMarker};
+use twilight_model::id::Id;
+
+/// Longest slowmode allowed by Discord, in seconds.
+const MAX_SLOWMODE: i64 = 21600;
+
+/// Longest duration text that is parsed, anything valid within the limit is much shorter.
+const MAX_INPUT_LENGTH: usize = 32;
+
+/// Command: Set the slowmode of a channel.
+pub struct Slowmode;
+
+impl Slowmode {
+    pub fn command() -> impl Into<BaseCommand> {
+        use riveting_bot::commands::builder::*;
+
+        command("slowmode", "Set the slowmode of a channel.")
+            .attach(Self::classic)
+            .attach(Self::slash)
+            .permissions(Permissions::MANAGE_CHANNELS)
+            .bot_permissions(Permissions::MANAGE_CHANNELS)
+            .option(string("duration", "Eg. `10s`, `5m` or `off`.").required())
+            .option(channel("channel", "Channel to set, this one if not given."))
+            .example("!slowmode 30s")
+            .example("/slowmode duration: off")
+    }
+
+    async fn uber(
+        ctx: &Context,
+        args: &Args,
+        guild_id: Option<Id<GuildMarker>>,
+        current: Id<ChannelMarker>,
+    ) -> CommandResult<String> {
+        let Some(guild_id) = guild_id else {
+            return Err(CommandError::Disabled);
+        };
+
+        let channel_id = match args.channel("channel") {
+            Ok(chan) => ctx.guild_channel_from(guild_id, chan.id()).await?.id,
+            Err(_) => current,
+        };
+
+        let input = args.string("duration")?;
+        let out_of_range = || {
+            Ok(format!(
+                "Slowmode must be between 0s and {}.",
+                time::format_duration(chrono::Duration::seconds(MAX_SLOWMODE))
+            ))
+        };
+
+        if input.trim().len() > MAX_INPUT_LENGTH {
+            return out_of_range();
+        }
+
+        let secs = match input.trim().to_lowercase().as_str() {
+            "off" | "0" => 0,
+            text => match text.parse::<i64>() {
+                Ok(secs) => secs, // Plain seconds.
+                Err(_) => match time::parse_duration(text) {
+                    Ok(duration) => duration.num_seconds(),
+                    Err(e) => return Ok(e.to_string()),
+                },
+            },
+        };
+
+        if !(0..=MAX_SLOWMODE).contains(&secs) {
+            return out_of_range();
+        }
+
+        ctx.http
+            .update_channel(channel_id)
+            .rate_limit_per_user(secs as u16)?
+            .await?;
+
+        info!("Slowmode of channel '{channel_id}' set to {secs}s in guild '{guild_id}'");
+
+        Ok(if secs == 0 {
+            format!("Slowmode of {} turned off.", channel_id.mention())
+        } else {
+            format!(
+                "Slowmode of {} set to {}.",
+                channel_id.mention(),
+                time::format_duration(chrono::Duration::seconds(secs))
+            )
+        })
+    }
+
+    async fn classic(ctx: Context, req: ClassicRequest) -> CommandResponse {
+        let content = Self::uber(
+            &ctx,
+            &req.args,
+            req.message.guild_id,
+            req.message.channel_id,
+        )
+        .await?;
+
+        ctx.http
+            .create_message(req.message.channel_id)
+            .reply(req.message.id)
+            .content(&content)?
+            .await?;
+
+        Ok(Response::none())
+    }
+
+    async fn slash(ctx: Context, req: SlashRequest) -> CommandResponse {
+        let Some(channel) = req.interaction.channel.as_ref() else {
+            return Err(CommandError::MissingArgs);
+        };
+
+        let content = Self::uber(&ctx, &req.args, req.interaction.guild_id, channel.id).await?;
+
+        ctx.update_response(&req.interaction.token, &content)
+            .await?;
+
+        Ok(Response::none())
+    }
+}
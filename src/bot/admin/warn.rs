@@ -0,0 +1,241 @@
+use std::time::Duration;
+
+use riveting_bot::commands::prelude::*;
+use riveting_bot::commands::MAX_MESSAGE_LENGTH;
+use riveting_bot::config::Warning;
+use riveting_bot::utils;
+use riveting_bot::utils::prelude::*;
+use twilight_mention::timestamp::{Timestamp, TimestampStyle};
+use twilight_mention::Mention;
+use twilight_model::id::marker::GuildMarker;
+use twilight_model::id::Id;
+use twilight_model::user::User;
+
+/// Time after which the replies are deleted, so that classic ones do not stay public.
+const REPLY_TTL: Duration = Duration::from_secs(60);
+
+/// Command: Give a warning to a user.
+pub struct Warn;
+
+impl Warn {
+    pub fn command() -> impl Into<BaseCommand> {
+        use riveting_bot::commands::builder::*;
+
+        command("warn", "Give a warning to a user.")
+            .attach(Self::classic)
+            .attach(Self::slash)
+            .permissions(Permissions::MODERATE_MEMBERS)
+            .ephemeral()
+            .auto_delete(REPLY_TTL)
+            .option(user("user", "Who to warn.").alias("member").required())
+            .option(
+                string("reason", "Reason for the warning.")
+                    .required()
+                    .rest(),
+            )
+            .example("!warn @user Spamming in #general")
+            .example("/warn user: @user reason: Spamming")
+    }
+
+    async fn uber(
+        ctx: &Context,
+        guild_id: Option<Id<GuildMarker>>,
+        moderator: &User,
+        args: &Args,
+    ) -> CommandResult<String> {
+        let Some(guild_id) = guild_id else {
+            return Err(CommandError::Disabled);
+        };
+
+        let user_id = args.user("user")?.id();
+        let reason = args.string("reason")?.into_string();
+
+        let id = ctx.config.guild(guild_id).add_warning(Warning::new(
+            user_id,
+            moderator.id,
+            reason.to_owned(),
+        ))?;
+
+        info!("Warning '{id}' given to user '{user_id}' in guild '{guild_id}'");
+
+        // Let the user know, if they accept direct messages.
+        let guild_name = match ctx.cache.guild(guild_id) {
+            Some(g) => g.name().to_string(),
+            None => ctx.http.guild(guild_id).send().await?.name,
+        };
+        let content = format!("You have been warned in **{guild_name}**: {reason}");
+        if let Err(e) = ctx.dm_user(user_id, &content).await {
+            debug!("Could not send warning to user '{user_id}': {e}");
+        }
+
+        ctx.mod_log(
+            guild_id,
+            &format!(
+                "⚠️ Warning `#{id}` given to **{}** (`{user_id}`) by **{}**: {reason}",
+                utils::display_name(&ctx.user_from(user_id).await?),
+                utils::display_name(moderator)
+            ),
+        )
+        .await?;
+
+        Ok(format!("Warning `#{id}` given to {}", user_id.mention()))
+    }
+
+    async fn classic(ctx: Context, req: ClassicRequest) -> CommandResponse {
+        let content =
+            Self::uber(&ctx, req.message.guild_id, &req.message.author, &req.args).await?;
+
+        Ok(Response::reply(ctx, req, content))
+    }
+
+    async fn slash(ctx: Context, req: SlashRequest) -> CommandResponse {
+        let author = req.interaction.author().ok_or(CommandError::MissingArgs)?;
+        let content = Self::uber(&ctx, req.interaction.guild_id, author, &req.args).await?;
+
+        Ok(Response::reply(ctx, req, content))
+    }
+}
+
+/// Command: List warnings of a user.
+pub struct Warnings;
+
+impl Warnings {
+    pub fn command() -> impl Into<BaseCommand> {
+        use riveting_bot::commands::builder::*;
+
+        command("warnings", "List warnings of a user.")
+            .attach(Self::classic)
+            .attach(Self::slash)
+            .permissions(Permissions::MODERATE_MEMBERS)
+            .ephemeral()
+            .auto_delete(REPLY_TTL)
+            .option(user("user", "Whose warnings to list.").required())
+    }
+
+    async fn uber(
+        ctx: &Context,
+        guild_id: Option<Id<GuildMarker>>,
+        args: &Args,
+    ) -> CommandResult<String> {
+        let Some(guild_id) = guild_id else {
+            return Err(CommandError::Disabled);
+        };
+
+        let user_id = args.user("user")?.id();
+        let warnings = ctx.config.guild(guild_id).warnings(user_id)?;
+
+        if warnings.is_empty() {
+            return Ok(format!("{} has no warnings", user_id.mention()));
+        }
+
+        // Moderators are shown by name, they may have left the server since.
+        let mut moderator_ids: Vec<_> = warnings.iter().map(|(_, w)| w.moderator_id).collect();
+        moderator_ids.sort_unstable();
+        moderator_ids.dedup();
+        let moderators = ctx.users_from(&moderator_ids).await;
+
+        let total = warnings.len();
+        let mut content = format!("Warnings of {}:\n", user_id.mention());
+        for (shown, (id, w)) in warnings.into_iter().enumerate() {
+            let time = Timestamp::new(w.timestamp as _, Some(TimestampStyle::ShortDate));
+            let moderator = moderator_ids
+                .binary_search(&w.moderator_id)
+                .ok()
+                .and_then(|i| moderators[i].as_ref())
+                .map_or_else(
+                    || w.moderator_id.mention().to_string(),
+                    |u| u.name.to_owned(),
+                );
+            let line = format!("`#{id}` {} by {moderator}: {}\n", time.mention(), w.reason);
+
+            // Leave room for the note about the warnings that did not fit.
+            let more = format!("…and {} more", total - shown);
+            if content.chars().count() + line.chars().count() + more.chars().count()
+                > MAX_MESSAGE_LENGTH
+            {
+                content.push_str(&more);
+                break;
+            }
+            content.push_str(&line);
+        }
+
+        Ok(content)
+    }
+
+    async fn classic(ctx: Context, req: ClassicRequest) -> CommandResponse {
+        let content = Self::uber(&ctx, req.message.guild_id, &req.args).await?;
+
+        Ok(Response::reply(ctx, req, content))
+    }
+
+    async fn slash(ctx: Context, req: SlashRequest) -> CommandResponse {
+        let content = Self::uber(&ctx, req.interaction.guild_id, &req.args).await?;
+
+        Ok(Response::reply(ctx, req, content))
+    }
+}
+
+/// Command: Remove a warning by id.
+pub struct DelWarn;
+
+impl DelWarn {
+    pub fn command() -> impl Into<BaseCommand> {
+        use riveting_bot::commands::builder::*;
+
+        command("delwarn", "Remove a warning.")
+            .attach(Self::classic)
+            .attach(Self::slash)
+            .permissions(Permissions::MODERATE_MEMBERS)
+            .ephemeral()
+            .auto_delete(REPLY_TTL)
+            .option(integer("id", "Id of the warning.").required().min(1))
+    }
+
+    async fn uber(
+        ctx: &Context,
+        guild_id: Option<Id<GuildMarker>>,
+        moderator: &User,
+        args: &Args,
+    ) -> CommandResult<String> {
+        let Some(guild_id) = guild_id else {
+            return Err(CommandError::Disabled);
+        };
+
+        let id = args.integer("id")? as u64;
+        let Some(warning) = ctx.config.guild(guild_id).remove_warning(id)? else {
+            return Err(CommandError::UnknownResource(format!("Warning '{id}'")));
+        };
+
+        info!("Warning '{id}' removed in guild '{guild_id}'");
+
+        ctx.mod_log(
+            guild_id,
+            &format!(
+                "🗑️ Warning `#{id}` of **{}** (`{}`) removed by **{}**",
+                utils::display_name(&ctx.user_from(warning.user_id).await?),
+                warning.user_id,
+                utils::display_name(moderator)
+            ),
+        )
+        .await?;
+
+        Ok(format!(
+            "Warning `#{id}` of {} removed",
+            warning.user_id.mention()
+        ))
+    }
+
+    async fn classic(ctx: Context, req: ClassicRequest) -> CommandResponse {
+        let content =
+            Self::uber(&ctx, req.message.guild_id, &req.message.author, &req.args).await?;
+
+        Ok(Response::reply(ctx, req, content))
+    }
+
+    async fn slash(ctx: Context, req: SlashRequest) -> CommandResponse {
+        let author = req.interaction.author().ok_or(CommandError::MissingArgs)?;
+        let content = Self::uber(&ctx, req.interaction.guild_id, author, &req.args).await?;
+
+        Ok(Response::reply(ctx, req, content))
+    }
+}
@@ -1,10 +1,26 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::future::Future;
+use std::time::Duration;
+
 use riveting_bot::commands::prelude::*;
+use riveting_bot::utils;
 use riveting_bot::utils::prelude::*;
-use twilight_model::id::marker::{ChannelMarker, MessageMarker};
+use twilight_mention::Mention;
+use twilight_model::channel::message::AllowedMentions;
+use twilight_model::channel::Message;
+use twilight_model::id::marker::{ChannelMarker, MessageMarker, UserMarker};
 use twilight_model::id::Id;
 
 const MAX_DELETE: i64 = 100;
 
+/// Most messages older than two weeks deleted by one command,
+/// as they cannot be bulk deleted and are deleted one at a time instead.
+const MAX_SINGLE_DELETE: usize = 20;
+
+/// Time to wait for the deletion to be confirmed.
+const CONFIRM_TIMEOUT: Duration = Duration::from_secs(30);
+
 /// Command: Delete a bunch of messages at once.
 pub struct BulkDelete {}
 
@@ -15,13 +31,23 @@ impl BulkDelete {
         command("bulk-delete", "Delete many of messages.")
             .attach(Self::classic)
             .attach(Self::slash)
-            .permissions(Permissions::ADMINISTRATOR)
+            .permissions(Permissions::MANAGE_MESSAGES)
+            .bot_permissions(Permissions::MANAGE_MESSAGES | Permissions::READ_MESSAGE_HISTORY)
             .option(
                 integer("amount", "Number of messages to delete.")
-                    .required()
                     .min(0)
                     .max(100),
             )
+            .option(string(
+                "until",
+                "Delete every message after this message id or link instead.",
+            ))
+            .option(bool("bots", "Only delete messages from bots."))
+            .option(user("user", "Only delete messages from this user."))
+            .option(bool("report", "Report the deleted counts per author."))
+            .with_dry_run()
+            .example("!bulk-delete 10 bots: true")
+            .example("/bulk-delete until: 123456789012345678")
     }
 
     async fn uber(
@@ -30,23 +56,43 @@ impl BulkDelete {
         timestamp: i64,
         channel_id: Option<Id<ChannelMarker>>,
         message_id: Option<Id<MessageMarker>>,
-    ) -> CommandResult<()> {
+        user_id: Id<UserMarker>,
+    ) -> CommandResult<Option<String>> {
         const TWO_WEEKS_SECS: i64 = 60 * 60 * 24 * 7 * 2;
         let two_weeks_ago = timestamp - TWO_WEEKS_SECS;
-        let count = args.integer("amount")?;
 
-        let Ok(delete_count) = count.min(MAX_DELETE).try_into() else {
-            return Err(CommandError::UnexpectedArgs(format!(
-                "Could not parse delete count: '{count}'"
-            )));
+        let Some(channel_id) = channel_id else {
+            return Err(CommandError::MissingArgs);
         };
 
-        if delete_count == 0 {
-            return Ok(());
-        }
+        let until = match args.string("until") {
+            Ok(until) => match parse_until(&until, channel_id) {
+                Some(id) => Some(id),
+                None => {
+                    return Ok(Some(
+                        "Expected a message id or link in this channel.".into(),
+                    ))
+                },
+            },
+            Err(_) => None,
+        };
 
-        let Some(channel_id) = channel_id else {
-            return Err(CommandError::MissingArgs);
+        let delete_count = match until {
+            Some(_) => 0,
+            None => {
+                let count = args.integer("amount")?;
+                let Ok(delete_count) = count.min(MAX_DELETE).try_into() else {
+                    return Err(CommandError::UnexpectedArgs(format!(
+                        "Could not parse delete count: '{count}'"
+                    )));
+                };
+
+                if delete_count == 0 {
+                    return Ok(None);
+                }
+
+                delete_count
+            },
         };
 
         let message_id = match message_id {
@@ -66,59 +112,294 @@ impl BulkDelete {
             },
         };
 
-        // Fetch and filter messages that are not older than two weeks.
-        let msgs: Vec<_> = ctx
-            .http
-            .channel_messages(channel_id)
-            .before(message_id)
-            .limit(delete_count)?
-            .send()
-            .await?
+        let bots_only = args.bool("bots").unwrap_or(false);
+        let author = args.user("user").ok().map(|u| u.id());
+
+        let (msgs, capped) = match until {
+            // Fetch every message between the given one and the command.
+            Some(until) => {
+                let max = ctx.config.global().max_purge()?;
+                if max == 0 {
+                    return Ok(Some("Deleting every message after one is disabled.".into()));
+                }
+                fetch_between(ctx, channel_id, until, message_id, max).await?
+            },
+            // Fetch and filter messages that are not older than two weeks.
+            None => {
+                let msgs = ctx
+                    .http
+                    .channel_messages(channel_id)
+                    .before(message_id)
+                    .limit(delete_count)?
+                    .send()
+                    .await?
+                    .into_iter()
+                    .filter(|m| two_weeks_ago < m.timestamp.as_secs())
+                    .collect();
+                (msgs, false)
+            },
+        };
+
+        // Old messages are slow to delete, so only some of them are.
+        let (mut msgs, mut old): (Vec<_>, Vec<_>) = msgs
             .into_iter()
-            .filter(|m| two_weeks_ago < m.timestamp.as_secs())
-            .map(|m| m.id)
-            .collect();
+            .filter(|m| !bots_only || m.author.bot)
+            .filter(|m| author.is_none_or(|a| a == m.author.id))
+            .partition(|m| two_weeks_ago < m.timestamp.as_secs());
+        let capped = capped || old.len() > MAX_SINGLE_DELETE;
+        old.truncate(MAX_SINGLE_DELETE);
+        msgs.append(&mut old);
+
+        let report = args
+            .bool("report")
+            .unwrap_or(false)
+            .then(|| per_author(&msgs));
+        let capped = if capped {
+            " (limit reached, run again to continue)"
+        } else {
+            ""
+        };
+
+        if args.is_dry_run() {
+            return Ok(Some(format!(
+                "Dry run: would affect {} messages{capped}.{}",
+                msgs.len(),
+                report.unwrap_or_default()
+            )));
+        }
+
+        let prompt = format!("Delete {} messages{capped}?", msgs.len());
+        if !ctx
+            .confirm_buttons(channel_id, &prompt, user_id, CONFIRM_TIMEOUT)
+            .await?
+        {
+            return Ok(None);
+        }
 
         debug!("Deleting {} messages", msgs.len());
 
-        // Delete the messages.
-        if msgs.len() > 1 {
-            // Bulk delete must have 2 to 100 messages.
-            let _ = ctx
-                .http
-                .delete_messages(channel_id, &msgs)
-                .context("Failed to delete multiple messages")?
-                .await?;
-        } else if let Some(msg) = msgs.first() {
-            ctx.http.delete_message(channel_id, *msg).await?;
+        // Bulk delete only works on messages newer than two weeks.
+        let (recent, old): (Vec<_>, Vec<_>) = msgs
+            .iter()
+            .partition(|m| two_weeks_ago < m.timestamp.as_secs());
+        let recent: Vec<_> = recent.into_iter().map(|m| m.id).collect();
+
+        for batch in recent.chunks(MAX_DELETE as usize) {
+            if batch.len() > 1 {
+                // Bulk delete must have 2 to 100 messages.
+                let _ = ctx
+                    .http
+                    .delete_messages(channel_id, batch)
+                    .context("Failed to delete multiple messages")?
+                    .await?;
+            } else if let Some(msg) = batch.first() {
+                ctx.http.delete_message(channel_id, *msg).await?;
+            }
         }
 
-        Ok(())
+        for msg in old {
+            ctx.http.delete_message(channel_id, msg.id).await?;
+        }
+
+        let deleted = format!("Deleted {} messages.", msgs.len());
+        Ok(match (until, report) {
+            (_, Some(r)) => Some(format!("{deleted}{r}")),
+            (Some(_), None) => Some(deleted),
+            (None, None) => None,
+        })
     }
 
     async fn classic(ctx: Context, req: ClassicRequest) -> CommandResponse {
-        Self::uber(
+        let report = Self::uber(
             &ctx,
             &req.args,
             req.message.timestamp.as_secs(),
             Some(req.message.channel_id),
             Some(req.message.id),
+            req.message.author.id,
         )
         .await?;
 
+        // The report lists authors by mention, which should not ping them.
+        if let Some(content) = report {
+            ctx.http
+                .create_message(req.message.channel_id)
+                .reply(req.message.id)
+                .allowed_mentions(Some(&AllowedMentions::default()))
+                .content(&content)?
+                .await?;
+
+            return Ok(Response::none());
+        }
+
         Ok(Response::clear(ctx, req))
     }
 
     async fn slash(ctx: Context, req: SlashRequest) -> CommandResponse {
-        Self::uber(
+        let report = Self::uber(
             &ctx,
             &req.args,
             chrono::Utc::now().timestamp(),
             req.interaction.channel.as_ref().map(|c| c.id),
             None,
+            req.interaction
+                .author_id()
+                .ok_or(CommandError::MissingArgs)?,
         )
         .await?;
 
+        if let Some(content) = report {
+            ctx.interaction()
+                .update_response(&req.interaction.token)
+                .allowed_mentions(Some(&AllowedMentions::default()))
+                .content(Some(&content))?
+                .await?;
+
+            return Ok(Response::none());
+        }
+
         Ok(Response::clear(ctx, req))
     }
 }
+
+/// List message counts by author, most first.
+fn per_author(msgs: &[Message]) -> String {
+    let mut counts = HashMap::<_, usize>::new();
+    for msg in msgs {
+        *counts.entry(msg.author.id).or_default() += 1;
+    }
+
+    let mut counts: Vec<_> = counts.into_iter().collect();
+    counts.sort_unstable_by_key(|&(_, count)| std::cmp::Reverse(count));
+
+    counts
+        .into_iter()
+        .fold(String::new(), |mut text, (id, count)| {
+            let _ = write!(text, "\n{}: {count}", id.mention());
+            text
+        })
+}
+
+/// Message id from a plain id or a message link, if the link points to the given channel.
+fn parse_until(text: &str, channel_id: Id<ChannelMarker>) -> Option<Id<MessageMarker>> {
+    match utils::parse_message_link(text)? {
+        (Some(channel), _) if channel != channel_id => None,
+        (_, message_id) => Some(message_id),
+    }
+}
+
+/// Fetch messages after `after` and before `before`, oldest first, up to `max` of them.
+/// Also returns whether there were more messages than `max`.
+async fn fetch_between(
+    ctx: &Context,
+    channel_id: Id<ChannelMarker>,
+    after: Id<MessageMarker>,
+    before: Id<MessageMarker>,
+    max: usize,
+) -> CommandResult<(Vec<Message>, bool)> {
+    collect_between(after, before, max, |cursor| async move {
+        let page = ctx
+            .http
+            .channel_messages(channel_id)
+            .after(cursor)
+            .limit(MAX_DELETE as u16)?
+            .send()
+            .await?;
+        Ok(page)
+    })
+    .await
+}
+
+/// Collect the pages of messages from `fetch_page`, which gives the messages after a cursor,
+/// for [`fetch_between`].
+async fn collect_between<F, Fut>(
+    after: Id<MessageMarker>,
+    before: Id<MessageMarker>,
+    max: usize,
+    mut fetch_page: F,
+) -> CommandResult<(Vec<Message>, bool)>
+where
+    F: FnMut(Id<MessageMarker>) -> Fut,
+    Fut: Future<Output = CommandResult<Vec<Message>>>,
+{
+    let mut msgs = Vec::new();
+    let mut cursor = after;
+
+    loop {
+        let mut page = fetch_page(cursor).await?;
+
+        let full = page.len() == MAX_DELETE as usize;
+        page.sort_unstable_by_key(|m| m.id);
+
+        let Some(last) = page.last() else {
+            break;
+        };
+        cursor = last.id;
+
+        let reached = cursor >= before;
+        msgs.extend(page.into_iter().filter(|m| m.id < before));
+
+        if msgs.len() > max {
+            msgs.truncate(max);
+            return Ok((msgs, true));
+        }
+
+        if reached || !full {
+            break;
+        }
+    }
+
+    Ok((msgs, false))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::chat_message;
+
+    /// Collect from a channel with messages of ids `1..=count`, newest first in each page.
+    async fn collect(count: u64, after: u64, before: u64, max: usize) -> (Vec<u64>, bool) {
+        let (msgs, capped) = collect_between(Id::new(after), Id::new(before), max, |cursor| {
+            let mut page: Vec<_> = (cursor.get() + 1..=count)
+                .take(MAX_DELETE as usize)
+                .map(|id| chat_message(id, 2, "", None))
+                .collect();
+            page.reverse();
+            async move { Ok(page) }
+        })
+        .await
+        .unwrap();
+        (msgs.iter().map(|m| m.id.get()).collect(), capped)
+    }
+
+    #[test]
+    fn until_message() {
+        let channel_id = Id::new(10);
+
+        assert_eq!(parse_until("123", channel_id), Some(Id::new(123)));
+        assert_eq!(
+            parse_until("https://discord.com/channels/1/10/123", channel_id),
+            Some(Id::new(123))
+        );
+        assert_eq!(
+            parse_until("https://discord.com/channels/1/11/123", channel_id),
+            None
+        );
+        assert_eq!(parse_until("last week", channel_id), None);
+    }
+
+    #[tokio::test]
+    async fn messages_between() {
+        assert_eq!(collect(10, 3, 8, 100).await, (vec![4, 5, 6, 7], false));
+        assert_eq!(collect(10, 10, 11, 100).await, (vec![], false));
+
+        // Several pages, stopping at the command message.
+        let (ids, capped) = collect(300, 50, 250, 1000).await;
+        assert_eq!(ids, (51..250).collect::<Vec<_>>());
+        assert!(!capped);
+
+        let (ids, capped) = collect(300, 50, 250, 120).await;
+        assert_eq!(ids, (51..171).collect::<Vec<_>>());
+        assert!(capped);
+    }
+}
@@ -1,8 +1,10 @@
 use indoc::formatdoc;
 use riveting_bot::commands::prelude::*;
+use riveting_bot::paginator;
 use riveting_bot::utils::prelude::*;
 use twilight_model::id::marker::GuildMarker;
 use twilight_model::id::Id;
+use twilight_util::builder::embed::EmbedBuilder;
 
 /// Command: Ping Pong!
 pub struct Ping;
@@ -28,10 +30,7 @@ impl Ping {
     }
 
     async fn slash(ctx: Context, req: SlashRequest) -> CommandResponse {
-        ctx.interaction()
-            .create_followup(&req.interaction.token)
-            .content("Pong!")?
-            .await?;
+        ctx.update_response(&req.interaction.token, "Pong!").await?;
 
         Ok(Response::none())
     }
@@ -52,24 +51,24 @@ impl About {
             .dm()
     }
 
-    fn uber(self, ctx: &Context) -> String {
-        formatdoc!(
+    fn uber(self, ctx: &Context) -> AnyResult<String> {
+        Ok(formatdoc!(
             "I am a RivetingBot!
             You can list my commands with `/help` or `{prefix}help` command.
             My current version *(allegedly)* is `{version}`.
             My source is available at <{link}>
             ",
-            prefix = ctx.config.classic_prefix(self.guild_id).unwrap_or_default(),
+            prefix = ctx.config.classic_prefix(self.guild_id)?,
             version = env!("CARGO_PKG_VERSION"),
             link = env!("CARGO_PKG_REPOSITORY"),
-        )
+        ))
     }
 
     async fn classic(ctx: Context, req: ClassicRequest) -> CommandResponse {
         let about_msg = Self {
             guild_id: req.message.guild_id,
         }
-        .uber(&ctx);
+        .uber(&ctx)?;
 
         ctx.http
             .create_message(req.message.channel_id)
@@ -84,11 +83,9 @@ impl About {
         let about_msg = Self {
             guild_id: req.interaction.guild_id,
         }
-        .uber(&ctx);
+        .uber(&ctx)?;
 
-        ctx.interaction()
-            .create_followup(&req.interaction.token)
-            .content(&about_msg)?
+        ctx.update_response(&req.interaction.token, &about_msg)
             .await?;
 
         Ok(Response::none())
@@ -108,56 +105,85 @@ impl Help {
         command("help", "List bot commands.")
             .attach(Self::classic)
             .attach(Self::slash)
-            .option(string("command", "Get help on a command.")) // Choices added here after other binds.
+            .option(string("command", "Get help on a command.").autocomplete()) // Choices added here after other binds.
             .dm()
     }
 
-    fn uber(self, ctx: &Context) -> AnyResult<String> {
+    /// Help of the command, or pages of the command list.
+    fn uber(self, ctx: &Context) -> AnyResult<Vec<String>> {
         Ok(if let Ok(value) = self.args.string("command") {
-            ctx.commands.get(&value).map_or_else(
+            let path: Vec<_> = value.split_whitespace().collect();
+            vec![ctx.commands.resolve(&path).map_or_else(
                 || format!("Command `{value}` not found :|"),
                 |cmd| cmd.generate_help(),
-            )
+            )]
         } else {
-            formatdoc! {"
-                ```yaml
-                Prefix: '/' or '{prefix}'
-                Commands:
-                {commands}
-                ```",
-                prefix = ctx.config.classic_prefix(self.guild_id).unwrap_or_default(),
-                commands = ctx.commands.display(ctx, self.guild_id)?
-            }
+            ctx.commands.display(ctx, self.guild_id)?
         })
     }
 
     async fn classic(ctx: Context, req: ClassicRequest) -> CommandResponse {
-        let help_msg = Self {
+        let pages = Self {
             args: req.args,
             guild_id: req.message.guild_id,
         }
         .uber(&ctx)?;
 
-        ctx.http
-            .create_message(req.message.channel_id)
-            .reply(req.message.id)
-            .content(&help_msg)?
-            .await?;
+        // Only the first page is a reply, the rest follow it.
+        for (i, page) in pages.iter().enumerate() {
+            let msg = ctx.http.create_message(req.message.channel_id);
+            let msg = if i == 0 {
+                msg.reply(req.message.id)
+            } else {
+                msg
+            };
+            msg.content(page)?.await?;
+        }
 
         Ok(Response::none())
     }
 
     async fn slash(ctx: Context, req: SlashRequest) -> CommandResponse {
-        let help_msg = Self {
+        // Commands with subcommands or groups get buttons to browse them.
+        if let Ok(value) = req.args.string("command") {
+            let path: Vec<_> = value.split_whitespace().collect();
+            if let Some(resolved) = ctx.commands.resolve(&path) {
+                riveting_bot::help::update_response(
+                    &ctx,
+                    &req.interaction.token,
+                    resolved.path(),
+                    req.interaction.author_id(),
+                )
+                .await?;
+
+                return Ok(Response::none());
+            }
+        }
+
+        let mut pages = Self {
             args: req.args,
             guild_id: req.interaction.guild_id,
         }
         .uber(&ctx)?;
 
-        ctx.interaction()
-            .create_followup(&req.interaction.token)
-            .content(&help_msg)?
+        if pages.len() == 1 {
+            ctx.interaction()
+                .update_response(&req.interaction.token)
+                .content(pages.pop().as_deref())?
+                .await?;
+        } else {
+            let pages = pages
+                .into_iter()
+                .map(|page| EmbedBuilder::new().description(page).build())
+                .collect();
+            paginator::update_response(
+                &ctx,
+                &req.interaction.token,
+                pages,
+                req.interaction.author_id(),
+            )
             .await?;
+        }
 
         Ok(Response::none())
     }
@@ -23,6 +23,7 @@ impl Voice {
         command("voice", "Manage voice connection.")
             .attach(Self::classic)
             .attach(Self::slash)
+            .intents(Intents::GUILD_VOICE_STATES)
             .option(
                 sub("join", "Join the bot to a voice channel.")
                     .attach(Join::classic)
@@ -72,7 +73,7 @@ impl Join {
     ) -> AnyResult<Arc<Mutex<Call>>> {
         // If no arg was given, try to find user in voice channels, otherwise use channel id from the request itself.
         let channel_id = match args.channel("channel") {
-            Ok(c) => c.id(),
+            Ok(c) => ctx.guild_channel_from(guild_id, c.id()).await?.id,
             Err(e) => {
                 debug!("{e}; Using fallback");
                 match ctx.user_voice_channel(guild_id, user_id).await {
@@ -97,18 +98,21 @@ impl Join {
         } {
             let ctx = ctx.to_owned();
             tokio::spawn(async move {
-                ctx.standby
-                    .wait_for(guild_id, move |event: &Event| {
-                        match event {
-                            Event::GatewayClose(_) => true,
-                            Event::VoiceStateUpdate(vsu) => {
-                                // If the update is a disconnect and for the user who called join.
-                                vsu.channel_id.is_none() && vsu.user_id == user_id
-                            },
-                            _ => false,
-                        }
-                    })
-                    .await?;
+                let disconnect = ctx.standby.wait_for(guild_id, move |event: &Event| {
+                    match event {
+                        Event::GatewayClose(_) => true,
+                        Event::VoiceStateUpdate(vsu) => {
+                            // If the update is a disconnect and for the user who called join.
+                            vsu.channel_id.is_none() && vsu.user_id == user_id
+                        },
+                        _ => false,
+                    }
+                });
+
+                // Also leave when shutting down, this would never finish after a restart.
+                if let Some(disconnect) = ctx.until_shutdown(disconnect).await {
+                    disconnect?;
+                }
 
                 debug!("Autodisconnecting from voice");
                 ctx.voice
@@ -169,11 +173,11 @@ impl Join {
             Ok(c) => {
                 if let Some(channel_id) = c.lock().await.current_channel() {
                     ctx.interaction()
-                        .create_followup(&req.interaction.token)
-                        .content(&format!(
+                        .update_response(&req.interaction.token)
+                        .content(Some(&format!(
                             "Joined channel {}",
                             ctx.channel_from(channel_id.0.into()).await?.mention()
-                        ))?
+                        )))?
                         .send()
                         .await?;
                 }
@@ -329,8 +333,8 @@ impl Play {
         {
             Ok(Some(content)) => {
                 ctx.interaction()
-                    .create_followup(&req.interaction.token)
-                    .content(&content)?
+                    .update_response(&req.interaction.token)
+                    .content(Some(&content))?
                     .await?;
                 Ok(Response::none())
             },
@@ -389,8 +393,8 @@ impl Skip {
         {
             Ok(Some(content)) => {
                 ctx.interaction()
-                    .create_followup(&req.interaction.token)
-                    .content(&content)?
+                    .update_response(&req.interaction.token)
+                    .content(Some(&content))?
                     .await?;
                 Ok(Response::none())
             },
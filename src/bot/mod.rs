@@ -40,7 +40,7 @@ use std::sync::Arc;
 use riveting_bot::commands::{Commands, CommandsBuilder};
 use riveting_bot::config::BotConfig;
 use riveting_bot::utils::prelude::*;
-use riveting_bot::BotEventSender;
+use riveting_bot::{utils, BotEventSender};
 use twilight_standby::Standby;
 
 /// Generic commands.
@@ -59,7 +59,8 @@ pub mod admin;
 pub mod owner;
 
 /// Create the list of bot commands.
-pub fn create_commands() -> AnyResult<Commands> {
+pub fn create_commands(config: &BotConfig) -> AnyResult<Commands> {
+    let settings = config.global().bot_settings()?.to_owned();
     let mut commands = CommandsBuilder::new();
 
     // Basic functionality.
@@ -68,31 +69,81 @@ pub fn create_commands() -> AnyResult<Commands> {
         .bind(meta::essential::About::command())
         .bind(meta::essential::Help::command());
 
+    // Commands that can be disabled in the settings.
+    #[allow(unused_mut)] // Without optional command features.
+    let mut optional = CommandsBuilder::new();
+
     #[cfg(feature = "voice")]
-    commands.bind(meta::voice::Voice::command());
+    optional.bind(meta::voice::Voice::command());
 
     // Extra utility.
     #[cfg(feature = "bulk-delete")]
-    commands.bind(meta::bulk::BulkDelete::command());
+    optional.bind(meta::bulk::BulkDelete::command());
 
     #[cfg(feature = "user")]
-    commands
+    optional
         .bind(user::fuel::Fuel::command())
         .bind(user::time::Time::command())
         .bind(user::joke::Joke::command())
         .bind(user::coinflip::Coinflip::command())
-        .bind(user::user_info::UserInfo::command());
+        .bind(user::user_info::UserInfo::command())
+        .bind(user::translate::Translate::command())
+        .bind(user::snipe::Snipe::command())
+        .bind(user::quote::Quote::command())
+        .bind(user::votes::Votes::command())
+        .bind(user::remind::Remind::command())
+        .bind(user::leaderboard::Leaderboard::command());
 
     // Moderation functionality.
     #[cfg(feature = "admin")]
-    commands
+    optional
         .bind(admin::bot::Bot::command())
         .bind(admin::roles::Roles::command())
-        .bind(admin::silence::Mute::command());
+        .bind(admin::silence::Mute::command())
+        .bind(admin::silence::Untimeout::command())
+        .bind(admin::slowmode::Slowmode::command())
+        .bind(admin::warn::Warn::command())
+        .bind(admin::warn::Warnings::command())
+        .bind(admin::warn::DelWarn::command())
+        .bind(admin::perms::Perms::command())
+        .bind(admin::channels::Channels::command())
+        .bind(admin::nick::SetNick::command())
+        .bind(admin::forget::Forget::command())
+        .bind(admin::emoji::StealEmoji::command())
+        .bind(admin::config::Config::command())
+        .bind(admin::setup::Setup::command())
+        .bind(admin::embed::PostEmbed::command());
+
+    // Likely a typo, or a command of a feature that is not compiled in.
+    if let Some(enabled) = &settings.enabled_commands {
+        let mut unknown: Vec<_> = enabled
+            .iter()
+            .filter(|name| !optional.list.iter().any(|c| c.command.name == *name))
+            .collect();
+        if !unknown.is_empty() {
+            unknown.sort_unstable();
+            warn!(
+                "Enabled commands that are not optional commands: {}",
+                utils::nice_list(&unknown)
+            );
+        }
+    }
+
+    for cmd in optional.list {
+        let enabled = settings.is_command_enabled(cmd.command.name);
+        commands.bind_if(enabled, cmd);
+    }
 
     // Bot owner functionality.
     #[cfg(feature = "owner")]
-    commands.bind(owner::Shutdown::command());
+    commands
+        .bind(owner::Shutdown::command())
+        .bind(owner::Restart::command())
+        .bind(owner::Maintenance::command())
+        .bind(owner::Reload::command())
+        .bind(owner::Export::command())
+        .bind(owner::Whitelist::command())
+        .bind(owner::Logs::command());
 
     add_commands_to_help(&mut commands);
 
@@ -1,6 +1,30 @@
+use std::time::Duration;
+use std::{env, fs};
+
 use riveting_bot::commands::prelude::*;
+use riveting_bot::commands::{checks, hooks};
 use riveting_bot::utils::prelude::*;
-use riveting_bot::BotEvent;
+use riveting_bot::{utils, BotEvent, ShutdownReason};
+use twilight_model::id::marker::GuildMarker;
+use twilight_model::id::Id;
+
+/// Log file written by the bot.
+const LOG_FILE: &str = "./data/log.log";
+
+/// Lines of log shown by default.
+const DEFAULT_LOG_LINES: i64 = 50;
+
+/// Maximum lines of log shown.
+const MAX_LOG_LINES: i64 = 1000;
+
+/// Maximum bytes read from the end of the log file.
+const MAX_LOG_BYTES: u64 = 256 * 1024;
+
+/// Maximum length of a message content.
+const MAX_MESSAGE_LENGTH: usize = 2000;
+
+/// Time to keep confirmations before they are deleted.
+const CONFIRMATION_TTL: Duration = Duration::from_secs(30);
 
 /// Command: Disconnect and shut down the bot.
 pub struct Shutdown;
@@ -11,35 +35,426 @@ impl Shutdown {
 
         command("shutdown", "Shutdown the bot.")
             .attach(Self::classic)
+            .check(checks::owner_only())
+            .after(hooks::audit())
             .dm()
     }
 
     async fn classic(ctx: Context, req: ClassicRequest) -> CommandResponse {
-        // Owner check (not done by command handling).
         let sender_id = req.message.author.id;
-        let ok = if let Some(owner) = &ctx.application.owner {
-            owner.id == sender_id
-        } else if let Some(team) = &ctx.application.team {
-            team.members.iter().any(|m| m.user.id == sender_id)
+
+        ctx.http
+            .create_message(req.message.channel_id)
+            .reply(req.message.id)
+            .content("Shutting down...")?
+            .send()
+            .await?;
+
+        // Send a shutdown signal to the bot.
+        ctx.events_tx.send(BotEvent::Shutdown {
+            reason: ShutdownReason::Owner(sender_id),
+        })?;
+
+        Ok(Response::none())
+    }
+}
+
+/// Command: Disconnect and setup the bot again.
+pub struct Restart;
+
+impl Restart {
+    pub fn command() -> impl Into<BaseCommand> {
+        use riveting_bot::commands::builder::*;
+
+        command("restart", "Restart the bot.")
+            .attach(Self::classic)
+            .check(checks::owner_only())
+            .after(hooks::audit())
+            .dm()
+    }
+
+    async fn classic(ctx: Context, req: ClassicRequest) -> CommandResponse {
+        info!("Restarting by chat command");
+
+        ctx.http
+            .create_message(req.message.channel_id)
+            .reply(req.message.id)
+            .content("Restarting...")?
+            .send()
+            .await?;
+
+        // Send a restart signal to the bot.
+        ctx.events_tx.send(BotEvent::Restart)?;
+
+        Ok(Response::none())
+    }
+}
+
+/// Command: Toggle maintenance mode, where only the owners can use commands.
+pub struct Maintenance;
+
+impl Maintenance {
+    pub fn command() -> impl Into<BaseCommand> {
+        use riveting_bot::commands::builder::*;
+
+        command("maintenance", "Toggle maintenance mode.")
+            .attach(Self::classic)
+            .check(checks::owner_only())
+            .after(hooks::audit())
+            .auto_delete(CONFIRMATION_TTL)
+            .option(bool("enabled", "Set maintenance mode on or off."))
+            .dm()
+    }
+
+    async fn classic(ctx: Context, req: ClassicRequest) -> CommandResponse {
+        let enabled = req
+            .args
+            .bool("enabled")
+            .unwrap_or_else(|_| !ctx.in_maintenance());
+        ctx.set_maintenance(enabled);
+
+        info!(
+            "Maintenance mode {} by chat command",
+            if enabled { "enabled" } else { "disabled" }
+        );
+
+        let content = if enabled {
+            "Maintenance mode enabled, only the owners can use commands."
         } else {
-            false
+            "Maintenance mode disabled."
         };
 
-        if !ok {
-            return Ok(Response::none());
-        }
+        Ok(Response::reply(ctx, req, content.to_string()))
+    }
+}
 
-        info!("Shutting down by chat command");
+/// Command: Reload the settings from their files.
+pub struct Reload;
+
+impl Reload {
+    pub fn command() -> impl Into<BaseCommand> {
+        use riveting_bot::commands::builder::*;
+
+        command("reload", "Reload the bot and guild settings from files.")
+            .attach(Self::classic)
+            .check(checks::owner_only())
+            .after(hooks::audit())
+            .dm()
+    }
+
+    async fn classic(ctx: Context, req: ClassicRequest) -> CommandResponse {
+        let content = match ctx.config.reload() {
+            Ok(summary) => {
+                info!("Settings reloaded by chat command");
+                ctx.commands.clear_lookups();
+
+                let mut lines = vec![format!(
+                    "Settings reloaded, {} guild(s) loaded.",
+                    summary.guilds
+                )];
+                lines.extend(summary.prefixes.iter().map(|(guild_id, old, new)| {
+                    let target = match guild_id {
+                        Some(id) => format!("Guild `{id}`"),
+                        None => "Global".to_string(),
+                    };
+                    format!("{target} prefix changed from `{old}` to `{new}`.")
+                }));
+                lines.join("\n")
+            },
+            Err(e) => {
+                error!("Failed to reload settings: {e:?}");
+                format!("Reload failed, keeping the current settings: {e:#}")
+            },
+        };
 
         ctx.http
             .create_message(req.message.channel_id)
             .reply(req.message.id)
-            .content("Shutting down...")?
-            .send()
+            .content(&utils::truncate(&content, MAX_MESSAGE_LENGTH))?
             .await?;
 
-        // Send a shutdown signal to the bot.
-        ctx.events_tx.send(BotEvent::Shutdown)?;
+        Ok(Response::none())
+    }
+}
+
+/// Command: Export bot data as files.
+pub struct Export;
+
+impl Export {
+    pub fn command() -> impl Into<BaseCommand> {
+        use riveting_bot::commands::builder::*;
+
+        command("export", "Export bot data as files.")
+            .attach(Self::classic)
+            .check(checks::owner_only())
+            .after(hooks::audit())
+            .option(
+                sub("commands", "Export the command schema as JSON.")
+                    .attach(ExportCommands::classic),
+            )
+            .dm()
+    }
+
+    async fn classic(_ctx: Context, _req: ClassicRequest) -> CommandResponse {
+        Err(CommandError::MissingArgs)
+    }
+}
+
+/// Command: Export the command schema as JSON.
+struct ExportCommands;
+
+impl ExportCommands {
+    async fn classic(ctx: Context, req: ClassicRequest) -> CommandResponse {
+        let schema = ctx.commands.to_json_schema()?;
+
+        Ok(Response::file(
+            ctx,
+            req,
+            "commands.json".to_string(),
+            schema.into_bytes(),
+        ))
+    }
+}
+
+/// Command: Manage the guild whitelist.
+pub struct Whitelist;
+
+impl Whitelist {
+    pub fn command() -> impl Into<BaseCommand> {
+        use riveting_bot::commands::builder::*;
+
+        command("whitelist", "Manage the guild whitelist.")
+            .attach(Self::classic)
+            .check(checks::owner_only())
+            .after(hooks::audit())
+            .option(
+                sub("add", "Add a guild to the whitelist.")
+                    .attach(WhitelistAdd::classic)
+                    .option(string("guild", "Guild id.").required()),
+            )
+            .option(
+                sub("remove", "Remove a guild from the whitelist and leave it.")
+                    .attach(WhitelistRemove::classic)
+                    .option(string("guild", "Guild id.").required()),
+            )
+            .option(sub("list", "List whitelisted guilds.").attach(WhitelistList::classic))
+            .option(
+                sub(
+                    "pause",
+                    "Stop or start leaving guilds that are not whitelisted.",
+                )
+                .attach(WhitelistPause::classic)
+                .option(bool("paused", "Pause the whitelist on or off.")),
+            )
+            .dm()
+    }
+
+    async fn classic(_ctx: Context, _req: ClassicRequest) -> CommandResponse {
+        Err(CommandError::MissingArgs)
+    }
+}
+
+/// Command: Add a guild to the whitelist.
+struct WhitelistAdd;
+
+impl WhitelistAdd {
+    async fn classic(ctx: Context, req: ClassicRequest) -> CommandResponse {
+        let guild_id = parse_guild_id(&req.args)?;
+
+        // Adding the first guild creates the whitelist, which starts the enforcement.
+        let (added, created) = ctx.config.global_settings_with(|s| {
+            let created = s.whitelist.is_none();
+            Ok((
+                s.whitelist.get_or_insert_default().insert(guild_id),
+                created,
+            ))
+        })?;
+
+        info!("Guild '{guild_id}' added to the whitelist");
+
+        let content = match (added, created) {
+            (_, true) => format!(
+                "Whitelist created with `{guild_id}`, other guilds will be left on their next \
+                 connect."
+            ),
+            (true, false) => format!("Guild `{guild_id}` added to the whitelist."),
+            (false, false) => format!("Guild `{guild_id}` is already whitelisted."),
+        };
+
+        ctx.http
+            .create_message(req.message.channel_id)
+            .reply(req.message.id)
+            .content(&content)?
+            .await?;
+
+        Ok(Response::none())
+    }
+}
+
+/// Command: Remove a guild from the whitelist.
+struct WhitelistRemove;
+
+impl WhitelistRemove {
+    async fn classic(ctx: Context, req: ClassicRequest) -> CommandResponse {
+        let guild_id = parse_guild_id(&req.args)?;
+
+        let removed = ctx.config.global_settings_with(|s| {
+            Ok(s.whitelist.as_mut().is_some_and(|w| w.remove(&guild_id)))
+        })?;
+
+        let content = if !removed {
+            format!("Guild `{guild_id}` is not whitelisted.")
+        } else if ctx.cache.guild(guild_id).is_some() && !ctx.config.global().whitelist_paused()? {
+            info!("Guild '{guild_id}' removed from the whitelist, leaving it");
+            ctx.http.leave_guild(guild_id).await?;
+            format!("Guild `{guild_id}` removed from the whitelist and left.")
+        } else {
+            info!("Guild '{guild_id}' removed from the whitelist");
+            format!("Guild `{guild_id}` removed from the whitelist.")
+        };
+
+        ctx.http
+            .create_message(req.message.channel_id)
+            .reply(req.message.id)
+            .content(&content)?
+            .await?;
+
+        Ok(Response::none())
+    }
+}
+
+/// Command: List whitelisted guilds.
+struct WhitelistList;
+
+impl WhitelistList {
+    async fn classic(ctx: Context, req: ClassicRequest) -> CommandResponse {
+        let whitelist = ctx.config.global().whitelist()?.to_owned();
+
+        let paused = ctx.config.global().whitelist_paused()?;
+
+        let content = match whitelist {
+            None => "Whitelist is not in use.".to_string(),
+            Some(list) if list.is_empty() => "Whitelist is empty.".to_string(),
+            Some(list) => {
+                let mut lines: Vec<_> = list
+                    .into_iter()
+                    .map(|id| match ctx.cache.guild(id) {
+                        Some(g) => format!("`{id}` {}", g.name()),
+                        None => format!("`{id}` (not joined)"),
+                    })
+                    .collect();
+                lines.sort_unstable();
+                format!(
+                    "Whitelisted guilds{}:\n{}",
+                    if paused { " (paused)" } else { "" },
+                    lines.join("\n")
+                )
+            },
+        };
+
+        ctx.http
+            .create_message(req.message.channel_id)
+            .reply(req.message.id)
+            .content(&utils::truncate(&content, MAX_MESSAGE_LENGTH))?
+            .await?;
+
+        Ok(Response::none())
+    }
+}
+
+/// Command: Pause or resume leaving non-whitelisted guilds.
+struct WhitelistPause;
+
+impl WhitelistPause {
+    async fn classic(ctx: Context, req: ClassicRequest) -> CommandResponse {
+        let arg = req.args.bool("paused").ok();
+        let paused = ctx.config.global_settings_with(|s| {
+            s.whitelist_paused = arg.unwrap_or(!s.whitelist_paused);
+            Ok(s.whitelist_paused)
+        })?;
+
+        info!(
+            "Whitelist {} by chat command",
+            if paused { "paused" } else { "resumed" }
+        );
+
+        let content = if paused {
+            "Whitelist paused, non-whitelisted guilds are not left."
+        } else {
+            "Whitelist resumed, non-whitelisted guilds are left on their next connect."
+        };
+
+        ctx.http
+            .create_message(req.message.channel_id)
+            .reply(req.message.id)
+            .content(content)?
+            .await?;
+
+        Ok(Response::none())
+    }
+}
+
+/// Parse the `guild` argument as a guild id.
+fn parse_guild_id(args: &Args) -> CommandResult<Id<GuildMarker>> {
+    let text = args.string("guild")?;
+    text.trim()
+        .parse()
+        .map_err(|_| CommandError::UnexpectedArgs(format!("Invalid guild id: '{text}'")))
+}
+
+/// Command: Show the latest lines of the bot log.
+pub struct Logs;
+
+impl Logs {
+    pub fn command() -> impl Into<BaseCommand> {
+        use riveting_bot::commands::builder::*;
+
+        command("logs", "Show recent bot logs, in direct messages only.")
+            .attach(Self::classic)
+            .check(checks::owner_only())
+            .after(hooks::audit())
+            .check(checks::dm_only())
+            .option(
+                integer("lines", "Number of lines to show.")
+                    .min(1)
+                    .max(MAX_LOG_LINES),
+            )
+            .dm()
+    }
+
+    async fn classic(ctx: Context, req: ClassicRequest) -> CommandResponse {
+        let lines = req
+            .args
+            .integer("lines")
+            .unwrap_or(DEFAULT_LOG_LINES)
+            .clamp(1, MAX_LOG_LINES) as usize;
+
+        let file = fs::File::open(LOG_FILE).context("Failed to open log file")?;
+        let mut text =
+            utils::tail_lines(file, lines, MAX_LOG_BYTES).context("Failed to read log file")?;
+
+        // Never leak the token, even if something logged it.
+        if let Ok(token) = env::var("DISCORD_TOKEN") {
+            if !token.is_empty() {
+                text = text.replace(&token, "[REDACTED]");
+            }
+        }
+
+        let content = format!("```\n{}\n```", utils::escape_code_block(&text));
+        if content.chars().count() > MAX_MESSAGE_LENGTH {
+            return Ok(Response::file(
+                ctx,
+                req,
+                "log.txt".to_string(),
+                text.into_bytes(),
+            ));
+        }
+
+        ctx.http
+            .create_message(req.message.channel_id)
+            .reply(req.message.id)
+            .content(&content)?
+            .await?;
 
         Ok(Response::none())
     }
@@ -8,16 +8,32 @@ impl Coinflip {
     pub fn command() -> impl Into<BaseCommand> {
         use riveting_bot::commands::builder::*;
 
-        command("coinflip", "Flip a coin.").attach(Self::slash).dm()
+        command("coinflip", "Flip a coin.")
+            .attach(Self::classic)
+            .attach(Self::slash)
+            .dm()
     }
 
-    async fn slash(ctx: Context, req: SlashRequest) -> CommandResponse {
-        let flip = random::<bool>();
-        let flip = if flip { ":coin: Heads" } else { "Tails :coin:" };
+    fn uber() -> &'static str {
+        if random::<bool>() {
+            ":coin: Heads"
+        } else {
+            "Tails :coin:"
+        }
+    }
+
+    async fn classic(ctx: Context, req: ClassicRequest) -> CommandResponse {
+        ctx.http
+            .create_message(req.message.channel_id)
+            .reply(req.message.id)
+            .content(Self::uber())?
+            .await?;
 
-        ctx.interaction()
-            .create_followup(&req.interaction.token)
-            .content(flip)?
+        Ok(Response::none())
+    }
+
+    async fn slash(ctx: Context, req: SlashRequest) -> CommandResponse {
+        ctx.update_response(&req.interaction.token, Self::uber())
             .await?;
 
         Ok(Response::none())
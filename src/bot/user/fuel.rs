@@ -1,6 +1,7 @@
 use chrono::NaiveTime;
 use riveting_bot::commands::prelude::*;
 use riveting_bot::utils::prelude::*;
+use twilight_model::channel::message::Embed;
 use twilight_util::builder::embed::{EmbedBuilder, EmbedFieldBuilder, EmbedFooterBuilder};
 
 /// Command: Calculate fuel required.
@@ -11,6 +12,7 @@ impl Fuel {
         use riveting_bot::commands::builder::*;
 
         command("fuel", "Calculate race fuel required.")
+            .attach(Self::classic)
             .attach(Self::slash)
             .option(
                 integer("stint-minutes", "Length of the race or stint in minutes.")
@@ -41,11 +43,11 @@ impl Fuel {
             .dm()
     }
 
-    async fn slash(ctx: Context, req: SlashRequest) -> CommandResponse {
-        let stint = req.args.integer("stint-minutes")?;
-        let minutes = req.args.integer("lap-minutes")? as u32;
-        let seconds = req.args.number("lap-seconds")?;
-        let consumption = req.args.number("consumption")?;
+    fn uber(args: &Args) -> CommandResult<Embed> {
+        let stint = args.integer("stint-minutes")?;
+        let minutes = args.integer("lap-minutes")? as u32;
+        let seconds = args.number("lap-seconds")?;
+        let consumption = args.number("consumption")?;
 
         let length_in_seconds = (stint * 60) as f64;
         let laptime_in_seconds = (minutes * 60) as f64 + seconds;
@@ -90,7 +92,7 @@ impl Fuel {
             }
         }
 
-        let embed = EmbedBuilder::new()
+        Ok(EmbedBuilder::new()
             .title("⛽ Fuel kalkulus")
             .field(EmbedFieldBuilder::new("Minimum", fuel_needed.ceil().to_string()).inline())
             .field(
@@ -116,7 +118,23 @@ impl Fuel {
                     .format("%M:%S%.3f"),
             )))
             .color(0xDB3DBE)
-            .build();
+            .build())
+    }
+
+    async fn classic(ctx: Context, req: ClassicRequest) -> CommandResponse {
+        let embed = Self::uber(&req.args)?;
+
+        ctx.http
+            .create_message(req.message.channel_id)
+            .reply(req.message.id)
+            .embeds(&[embed])?
+            .await?;
+
+        Ok(Response::none())
+    }
+
+    async fn slash(ctx: Context, req: SlashRequest) -> CommandResponse {
+        let embed = Self::uber(&req.args)?;
 
         ctx.interaction()
             .update_response(&req.interaction.token)
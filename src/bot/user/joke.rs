@@ -1,4 +1,20 @@
 use riveting_bot::commands::prelude::*;
+use riveting_bot::config::JokeSettings;
+use riveting_bot::utils::prelude::*;
+
+/// Joke categories supported by the API.
+const CATEGORIES: [(&str, &str); 7] = [
+    ("Any", "Any"),
+    ("Misc", "Misc"),
+    ("Programming", "Programming"),
+    ("Dark", "Dark"),
+    ("Pun", "Pun"),
+    ("Spooky", "Spooky"),
+    ("Christmas", "Christmas"),
+];
+
+/// Message to send when the API is not available.
+const FALLBACK: &str = "The joke machine is broken right now, try again later 🔧";
 
 /// Command: Send a dad joke.
 pub struct Joke;
@@ -16,25 +32,75 @@ impl Joke {
     pub fn command() -> impl Into<BaseCommand> {
         use riveting_bot::commands::builder::*;
 
-        command("joke", "Send a bad joke.").attach(Self::slash).dm()
+        command("joke", "Send a bad joke.")
+            .attach(Self::classic)
+            .attach(Self::slash)
+            .option(string("category", "Category of the joke.").choices(CATEGORIES))
+            .dm()
+            .defer()
     }
 
-    async fn slash(ctx: Context, req: SlashRequest) -> CommandResponse {
-        let body = reqwest::get("https://v2.jokeapi.dev/joke/Any")
-            .await?
-            .json::<JokeResponse>()
-            .await?;
+    async fn uber(ctx: &Context, args: &Args) -> CommandResult<String> {
+        let settings = ctx.config.global().joke_settings()?.to_owned();
+
+        let category = args
+            .string("category")
+            .map(String::from)
+            .ok()
+            .or_else(|| settings.default_category.to_owned())
+            .unwrap_or_else(|| "Any".to_string());
+
+        match request_joke(&settings, &category).await {
+            Ok(JokeResponse::Single { joke }) => Ok(joke),
+            Ok(JokeResponse::TwoPart { setup, delivery }) => Ok(format!("> {setup}\n> {delivery}")),
+            Err(e) => {
+                warn!("Failed to get a joke: {}", e.oneliner());
+                Ok(FALLBACK.to_string())
+            },
+        }
+    }
 
-        let joke = match body {
-            JokeResponse::Single { joke } => joke,
-            JokeResponse::TwoPart { setup, delivery } => format!("> {setup}\n> {delivery}"),
-        };
+    async fn classic(ctx: Context, req: ClassicRequest) -> CommandResponse {
+        let joke = Self::uber(&ctx, &req.args).await?;
 
-        ctx.interaction()
-            .update_response(&req.interaction.token)
-            .content(Some(&joke))?
-            .await?;
+        req.reply(&ctx, &joke).await?;
 
         Ok(Response::none())
     }
+
+    async fn slash(ctx: Context, req: SlashRequest) -> CommandResponse {
+        let joke = Self::uber(&ctx, &req.args).await?;
+
+        ctx.update_response(&req.interaction.token, &joke).await?;
+
+        Ok(Response::none())
+    }
+}
+
+/// Request a joke from the configured API.
+async fn request_joke(settings: &JokeSettings, category: &str) -> AnyResult<JokeResponse> {
+    let url = format!("{}/{category}", settings.url.trim_end_matches('/'));
+    let mut req = reqwest::Client::new().get(&url);
+    if !settings.blacklist.is_empty() {
+        req = req.query(&[("blacklistFlags", settings.blacklist.join(","))]);
+    }
+
+    let body = req
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .with_context(|| format!("Joke API request failed: '{url}'"))?
+        .json::<serde_json::Value>()
+        .await
+        .context("Joke API response is not JSON")?;
+
+    // The API responds with an error object instead of a joke, e.g. for an unknown category.
+    if body.get("error").and_then(|e| e.as_bool()) == Some(true) {
+        anyhow::bail!(
+            "Joke API responded with an error: {}",
+            body.get("message").and_then(|m| m.as_str()).unwrap_or("-")
+        );
+    }
+
+    serde_json::from_value(body).context("Unexpected joke API response")
 }
@@ -0,0 +1,88 @@
+use std::fmt::Write;
+
+use riveting_bot::commands::prelude::*;
+use twilight_model::id::marker::GuildMarker;
+use twilight_model::id::Id;
+
+/// Number of entries shown by default.
+const DEFAULT_TOP: i64 = 10;
+
+/// Command: Guild leaderboards.
+pub struct Leaderboard;
+
+impl Leaderboard {
+    pub fn command() -> impl Into<BaseCommand> {
+        use riveting_bot::commands::builder::*;
+
+        command("leaderboard", "Show guild leaderboards.")
+            .attach(Self::classic)
+            .attach(Self::slash)
+            .option(
+                sub("commands", "Most used commands in this guild.")
+                    .attach(Commands::classic)
+                    .attach(Commands::slash)
+                    .option(integer("top", "Number of commands to show.").min(1).max(25)),
+            )
+            .example("/leaderboard commands top: 5")
+    }
+
+    async fn classic(_ctx: Context, _req: ClassicRequest) -> CommandResponse {
+        Err(CommandError::MissingArgs)
+    }
+
+    async fn slash(_ctx: Context, _req: SlashRequest) -> CommandResponse {
+        Err(CommandError::MissingArgs)
+    }
+}
+
+/// Command: Most used commands in the guild.
+struct Commands;
+
+impl Commands {
+    async fn uber(
+        ctx: &Context,
+        guild_id: Option<Id<GuildMarker>>,
+        args: &Args,
+    ) -> CommandResult<String> {
+        let Some(guild_id) = guild_id else {
+            return Err(CommandError::Disabled);
+        };
+
+        let top = args.integer("top").unwrap_or(DEFAULT_TOP).clamp(1, 25) as usize;
+        let usage = ctx.config.guild(guild_id).command_usage()?;
+
+        if usage.is_empty() {
+            return Ok("No commands have been used here yet.".to_string());
+        }
+
+        let mut content = "Most used commands:\n".to_string();
+        for (i, (name, count)) in usage.iter().take(top).enumerate() {
+            writeln!(content, "{}. `{name}`: {count}", i + 1)?;
+        }
+
+        Ok(content)
+    }
+
+    async fn classic(ctx: Context, req: ClassicRequest) -> CommandResponse {
+        let content = Self::uber(&ctx, req.message.guild_id, &req.args).await?;
+
+        ctx.http
+            .create_message(req.message.channel_id)
+            .reply(req.message.id)
+            .content(&content)?
+            .await?;
+
+        Ok(Response::none())
+    }
+
+    async fn slash(ctx: Context, req: SlashRequest) -> CommandResponse {
+        let content = Self::uber(&ctx, req.interaction.guild_id, &req.args).await?;
+
+        ctx.interaction()
+            .update_response(&req.interaction.token)
+            .content(Some(&content))?
+            .await?;
+
+        Ok(Response::none())
+    }
+}
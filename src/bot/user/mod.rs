@@ -1,5 +1,11 @@
 pub mod coinflip;
 pub mod fuel;
 pub mod joke;
+pub mod leaderboard;
+pub mod quote;
+pub mod remind;
+pub mod snipe;
 pub mod time;
+pub mod translate;
 pub mod user_info;
+pub mod votes;
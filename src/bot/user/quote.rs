@@ -0,0 +1,177 @@
+use riveting_bot::commands::handle;
+use riveting_bot::commands::prelude::*;
+use riveting_bot::utils;
+use riveting_bot::utils::prelude::*;
+use twilight_mention::Mention;
+use twilight_model::channel::message::Embed;
+use twilight_model::channel::Message;
+use twilight_model::guild::Permissions;
+use twilight_model::id::marker::{ChannelMarker, GuildMarker, RoleMarker, UserMarker};
+use twilight_model::id::Id;
+use twilight_util::builder::embed::{
+    EmbedAuthorBuilder, EmbedBuilder, EmbedFieldBuilder, ImageSource,
+};
+
+/// Longest description allowed in an embed.
+const MAX_DESCRIPTION: usize = 4096;
+
+/// Command: Show a linked message as an embed.
+pub struct Quote;
+
+impl Quote {
+    pub fn command() -> impl Into<BaseCommand> {
+        use riveting_bot::commands::builder::*;
+
+        command("quote", "Show a message by its link or id.")
+            .attach(Self::classic)
+            .attach(Self::slash)
+            .option(string("message", "Link or id of the message.").required())
+            .example("!quote https://discord.com/channels/1/2/3")
+            .dm()
+    }
+
+    async fn uber(
+        ctx: &Context,
+        args: &Args,
+        guild_id: Option<Id<GuildMarker>>,
+        channel_id: Id<ChannelMarker>,
+        user_id: Id<UserMarker>,
+        roles: &[Id<RoleMarker>],
+    ) -> CommandResult<Result<Embed, &'static str>> {
+        let text = args.string("message")?;
+        let msg = match fetch_linked(ctx, &text, guild_id, channel_id, user_id, roles).await? {
+            Ok(msg) => msg,
+            Err(reason) => return Ok(Err(reason)),
+        };
+        let target = msg.channel_id;
+
+        let mut author = EmbedAuthorBuilder::new(utils::display_name(&msg.author));
+        if let Some(avatar) = msg.author.avatar {
+            author = author.icon_url(ImageSource::url(format!(
+                "https://cdn.discordapp.com/avatars/{}/{avatar}.png",
+                msg.author.id
+            ))?);
+        }
+
+        let link = utils::message_link(msg.guild_id.or(guild_id), target, msg.id);
+        let mut embed = EmbedBuilder::new()
+            .author(author)
+            .description(utils::truncate(&msg.content, MAX_DESCRIPTION))
+            .field(EmbedFieldBuilder::new(
+                "Source",
+                format!("[Jump to message]({link}) in {}", target.mention()),
+            ))
+            .timestamp(msg.timestamp)
+            .color(0x5865F2);
+
+        let image = msg.attachments.iter().find(|a| {
+            a.content_type
+                .as_deref()
+                .is_some_and(|t| t.starts_with("image/"))
+        });
+        if let Some(image) = image {
+            embed = embed.image(ImageSource::url(&image.url)?);
+        }
+
+        Ok(Ok(embed.build()))
+    }
+
+    async fn classic(ctx: Context, req: ClassicRequest) -> CommandResponse {
+        let roles = req.message.member.as_ref().map_or(&[][..], |m| &m.roles);
+        let result = Self::uber(
+            &ctx,
+            &req.args,
+            req.message.guild_id,
+            req.message.channel_id,
+            req.message.author.id,
+            roles,
+        )
+        .await?;
+
+        let msg = ctx
+            .http
+            .create_message(req.message.channel_id)
+            .reply(req.message.id);
+
+        match result {
+            Ok(embed) => msg.embeds(&[embed])?.await?,
+            Err(content) => msg.content(content)?.await?,
+        };
+
+        Ok(Response::none())
+    }
+
+    async fn slash(ctx: Context, req: SlashRequest) -> CommandResponse {
+        let (Some(channel), Some(user_id)) = (
+            req.interaction.channel.as_ref(),
+            req.interaction.author_id(),
+        ) else {
+            return Err(CommandError::MissingArgs);
+        };
+        let roles = req
+            .interaction
+            .member
+            .as_ref()
+            .map_or(&[][..], |m| &m.roles);
+
+        let result = Self::uber(
+            &ctx,
+            &req.args,
+            req.interaction.guild_id,
+            channel.id,
+            user_id,
+            roles,
+        )
+        .await?;
+
+        let resp = ctx.interaction();
+        let resp = resp.update_response(&req.interaction.token);
+
+        match result {
+            Ok(embed) => resp.embeds(Some(&[embed]))?.await?,
+            Err(content) => resp.content(Some(content))?.await?,
+        };
+
+        Ok(Response::none())
+    }
+}
+
+/// Fetch a message by its link or id, if the user can read it.
+/// Messages are only fetched from the same guild, or the same channel outside of guilds.
+pub async fn fetch_linked(
+    ctx: &Context,
+    text: &str,
+    guild_id: Option<Id<GuildMarker>>,
+    channel_id: Id<ChannelMarker>,
+    user_id: Id<UserMarker>,
+    roles: &[Id<RoleMarker>],
+) -> CommandResult<Result<Message, &'static str>> {
+    let Some((linked, message_id)) = utils::parse_message_link(text) else {
+        return Ok(Err("Expected a message link or id."));
+    };
+    let target = linked.unwrap_or(channel_id);
+
+    // Only from the same server, where the user can read the channel.
+    match guild_id {
+        Some(guild_id) => {
+            if ctx.guild_channel_from(guild_id, target).await.is_err() {
+                return Ok(Err("That message is not in this server."));
+            }
+
+            let perms =
+                handle::member_channel_permissions(ctx, guild_id, target, user_id, roles).await?;
+            if !perms.contains(Permissions::VIEW_CHANNEL | Permissions::READ_MESSAGE_HISTORY) {
+                return Ok(Err("You cannot read that channel."));
+            }
+        },
+        None if linked.is_some_and(|c| c != channel_id) => {
+            return Ok(Err("Only messages of this conversation can be used here."));
+        },
+        None => (),
+    }
+
+    match ctx.http.message(target, message_id).send().await {
+        Ok(msg) => Ok(Ok(msg)),
+        Err(_) => Ok(Err("Could not find that message, or I cannot read it.")),
+    }
+}
@@ -0,0 +1,144 @@
+use chrono::Utc;
+use riveting_bot::commands::prelude::*;
+use riveting_bot::reminders::MAX_PER_USER;
+use riveting_bot::time::{self, EXAMPLES};
+use riveting_bot::utils;
+use riveting_bot::utils::prelude::*;
+use twilight_mention::timestamp::{Timestamp, TimestampStyle};
+use twilight_mention::Mention;
+use twilight_model::channel::message::AllowedMentions;
+use twilight_model::id::marker::{ChannelMarker, UserMarker};
+use twilight_model::id::Id;
+
+/// Longest reminder text, leaving room for the mention in front of it.
+const MAX_REMINDER_LENGTH: u16 = 1900;
+
+/// Command: Remind yourself about something later.
+pub struct Remind;
+
+impl Remind {
+    pub fn command() -> impl Into<BaseCommand> {
+        use riveting_bot::commands::builder::*;
+
+        command("remind", "Remind yourself about something later.")
+            .attach(Self::classic)
+            .attach(Self::slash)
+            .option(string("when", "When to remind, eg. `10m` or `tomorrow at 9am`.").required())
+            .option(
+                string("message", "What to remind about.")
+                    .max_length(MAX_REMINDER_LENGTH)
+                    .rest(),
+            )
+            .dm()
+            .example("!remind 10m stretch")
+            .example("!remind \"tomorrow at 9am\" standup")
+            .example("/remind when: next monday message: pay rent")
+            .help(format!(
+                "Times are in UTC. Examples: {EXAMPLES}, `friday at 17:30`, `2024-06-01 12:00`."
+            ))
+    }
+
+    async fn uber(
+        ctx: &Context,
+        args: &Args,
+        channel_id: Id<ChannelMarker>,
+        user_id: Id<UserMarker>,
+    ) -> CommandResult<String> {
+        let when = args.string("when")?;
+        let message = args.string("message").unwrap_or_default();
+
+        // Classic commands are not limited by discord, like the slash option is.
+        if message.chars().count() > usize::from(MAX_REMINDER_LENGTH) {
+            return Ok(format!(
+                "The reminder is too long, keep it to {MAX_REMINDER_LENGTH} characters at most."
+            ));
+        }
+
+        let time = match time::parse_time(&when, Utc::now()) {
+            Ok(time) => time,
+            Err(e) => return Ok(e.to_string()),
+        };
+
+        let Some(guard) = ctx.reminders.start(user_id) else {
+            return Ok(format!(
+                "You already have {MAX_PER_USER} reminders waiting, wait for one of them first."
+            ));
+        };
+
+        let wait = (time - Utc::now()).to_std().unwrap_or_default();
+        let http = ctx.http.clone();
+
+        // Waiting reminders are dropped when the bot shuts down.
+        ctx.delayed.spawn(wait, async move {
+            let _guard = guard;
+
+            let content = match message.trim() {
+                "" => format!("{} ⏰ Reminder!", user_id.mention()),
+                text => format!("{} ⏰ {text}", user_id.mention()),
+            };
+
+            // Only the reminded user is pinged, whatever the text mentions.
+            let mentions = AllowedMentions {
+                users: vec![user_id],
+                ..Default::default()
+            };
+
+            let sent: AnyResult<()> = async {
+                http.create_message(channel_id)
+                    .allowed_mentions(Some(&mentions))
+                    .content(&content)?
+                    .await?;
+                Ok(())
+            }
+            .await;
+
+            if let Err(e) = sent {
+                warn!("Failed to send reminder: {e}");
+            }
+        });
+
+        let stamp = Timestamp::new(time.timestamp() as _, Some(TimestampStyle::RelativeTime));
+        Ok(format!(
+            "I'll remind you {}, unless the bot restarts before then.",
+            stamp.mention()
+        ))
+    }
+
+    async fn classic(ctx: Context, req: ClassicRequest) -> CommandResponse {
+        let content = Self::uber(
+            &ctx,
+            &req.args,
+            req.message.channel_id,
+            req.message.author.id,
+        )
+        .await?;
+
+        ctx.http
+            .create_message(req.message.channel_id)
+            .reply(req.message.id)
+            .allowed_mentions(Some(&utils::default_allowed_mentions()))
+            .content(&content)?
+            .await?;
+
+        Ok(Response::none())
+    }
+
+    async fn slash(ctx: Context, req: SlashRequest) -> CommandResponse {
+        let Some(channel) = req.interaction.channel.as_ref() else {
+            return Err(CommandError::MissingArgs);
+        };
+        let user_id = req
+            .interaction
+            .author_id()
+            .ok_or(CommandError::MissingArgs)?;
+
+        let content = Self::uber(&ctx, &req.args, channel.id, user_id).await?;
+
+        ctx.interaction()
+            .update_response(&req.interaction.token)
+            .content(Some(&content))?
+            .await?;
+
+        Ok(Response::none())
+    }
+}
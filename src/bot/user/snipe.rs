@@ -0,0 +1,82 @@
+use riveting_bot::commands::prelude::*;
+use riveting_bot::utils;
+use twilight_mention::timestamp::{Timestamp, TimestampStyle};
+use twilight_mention::Mention;
+use twilight_model::channel::message::Embed;
+use twilight_model::id::marker::{ChannelMarker, GuildMarker};
+use twilight_model::id::Id;
+use twilight_util::builder::embed::{EmbedAuthorBuilder, EmbedBuilder, EmbedFieldBuilder};
+
+/// Command: Show the last deleted message of the channel.
+pub struct Snipe;
+
+impl Snipe {
+    pub fn command() -> impl Into<BaseCommand> {
+        use riveting_bot::commands::builder::*;
+
+        command("snipe", "Show the last deleted message of the channel.")
+            .attach(Self::classic)
+            .attach(Self::slash)
+    }
+
+    async fn uber(
+        ctx: &Context,
+        guild_id: Option<Id<GuildMarker>>,
+        channel_id: Id<ChannelMarker>,
+    ) -> CommandResult<Result<Embed, &'static str>> {
+        let Some(guild_id) = guild_id else {
+            return Err(CommandError::Disabled);
+        };
+
+        if !ctx.config.guild(guild_id).snipe_enabled()? {
+            return Ok(Err("Sniping is not enabled in this server."));
+        }
+
+        let Some(msg) = ctx.snipes.get(channel_id) else {
+            return Ok(Err("Nothing to snipe here 👀"));
+        };
+
+        let author = ctx.user_from(msg.author_id).await?;
+        let sent = Timestamp::new(msg.sent_at as _, Some(TimestampStyle::RelativeTime));
+
+        Ok(Ok(EmbedBuilder::new()
+            .author(EmbedAuthorBuilder::new(utils::display_name(&author)))
+            .description(msg.content)
+            .field(EmbedFieldBuilder::new("Sent", sent.mention().to_string()))
+            .color(0xCC4444)
+            .build()))
+    }
+
+    async fn classic(ctx: Context, req: ClassicRequest) -> CommandResponse {
+        let msg = ctx
+            .http
+            .create_message(req.message.channel_id)
+            .reply(req.message.id);
+
+        match Self::uber(&ctx, req.message.guild_id, req.message.channel_id).await? {
+            Ok(embed) => msg.embeds(&[embed])?.await?,
+            Err(content) => msg.content(content)?.await?,
+        };
+
+        Ok(Response::none())
+    }
+
+    async fn slash(ctx: Context, req: SlashRequest) -> CommandResponse {
+        let channel_id = req
+            .interaction
+            .channel
+            .as_ref()
+            .map(|c| c.id)
+            .ok_or(CommandError::MissingArgs)?;
+
+        let resp = ctx.interaction();
+        let resp = resp.update_response(&req.interaction.token);
+
+        match Self::uber(&ctx, req.interaction.guild_id, channel_id).await? {
+            Ok(embed) => resp.embeds(Some(&[embed]))?.await?,
+            Err(content) => resp.content(Some(content))?.await?,
+        };
+
+        Ok(Response::none())
+    }
+}
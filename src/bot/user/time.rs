@@ -55,6 +55,8 @@ impl Time {
                 .choices(TIMEZONES),
             )
             .dm()
+            .example("!time \"2024-06-01 12:00\" +2")
+            .example("/time expression: 18:00 timezone: UTC+2")
             .help(indoc::formatdoc! {"
                 Format examples: https://github.com/waltzofpearls/dateparser#accepted-date-formats
                     yyyy-mm-dd hh:mm:ss z   Mon dd, yyyy, hh:mm:ss      mm/dd/yyyy hh:mm:ss
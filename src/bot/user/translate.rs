@@ -0,0 +1,153 @@
+use std::env;
+use std::time::Duration;
+
+use riveting_bot::commands::checks;
+use riveting_bot::commands::prelude::*;
+use riveting_bot::config::TranslateSettings;
+use riveting_bot::utils::prelude::*;
+use serde::{Deserialize, Serialize};
+use twilight_model::channel::message::Embed;
+use twilight_util::builder::embed::{EmbedBuilder, EmbedFooterBuilder};
+
+/// How often a user can translate, to keep the API usage in check.
+const COOLDOWN: Duration = Duration::from_secs(10);
+
+/// How long translations are reused for the same text in the same channel.
+const CACHE_TTL: Duration = Duration::from_secs(10 * 60);
+
+/// Message to send when the translation API is not set up.
+const NOT_CONFIGURED: &str = "Translating is not configured for this bot 🤷";
+
+/// Command: Translate text with an external service.
+pub struct Translate;
+
+#[derive(Serialize)]
+struct TranslateRequest<'a> {
+    q: &'a str,
+    source: &'a str,
+    target: &'a str,
+    format: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    api_key: Option<&'a str>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TranslateResponse {
+    translated_text: String,
+    detected_language: Option<DetectedLanguage>,
+}
+
+#[derive(Deserialize)]
+struct DetectedLanguage {
+    language: String,
+}
+
+impl Translate {
+    pub fn command() -> impl Into<BaseCommand> {
+        use riveting_bot::commands::builder::*;
+
+        command("translate", "Translate text to another language.")
+            .attach(Self::classic)
+            .attach(Self::slash)
+            .example("!translate fi Good morning!")
+            .example("/translate language: en text: Hyvää huomenta!")
+            .option(
+                string("language", "Target language code, e.g. `en` or `fi`.")
+                    .required()
+                    .max_length(8),
+            )
+            .option(
+                string("text", "Text to translate.")
+                    .required()
+                    .max_length(2000)
+                    .rest(),
+            )
+            .check(checks::cooldown(COOLDOWN))
+            .cache_result(CACHE_TTL)
+            .dm()
+    }
+
+    async fn uber(ctx: &Context, args: &Args) -> CommandResult<Result<Embed, &'static str>> {
+        let Some(settings) = ctx.config.global().translate_settings()?.to_owned() else {
+            return Ok(Err(NOT_CONFIGURED));
+        };
+
+        let target = args.string("language")?.trim().to_lowercase();
+        let text = args.string("text")?;
+
+        let resp = match request_translation(&settings, &text, &target).await {
+            Ok(resp) => resp,
+            Err(e) => {
+                warn!("Failed to translate: {}", e.oneliner());
+                return Ok(Err("Could not translate that right now 😕"));
+            },
+        };
+
+        let source = resp
+            .detected_language
+            .map_or_else(|| "?".to_string(), |d| d.language);
+
+        Ok(Ok(EmbedBuilder::new()
+            .title("Translation")
+            .description(resp.translated_text)
+            .footer(EmbedFooterBuilder::new(format!("{source} → {target}")))
+            .color(0x4488DD)
+            .build()))
+    }
+
+    // Only the translations are cached, not the failures.
+    async fn classic(ctx: Context, req: ClassicRequest) -> CommandResponse {
+        match Self::uber(&ctx, &req.args).await? {
+            Ok(embed) => Ok(Response::embed(ctx, req, embed)),
+            Err(content) => {
+                req.reply(&ctx, content).await?;
+                Ok(Response::none())
+            },
+        }
+    }
+
+    async fn slash(ctx: Context, req: SlashRequest) -> CommandResponse {
+        match Self::uber(&ctx, &req.args).await? {
+            Ok(embed) => Ok(Response::embed(ctx, req, embed)),
+            Err(content) => {
+                ctx.interaction()
+                    .update_response(&req.interaction.token)
+                    .content(Some(content))?
+                    .await?;
+                Ok(Response::none())
+            },
+        }
+    }
+}
+
+/// Request a translation from the configured API.
+async fn request_translation(
+    settings: &TranslateSettings,
+    text: &str,
+    target: &str,
+) -> AnyResult<TranslateResponse> {
+    let api_key = settings
+        .api_key
+        .to_owned()
+        .or_else(|| env::var("TRANSLATE_API_KEY").ok());
+
+    let body = TranslateRequest {
+        q: text,
+        source: "auto",
+        target,
+        format: "text",
+        api_key: api_key.as_deref(),
+    };
+
+    reqwest::Client::new()
+        .post(&settings.url)
+        .json(&body)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .with_context(|| format!("Translation request failed: '{}'", settings.url))?
+        .json::<TranslateResponse>()
+        .await
+        .context("Unexpected translation response")
+}
@@ -1,8 +1,12 @@
 use std::fmt::Write;
 
 use riveting_bot::commands::prelude::*;
+use riveting_bot::utils;
 use riveting_bot::utils::prelude::*;
 use twilight_mention::Mention;
+use twilight_model::channel::message::Embed;
+use twilight_model::id::marker::{GuildMarker, UserMarker};
+use twilight_model::id::Id;
 use twilight_util::builder::embed::{self, EmbedFieldBuilder, ImageSource};
 
 // Useful: https://discord.com/developers/docs/reference#image-formatting-cdn-endpoints
@@ -15,23 +19,27 @@ impl UserInfo {
         use riveting_bot::commands::builder::*;
 
         command("userinfo", "Get information about a user.")
+            .attach(Self::classic)
             .attach(Self::slash)
             .option(user("user", "User to show information about."))
+            .option(bool("detailed", "Show more details, like permissions."))
             .dm()
     }
 
-    async fn slash(ctx: Context, req: SlashRequest) -> CommandResponse {
-        let Some(guild_id) = req.interaction.guild_id else {
+    async fn uber(
+        ctx: &Context,
+        args: &Args,
+        guild_id: Option<Id<GuildMarker>>,
+        author_id: Id<UserMarker>,
+    ) -> CommandResult<Embed> {
+        let Some(guild_id) = guild_id else {
             return Err(CommandError::Disabled);
         };
 
         // If no args provided, check own props
-        let user_id = match req.args.user("user") {
+        let user_id = match args.user("user") {
             Ok(user) => user.id(),
-            _ => req
-                .interaction
-                .author_id()
-                .ok_or(CommandError::MissingArgs)?,
+            _ => author_id,
         };
 
         let member = ctx.http.guild_member(guild_id, user_id).send().await?;
@@ -58,8 +66,22 @@ impl UserInfo {
             ))?);
         }
 
-        if let Some(nick) = member.nick {
-            embed = embed.field(EmbedFieldBuilder::new("AKA", nick).inline());
+        // Show the name used in this guild, if it differs from the title.
+        let title = utils::display_name(&user).into_owned();
+        let aka = utils::display_name_member(&member);
+        if aka != title {
+            embed = embed.field(EmbedFieldBuilder::new("AKA", aka).inline());
+        }
+
+        // Notable permissions, only on request.
+        if args.bool("detailed").unwrap_or(false) {
+            let perms = ctx
+                .member_permissions(guild_id, user_id, &member.roles)
+                .await?;
+            embed = embed.field(EmbedFieldBuilder::new(
+                "Permissions",
+                utils::notable_permissions(perms),
+            ));
         }
 
         let roles: String = member.roles.into_iter().fold(String::new(), |mut s, i| {
@@ -70,12 +92,35 @@ impl UserInfo {
         let roles = if roles.is_empty() { "-" } else { roles };
 
         let embed = embed
-            .title(user.name)
+            .title(title)
             .color(user.accent_color.unwrap_or(0))
             .image(ImageSource::url(image_url)?)
             .field(EmbedFieldBuilder::new("Roles", roles).inline())
             .build();
 
+        Ok(embed)
+    }
+
+    async fn classic(ctx: Context, req: ClassicRequest) -> CommandResponse {
+        let embed =
+            Self::uber(&ctx, &req.args, req.message.guild_id, req.message.author.id).await?;
+
+        ctx.http
+            .create_message(req.message.channel_id)
+            .reply(req.message.id)
+            .embeds(&[embed])?
+            .await?;
+
+        Ok(Response::none())
+    }
+
+    async fn slash(ctx: Context, req: SlashRequest) -> CommandResponse {
+        let author_id = req
+            .interaction
+            .author_id()
+            .ok_or(CommandError::MissingArgs)?;
+        let embed = Self::uber(&ctx, &req.args, req.interaction.guild_id, author_id).await?;
+
         ctx.interaction()
             .update_response(&req.interaction.token)
             .embeds(Some(&[embed]))?
@@ -0,0 +1,110 @@
+use std::fmt::Write;
+
+use riveting_bot::commands::prelude::*;
+use riveting_bot::utils;
+use twilight_model::channel::message::Reaction;
+use twilight_model::id::marker::{ChannelMarker, GuildMarker, RoleMarker, UserMarker};
+use twilight_model::id::Id;
+
+use super::quote;
+
+/// Command: Count the reactions of a message.
+pub struct Votes;
+
+impl Votes {
+    pub fn command() -> impl Into<BaseCommand> {
+        use riveting_bot::commands::builder::*;
+
+        command("votes", "Count the reactions of a message.")
+            .attach(Self::classic)
+            .attach(Self::slash)
+            .option(string("message", "Link or id of the message.").required())
+            .example("!votes https://discord.com/channels/1/2/3")
+            .dm()
+    }
+
+    async fn uber(
+        ctx: &Context,
+        args: &Args,
+        guild_id: Option<Id<GuildMarker>>,
+        channel_id: Id<ChannelMarker>,
+        user_id: Id<UserMarker>,
+        roles: &[Id<RoleMarker>],
+    ) -> CommandResult<String> {
+        let text = args.string("message")?;
+        let msg =
+            match quote::fetch_linked(ctx, &text, guild_id, channel_id, user_id, roles).await? {
+                Ok(msg) => msg,
+                Err(reason) => return Ok(reason.to_string()),
+            };
+
+        if msg.reactions.is_empty() {
+            return Ok("That message has no reactions.".to_string());
+        }
+
+        let link = utils::message_link(msg.guild_id.or(guild_id), msg.channel_id, msg.id);
+        Ok(format!("Votes of {link}\n{}", tally(msg.reactions)))
+    }
+
+    async fn classic(ctx: Context, req: ClassicRequest) -> CommandResponse {
+        let roles = req.message.member.as_ref().map_or(&[][..], |m| &m.roles);
+        let content = Self::uber(
+            &ctx,
+            &req.args,
+            req.message.guild_id,
+            req.message.channel_id,
+            req.message.author.id,
+            roles,
+        )
+        .await?;
+
+        ctx.http
+            .create_message(req.message.channel_id)
+            .reply(req.message.id)
+            .content(&content)?
+            .await?;
+
+        Ok(Response::none())
+    }
+
+    async fn slash(ctx: Context, req: SlashRequest) -> CommandResponse {
+        let (Some(channel), Some(user_id)) = (
+            req.interaction.channel.as_ref(),
+            req.interaction.author_id(),
+        ) else {
+            return Err(CommandError::MissingArgs);
+        };
+        let roles = req
+            .interaction
+            .member
+            .as_ref()
+            .map_or(&[][..], |m| &m.roles);
+
+        let content = Self::uber(
+            &ctx,
+            &req.args,
+            req.interaction.guild_id,
+            channel.id,
+            user_id,
+            roles,
+        )
+        .await?;
+
+        ctx.update_response(&req.interaction.token, &content)
+            .await?;
+
+        Ok(Response::none())
+    }
+}
+
+/// Reaction counts as lines, the most reacted first.
+fn tally(mut reactions: Vec<Reaction>) -> String {
+    reactions.sort_by_key(|r| std::cmp::Reverse(r.count));
+
+    reactions.iter().fold(String::new(), |mut text, r| {
+        // Deleted custom emojis have no name.
+        let emoji = utils::display_reaction_emoji(&r.emoji).unwrap_or_else(|id| format!("`{id}`"));
+        let _ = writeln!(text, "{emoji} **{}**", r.count);
+        text
+    })
+}
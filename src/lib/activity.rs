@@ -0,0 +1,78 @@
+//! Rotating bot activity.
+
+use std::time::Duration;
+
+use twilight_gateway::MessageSender;
+use twilight_model::gateway::payload::outgoing::UpdatePresence;
+use twilight_model::gateway::presence::{ActivityType, MinimalActivity, Status};
+
+use crate::config::ActivitySettings;
+use crate::utils::prelude::*;
+use crate::Context;
+
+/// Shortest allowed interval between activity changes, to stay clear of gateway rate-limits.
+pub const MIN_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Cycle through the configured activities on every shard, until the task is aborted.
+pub async fn rotate(ctx: Context, senders: Vec<MessageSender>, settings: ActivitySettings) {
+    if settings.list.is_empty() {
+        return;
+    }
+
+    let period = Duration::from_secs(settings.interval).max(MIN_INTERVAL);
+    // First change after one period, the initial presence is set when identifying.
+    let mut interval = tokio::time::interval_at(tokio::time::Instant::now() + period, period);
+
+    for template in settings.list.iter().cycle() {
+        interval.tick().await;
+
+        let name = format_activity(template, ctx.cache.stats().guilds());
+        let presence = match UpdatePresence::new(
+            vec![MinimalActivity {
+                kind: ActivityType::Playing,
+                name,
+                url: None,
+            }
+            .into()],
+            false,
+            None,
+            Status::Online,
+        ) {
+            Ok(presence) => presence,
+            Err(e) => {
+                warn!("Invalid activity '{template}': {e}");
+                continue;
+            },
+        };
+
+        for sender in senders.iter() {
+            if let Err(e) = sender.command(&presence) {
+                warn!("Failed to update presence: {e}");
+            }
+        }
+    }
+}
+
+/// Replace activity placeholders with their values.
+pub fn format_activity(template: &str, guilds: usize) -> String {
+    template
+        .replace("{guilds}", &guilds.to_string())
+        .replace("{version}", env!("CARGO_PKG_VERSION"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn activity_placeholders() {
+        assert_eq!(
+            format_activity("help | {guilds} guilds", 12),
+            "help | 12 guilds"
+        );
+        assert_eq!(
+            format_activity("v{version}", 0),
+            concat!("v", env!("CARGO_PKG_VERSION"))
+        );
+    }
+}
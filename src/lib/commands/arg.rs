@@ -1,12 +1,16 @@
 use std::borrow::Borrow;
-use std::sync::Arc;
+use std::fmt::{self, Display};
+use std::sync::{Arc, OnceLock};
 
 use derive_more::{From, IsVariant, Unwrap};
-use twilight_mention::ParseMention;
-use twilight_model::application::interaction::application_command::CommandOptionValue;
+use twilight_mention::{Mention, ParseMention};
+use twilight_model::application::interaction::application_command::{
+    CommandInteractionDataResolved, CommandOptionValue,
+};
+use twilight_model::id::marker::{GenericMarker, RoleMarker, UserMarker};
 use twilight_model::id::Id;
 
-use crate::commands::builder::{ArgDesc, ArgKind};
+use crate::commands::builder::{ArgDesc, ArgKind, DRY_RUN};
 use crate::commands::CommandError;
 use crate::utils::prelude::*;
 
@@ -14,12 +18,11 @@ pub mod types {
     use twilight_model::channel::{Attachment, Channel, Message};
     use twilight_model::guild::Role;
     use twilight_model::id::marker::{
-        AttachmentMarker, ChannelMarker, GenericMarker, MessageMarker, RoleMarker, UserMarker,
+        AttachmentMarker, ChannelMarker, MessageMarker, RoleMarker, UserMarker,
     };
-    use twilight_model::id::Id;
     use twilight_model::user::User;
 
-    use crate::commands::arg::Ref;
+    use crate::commands::arg::{Mentionable, Ref};
 
     pub type ArgBool = bool;
     pub type ArgNumber = f64;
@@ -30,7 +33,48 @@ pub mod types {
     pub type ArgAttachment = Ref<AttachmentMarker, Attachment>;
     pub type ArgUser = Ref<UserMarker, User>;
     pub type ArgRole = Ref<RoleMarker, Role>;
-    pub type ArgMention = Id<GenericMarker>;
+    pub type ArgMention = Mentionable;
+}
+
+/// A mentionable id, which is either a user or a role.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, IsVariant)]
+pub enum Mentionable {
+    User(Id<UserMarker>),
+    Role(Id<RoleMarker>),
+}
+
+impl Mentionable {
+    /// Resolve a slash command mentionable id by checking which one Discord resolved it as.
+    pub fn resolve(
+        id: Id<GenericMarker>,
+        resolved: Option<&CommandInteractionDataResolved>,
+    ) -> Option<Self> {
+        let resolved = resolved?;
+        if resolved.roles.contains_key(&id.cast()) {
+            Some(Self::Role(id.cast()))
+        } else if resolved.users.contains_key(&id.cast()) {
+            Some(Self::User(id.cast()))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the id without type information.
+    pub const fn id(&self) -> Id<GenericMarker> {
+        match self {
+            Self::User(id) => id.cast(),
+            Self::Role(id) => id.cast(),
+        }
+    }
+}
+
+impl Display for Mentionable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::User(id) => id.mention().fmt(f),
+            Self::Role(id) => id.mention().fmt(f),
+        }
+    }
 }
 
 /// Contained value that is either type `Ref::Id(Id<M>)` or `Ref::Obj(Arc<D>)`.
@@ -111,6 +155,11 @@ impl Args {
             .map(|a| &a.value)
     }
 
+    /// Returns `true` if the standard dry-run option was given as `true`.
+    pub fn is_dry_run(&self) -> bool {
+        self.bool(DRY_RUN).unwrap_or(false)
+    }
+
     /// Returns the inner box.
     pub fn into_inner(self) -> Box<[Arg]> {
         self.0
@@ -145,6 +194,66 @@ impl Arg {
     }
 }
 
+/// Names of a guild member, used to resolve plain user names in classic commands.
+#[derive(Debug, Clone)]
+pub struct MemberName {
+    pub id: Id<UserMarker>,
+    pub name: String,
+    pub discriminator: u16,
+    pub global_name: Option<String>,
+    pub nick: Option<String>,
+}
+
+impl MemberName {
+    /// Returns `true` if `text` is `name#discriminator` or any of the names, ignoring case.
+    fn matches(&self, text: &str) -> bool {
+        let text = text.to_lowercase();
+
+        if let Some((name, disc)) = text.rsplit_once('#') {
+            if let Ok(disc) = disc.parse::<u16>() {
+                return disc == self.discriminator && self.name.to_lowercase() == name;
+            }
+        }
+
+        [
+            Some(&self.name),
+            self.global_name.as_ref(),
+            self.nick.as_ref(),
+        ]
+        .into_iter()
+        .flatten()
+        .any(|n| n.to_lowercase() == text)
+    }
+}
+
+/// Names of the members of a guild, collected on first use.
+pub struct MemberNames<'a> {
+    collect: Box<dyn Fn() -> Vec<MemberName> + Send + Sync + 'a>,
+    names: OnceLock<Vec<MemberName>>,
+}
+
+impl<'a> MemberNames<'a> {
+    pub fn new(collect: impl Fn() -> Vec<MemberName> + Send + Sync + 'a) -> Self {
+        Self {
+            collect: Box::new(collect),
+            names: OnceLock::new(),
+        }
+    }
+
+    /// Get the names, collecting them if not done yet.
+    pub fn get(&self) -> &[MemberName] {
+        self.names.get_or_init(&self.collect)
+    }
+}
+
+impl fmt::Debug for MemberNames<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MemberNames")
+            .field("names", &self.names)
+            .finish_non_exhaustive()
+    }
+}
+
 /// Argument value type with data.
 #[derive(Debug, Clone, Unwrap, IsVariant)]
 pub enum ArgValue {
@@ -211,7 +320,7 @@ impl ArgValue {
             ArgKind::Message => {
                 Self::Message(Ref::Id(text.parse().context("Message arg parse error")?))
             },
-            ArgKind::Attachment => {
+            ArgKind::Attachment(_) => {
                 Self::Attachment(Ref::Id(text.parse().context("Attachment arg parse error")?))
             },
             ArgKind::User => {
@@ -220,15 +329,51 @@ impl ArgValue {
             ArgKind::Role => {
                 parse_mention_or_id(text, Self::Role).context("Role arg parse error")?
             },
-            ArgKind::Mention => Self::Mention(
-                text.parse().context("Mention arg parse error")?, // TODO: Parse from text (if other than id number).
-            ),
+            ArgKind::Mention => {
+                let text = text.trim();
+                let mention = match (Id::parse(text), Id::parse(text)) {
+                    (Ok(id), _) => Mentionable::User(id),
+                    (_, Ok(id)) => Mentionable::Role(id),
+                    _ => anyhow::bail!(
+                        "Mention arg parse error: '{text}' is not a user or role mention"
+                    ),
+                };
+                Self::Mention(mention)
+            },
         };
 
         Ok(val)
     }
 }
 
+impl ArgValue {
+    /// Create a value from value kind and text, like [`ArgValue::from_kind`].
+    /// In guild context, user args that are not a mention or id are resolved by `members` names.
+    pub fn from_kind_in(
+        kind: &ArgKind,
+        text: &str,
+        members: Option<&MemberNames>,
+    ) -> AnyResult<Self> {
+        let parsed = Self::from_kind(kind, text);
+
+        let (ArgKind::User, Some(members), Err(_)) = (kind, members, &parsed) else {
+            return parsed;
+        };
+
+        let text = text.trim();
+        let found: Vec<_> = members.get().iter().filter(|m| m.matches(text)).collect();
+
+        match found[..] {
+            [] => parsed,
+            [member] => Ok(Self::User(Ref::Id(member.id))),
+            _ => anyhow::bail!(
+                "User arg parse error: '{text}' matches {} members, use a mention or id instead",
+                found.len()
+            ),
+        }
+    }
+}
+
 impl TryFrom<CommandOptionValue> for ArgValue {
     type Error = &'static str;
 
@@ -239,11 +384,15 @@ impl TryFrom<CommandOptionValue> for ArgValue {
             CommandOptionValue::Integer(i) => Ok(Self::Integer(i)),
             CommandOptionValue::String(s) => Ok(Self::String(s.into_boxed_str())),
             CommandOptionValue::Channel(id) => Ok(Self::Channel(Ref::Id(id))),
-            CommandOptionValue::Mentionable(id) => Ok(Self::Mention(id)),
+            CommandOptionValue::Mentionable(_) => {
+                Err("Cannot convert mentionable without resolved data, use `Mentionable::resolve`")
+            },
             CommandOptionValue::Attachment(id) => Ok(Self::Attachment(Ref::Id(id))),
             CommandOptionValue::User(id) => Ok(Self::User(Ref::Id(id))),
             CommandOptionValue::Role(id) => Ok(Self::Role(Ref::Id(id))),
-            CommandOptionValue::Focused(_s, _c) => todo!(), // FIXME: To be implemented
+            CommandOptionValue::Focused(..) => {
+                Err("Cannot convert autocomplete value to argument value")
+            },
             CommandOptionValue::SubCommand(_) | CommandOptionValue::SubCommandGroup(_) => {
                 Err("Cannot convert subcommand or group to argument value")
             },
@@ -309,3 +458,62 @@ where
         self.as_ref().and_then(|v| v.borrow().mention())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+
+    #[test]
+    fn user_or_role_mention() {
+        let user = ArgValue::from_kind(&ArgKind::Mention, "<@123>").unwrap();
+        assert_eq!(user.mention(), Some(Mentionable::User(Id::new(123))));
+
+        let role = ArgValue::from_kind(&ArgKind::Mention, "<@&456>").unwrap();
+        assert_eq!(role.mention(), Some(Mentionable::Role(Id::new(456))));
+        assert_eq!(role.mention().unwrap().to_string(), "<@&456>");
+
+        // Plain ids are ambiguous.
+        assert!(ArgValue::from_kind(&ArgKind::Mention, "123").is_err());
+        assert!(ArgValue::from_kind(&ArgKind::Mention, "<#123>").is_err());
+    }
+
+    #[test]
+    fn user_by_name() {
+        let member = |id: u64, name: &str, nick: Option<&str>| MemberName {
+            id: Id::new(id),
+            name: name.to_string(),
+            discriminator: 0,
+            global_name: None,
+            nick: nick.map(str::to_string),
+        };
+        let collected = AtomicUsize::new(0);
+        let members = MemberNames::new(|| {
+            collected.fetch_add(1, Ordering::Relaxed);
+            vec![
+                member(1, "alice", Some("Ali")),
+                member(2, "bob", Some("Bobby")),
+                member(3, "bobby", None),
+            ]
+        });
+        let parse = |text| ArgValue::from_kind_in(&ArgKind::User, text, Some(&members));
+
+        // Not collected unless a name is needed.
+        assert_eq!(parse("<@2>").unwrap().user().unwrap().id(), Id::new(2));
+        assert_eq!(collected.load(Ordering::Relaxed), 0);
+
+        assert_eq!(parse("ali").unwrap().user().unwrap().id(), Id::new(1));
+        assert_eq!(parse("alice#0").unwrap().user().unwrap().id(), Id::new(1));
+        assert_eq!(collected.load(Ordering::Relaxed), 1);
+
+        // Nick of one and name of another.
+        assert!(parse("bobby")
+            .unwrap_err()
+            .to_string()
+            .contains("matches 2"));
+
+        assert!(parse("carol").is_err());
+        assert!(ArgValue::from_kind_in(&ArgKind::User, "ali", None).is_err());
+    }
+}
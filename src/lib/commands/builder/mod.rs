@@ -17,31 +17,38 @@
 //! fn string("name", "description") -> StringOptionBuilder
 //! fn channel("name", "description") -> ChannelOptionBuilder
 //! fn message("name", "description") -> ArgDesc
-//! fn attachment("name", "description") -> ArgDesc
+//! fn attachment("name", "description") -> AttachmentOptionBuilder
 //! fn user("name", "description") -> ArgDesc
 //! fn role("name", "description") -> ArgDesc
 //! fn mention("name", "description") -> ArgDesc
 //! ```
 //!
+//! Destructive commands can add a standard `dry_run` option with `with_dry_run()`.
+//!
 
 use std::collections::HashSet;
 use std::sync::Arc;
+use std::time::Duration;
 
 use derive_more::{Display, IsVariant, Unwrap};
 use thiserror::Error;
+use twilight_model::channel::Attachment;
 pub use twilight_model::channel::ChannelType;
+pub use twilight_model::gateway::Intents;
 pub use twilight_model::guild::Permissions;
 
 use crate::commands::builder::twilight::{
     CommandValidationError, MessageCommand, SlashCommand, TwilightCommand, UserCommand,
 };
+use crate::commands::checks::Check;
 use crate::commands::function::{
     ClassicFunction, Function, FunctionKind, IntoFunction, MessageFunction, SlashFunction,
     UserFunction,
 };
-use crate::commands::ResponseFuture;
+use crate::commands::hooks::After;
+use crate::commands::{CommandError, ResponseFuture};
 use crate::utils::prelude::*;
-use crate::Context;
+use crate::{utils, Context};
 
 pub mod twilight;
 
@@ -91,8 +98,8 @@ pub const fn message(name: &'static str, description: &'static str) -> ArgDesc {
 }
 
 /// Create a new argument with kind `Attachment`.
-pub const fn attachment(name: &'static str, description: &'static str) -> ArgDesc {
-    ArgDesc::new(name, description, ArgKind::Attachment)
+pub fn attachment(name: &'static str, description: &'static str) -> AttachmentOptionBuilder {
+    AttachmentOptionBuilder::new(name, description)
 }
 
 /// Create a new argument with kind `User`.
@@ -110,6 +117,14 @@ pub const fn mention(name: &'static str, description: &'static str) -> ArgDesc {
     ArgDesc::new(name, description, ArgKind::Mention)
 }
 
+/// Name of the standard dry-run option of destructive commands.
+pub const DRY_RUN: &str = "dry_run";
+
+/// Create the standard dry-run argument.
+const fn dry_run() -> ArgDesc {
+    bool(DRY_RUN, "Only report what would happen, without doing it.")
+}
+
 /// Helper macro to implement common methods for data builder.
 /// This assumes `data` type implements `Default`.
 macro_rules! impl_data_builder {
@@ -126,12 +141,19 @@ macro_rules! impl_data_builder {
             ))
         }
 
-        /// Set argument to be required. All required arguments must be before any optional ones.
+        /// Set argument to be required. All required arguments must be before any optional ones,
+        /// this is checked when the command is validated.
         $vis const fn required(mut self) -> Self {
             self.0.required = true;
             self
         }
 
+        /// Add an alternate name for classic named arguments, eg. `u:value` or `--u value`.
+        $vis fn alias(mut self, alias: &'static str) -> Self {
+            self.0.aliases.push(alias);
+            self
+        }
+
         /// Finalize the argument.
         $vis fn build(self) -> ArgDesc {
             self.0
@@ -228,6 +250,20 @@ impl StringOptionBuilder {
         self
     }
 
+    /// In classic commands, take the rest of the message as the value, quoted or not.
+    /// This should be the last argument of the command.
+    pub fn rest(mut self) -> Self {
+        self.inner_mut().rest = true;
+        self
+    }
+
+    /// Suggest the choices while typing, instead of listing them for slash commands.
+    /// This lifts the limit of 25 choices, but the value is not restricted to them.
+    pub fn autocomplete(mut self) -> Self {
+        self.inner_mut().autocomplete = true;
+        self
+    }
+
     /// Set string option choices as `(name, value)` pairs.
     pub fn choices<N, V>(mut self, choices: impl IntoIterator<Item = (N, V)>) -> Self
     where
@@ -260,6 +296,31 @@ impl ChannelOptionBuilder {
     }
 }
 
+#[derive(Debug, Clone)]
+pub struct AttachmentOptionBuilder(ArgDesc);
+
+impl AttachmentOptionBuilder {
+    impl_data_builder!(
+        /// Create new attachment option builder.
+        pub fn new(..) -> Self(Attachment(AttachmentData))
+    );
+
+    /// Maximum allowed file size in bytes.
+    pub fn max_size(mut self, bytes: u64) -> Self {
+        self.inner_mut().max_size = Some(bytes);
+        self
+    }
+
+    /// Set allowed content types, eg. `image/png` or `image/*` for any image.
+    pub fn content_types<T>(mut self, types: impl IntoIterator<Item = T>) -> Self
+    where
+        T: Into<String>,
+    {
+        self.inner_mut().content_types = types.into_iter().map(Into::into).collect();
+        self
+    }
+}
+
 #[derive(Debug, Default, Clone)]
 pub struct NumericalData<T> {
     pub min: Option<T>,
@@ -272,6 +333,8 @@ pub struct StringData {
     pub max_length: Option<u16>,
     pub min_length: Option<u16>,
     pub choices: Vec<(String, String)>,
+    pub rest: bool,
+    pub autocomplete: bool,
 }
 
 #[derive(Debug, Default, Clone)]
@@ -279,6 +342,52 @@ pub struct ChannelData {
     pub channel_types: Vec<ChannelType>,
 }
 
+#[derive(Debug, Default, Clone)]
+pub struct AttachmentData {
+    pub max_size: Option<u64>,
+    /// Allowed content types, any type is allowed if empty.
+    pub content_types: Vec<String>,
+}
+
+impl AttachmentData {
+    /// Check the attachment against the size and content type limits.
+    /// # Errors
+    /// Returns `CommandError::ArgsMismatch` if the attachment is too large or of a wrong type.
+    pub fn validate(&self, attachment: &Attachment) -> Result<(), CommandError> {
+        if self.max_size.is_some_and(|max| attachment.size > max) {
+            return Err(CommandError::ArgsMismatch);
+        }
+
+        if self.content_types.is_empty() {
+            return Ok(());
+        }
+
+        // Ignore parameters, eg. `text/plain; charset=utf-8`.
+        let Some(given) = attachment
+            .content_type
+            .as_deref()
+            .and_then(|t| t.split(';').next())
+            .map(str::trim)
+        else {
+            return Err(CommandError::ArgsMismatch);
+        };
+
+        let allowed = self
+            .content_types
+            .iter()
+            .any(|t| match t.strip_suffix("/*") {
+                Some(main) => given.split('/').next() == Some(main),
+                None => t.eq_ignore_ascii_case(given),
+            });
+
+        if allowed {
+            Ok(())
+        } else {
+            Err(CommandError::ArgsMismatch)
+        }
+    }
+}
+
 #[derive(Debug, Clone, Display)]
 pub enum ArgKind {
     #[display("bool")]
@@ -300,7 +409,7 @@ pub enum ArgKind {
     Message,
 
     #[display("attachment")]
-    Attachment, // TODO: Define if this should try to capture the object (eg. uploaded attachment or attachment in replied message)
+    Attachment(AttachmentData), // TODO: Define if this should try to capture the object (eg. uploaded attachment or attachment in replied message)
 
     #[display("user")]
     User, // TODO: Define if this should try to capture the object (eg. sender)
@@ -312,12 +421,40 @@ pub enum ArgKind {
     Mention,
 }
 
+impl ArgKind {
+    /// Choices of the argument as `(name, value)` pairs, if it has any.
+    pub fn choice_values(&self) -> Option<Vec<(&str, String)>> {
+        let choices: Vec<_> = match self {
+            Self::Number(data) => data
+                .choices
+                .iter()
+                .map(|(n, v)| (n.as_str(), v.to_string()))
+                .collect(),
+            Self::Integer(data) => data
+                .choices
+                .iter()
+                .map(|(n, v)| (n.as_str(), v.to_string()))
+                .collect(),
+            Self::String(data) => data
+                .choices
+                .iter()
+                .map(|(n, v)| (n.as_str(), v.to_owned()))
+                .collect(),
+            _ => return None,
+        };
+
+        (!choices.is_empty()).then_some(choices)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct ArgDesc {
     pub name: &'static str,
     pub description: &'static str,
     pub kind: ArgKind,
     pub required: bool,
+    /// Alternate names for classic named arguments, slash commands only use `name`.
+    pub aliases: Vec<&'static str>,
 }
 
 impl ArgDesc {
@@ -328,14 +465,27 @@ impl ArgDesc {
             description,
             kind,
             required: false,
+            aliases: Vec::new(),
         }
     }
 
-    /// Set argument to be required. All required arguments must be before any optional ones.
+    /// Returns `true` if `name` is the name or an alias of the argument.
+    pub fn is_named(&self, name: &str) -> bool {
+        self.name == name || self.aliases.contains(&name)
+    }
+
+    /// Set argument to be required. All required arguments must be before any optional ones,
+    /// this is checked when the command is validated.
     pub const fn required(mut self) -> Self {
         self.required = true;
         self
     }
+
+    /// Add an alternate name for classic named arguments, eg. `u:value` or `--u value`.
+    pub fn alias(mut self, alias: &'static str) -> Self {
+        self.aliases.push(alias);
+        self
+    }
 }
 
 /// This error type contains a collection of missing function errors found in a command.
@@ -358,6 +508,91 @@ impl std::fmt::Display for MissingFunctionsError {
     }
 }
 
+/// Checks names, descriptions, uniqueness and argument order of the options, recursively.
+fn check_options(
+    parent: &'static str,
+    options: &[CommandOption],
+) -> Result<(), CommandValidationError> {
+    let mut names = HashSet::with_capacity(options.len());
+    let mut optional = None;
+
+    for opt in options {
+        let (name, description) = match opt {
+            CommandOption::Arg(a) => (a.name, a.description),
+            CommandOption::Sub(s) => (s.name, s.description),
+            CommandOption::Group(g) => (g.name, g.description),
+        };
+
+        check_option_meta(name, description)?;
+
+        if !names.insert(name) {
+            return Err(CommandValidationError::AmbiguousName(format!(
+                "Duplicate name '{name}' in '{parent}'"
+            )));
+        }
+
+        if let CommandOption::Arg(a) = opt {
+            for &alias in a.aliases.iter() {
+                check_option_meta(alias, description)?;
+                if !names.insert(alias) {
+                    return Err(CommandValidationError::AmbiguousName(format!(
+                        "Alias '{alias}' of '{name}' collides with another name in '{parent}'"
+                    )));
+                }
+            }
+        }
+
+        match opt {
+            CommandOption::Arg(a) if a.required => {
+                if let Some(optional) = optional {
+                    return Err(CommandValidationError::RequiredAfterOptional {
+                        required: name,
+                        optional,
+                        parent,
+                    });
+                }
+            },
+            CommandOption::Arg(_) => optional = optional.or(Some(name)),
+            CommandOption::Sub(s) => check_options(s.name, &s.options)?,
+            CommandOption::Group(g) => {
+                let mut subs = HashSet::with_capacity(g.subs.len());
+                for s in g.subs.iter() {
+                    check_option_meta(s.name, s.description)?;
+                    if !subs.insert(s.name) {
+                        return Err(CommandValidationError::AmbiguousName(format!(
+                            "Duplicate name '{}' in '{}'",
+                            s.name, g.name
+                        )));
+                    }
+                    check_options(s.name, &s.options)?;
+                }
+            },
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks that a name and a description are acceptable for Discord.
+/// Names must be 1-32 lowercase letters, numbers, `-` or `_`, descriptions 1-100 characters.
+fn check_option_meta(name: &str, description: &str) -> Result<(), CommandValidationError> {
+    let valid_char = |c: char| (c == '-' || c == '_' || c.is_alphanumeric()) && !c.is_uppercase();
+
+    if !(1..=32).contains(&name.chars().count()) || !name.chars().all(valid_char) {
+        return Err(CommandValidationError::InvalidName(format!(
+            "'{name}' must be 1-32 lowercase letters, numbers, '-' or '_'"
+        )));
+    }
+
+    if !(1..=100).contains(&description.chars().count()) {
+        return Err(CommandValidationError::InvalidDescription(format!(
+            "Description of '{name}' must be 1-100 characters"
+        )));
+    }
+
+    Ok(())
+}
+
 /// Base command type, contains meta information with the command itself.
 #[derive(Debug, Clone)]
 pub struct BaseCommand {
@@ -365,6 +600,8 @@ pub struct BaseCommand {
     pub command: CommandFunction,
     /// Additional help for using the command. (not full usage help)
     pub help: String,
+    /// Usage examples, shown with the help.
+    pub examples: Vec<&'static str>,
     /// If the command can be used in DMs.
     pub dm_enabled: bool,
     /// Default guild member permissions for the command.
@@ -373,6 +610,64 @@ pub struct BaseCommand {
     /// - `Some(Permissions::all())`: Administrator,
     /// - `Some(perms)`: User must satisfy all contained perms,
     pub member_permissions: Option<Permissions>,
+    /// Permissions that the bot needs in the channel to execute the command.
+    pub bot_permissions: Option<Permissions>,
+    /// Gateway intents that the command requires to function.
+    pub intents: Intents,
+    /// How the attached functions are executed.
+    pub execution: Execution,
+    /// If classic commands post a placeholder reply before executing.
+    pub defer: bool,
+    /// If slash commands respond with a modal, so they are not acknowledged before executing.
+    pub modal: bool,
+    /// If slash command responses are only visible to the user.
+    pub ephemeral: bool,
+    /// If slash command errors and denials are as visible as the responses,
+    /// instead of only to the user.
+    pub public_errors: bool,
+    /// Preconditions that must pass before the functions are called.
+    pub checks: Vec<Check>,
+    /// Hooks that are run after the functions, whether they succeeded or not.
+    pub after: Vec<After>,
+    /// Installation types where the command is available.
+    pub integration_types: Vec<IntegrationType>,
+    /// Places where the command can be used, kept in sync with `dm_enabled`.
+    pub contexts: Vec<InteractionContext>,
+    /// How long a reply is reused for the same command and arguments.
+    pub cache_result: Option<Duration>,
+    /// How long responses are kept before they are deleted.
+    pub auto_delete: Option<Duration>,
+}
+
+/// Installation type of the application, the discriminant is the value Discord uses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IntegrationType {
+    /// Installed to a guild.
+    GuildInstall = 0,
+    /// Installed to a user.
+    UserInstall = 1,
+}
+
+/// Place where a command can be used, the discriminant is the value Discord uses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InteractionContext {
+    /// Guild channels.
+    Guild = 0,
+    /// Direct messages with the bot.
+    BotDm = 1,
+    /// Group and direct messages between other users, needs a user install.
+    PrivateChannel = 2,
+}
+
+/// Execution strategy of the attached functions of a command.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Execution {
+    /// Run all functions concurrently, every response is processed.
+    #[default]
+    All,
+    /// Run all functions concurrently, the first successful response is processed
+    /// and the rest are cancelled.
+    FirstSuccess,
 }
 
 impl BaseCommand {
@@ -399,11 +694,45 @@ impl BaseCommand {
     pub fn validate(&self) -> AnyResult<()> {
         self.check_missing_functions()?;
 
+        check_option_meta(self.command.name, self.command.description)
+            .and_then(|_| check_options(self.command.name, &self.command.options))
+            .with_context(|| format!("Invalid command '{}'", self.command.name))?;
+
         // HACK: Mostly waste of cpu cycles.
+        // Still catches what the checks above do not, like option limits and choices.
         self.twilight_commands()
             .try_for_each(|c| c.map(|_| ()))
             .with_context(|| format!("Failed to validate command '{}'", self.command.name))
-            .map_err(Into::into)
+    }
+
+    /// Name of the command.
+    pub const fn name(&self) -> &'static str {
+        self.command.name
+    }
+
+    /// Description of the command.
+    pub const fn description(&self) -> &'static str {
+        self.command.description
+    }
+
+    /// Options of the command, including any subcommands and groups.
+    pub fn options(&self) -> &[CommandOption] {
+        &self.command.options
+    }
+
+    /// Default guild member permissions for the command, see [`Self::member_permissions`].
+    pub const fn permissions(&self) -> Option<Permissions> {
+        self.member_permissions
+    }
+
+    /// Kinds of the functions attached to the base command, without duplicates.
+    pub fn kinds(&self) -> impl Iterator<Item = FunctionKind> + '_ {
+        self.command.kinds()
+    }
+
+    /// Returns the required intents that are not contained in `enabled`.
+    pub fn missing_intents(&self, enabled: Intents) -> Intents {
+        self.intents.difference(enabled)
     }
 
     /// Generate usage help text.
@@ -437,16 +766,28 @@ impl BaseCommand {
 
         let help_spacer = if self.help.is_empty() { "" } else { "\n" };
 
+        let examples = if self.examples.is_empty() {
+            String::new()
+        } else {
+            let list = self
+                .examples
+                .iter()
+                .map(|e| format!("\t{}", help_line(e)))
+                .collect::<Vec<_>>()
+                .join("\n");
+            format!("\nExamples:\n{list}\n")
+        };
+
         let text = indoc::formatdoc! {"
             ```yaml
             {cmd}
-            {help_spacer}{help}
+            {help_spacer}{help}{examples}
             Permissions required: {perms}
             Enabled in DMs: {dm}
             Types: {types}
             ```",
             cmd = self.command.generate_help(0),
-            help = self.help,
+            help = utils::escape_code_block(&self.help),
         };
 
         text
@@ -507,8 +848,22 @@ impl BaseCommandBuilder {
         Self(BaseCommand {
             command: CommandFunctionBuilder::new(name, description).into(),
             help: String::new(),
+            examples: Vec::new(),
             dm_enabled: false,
             member_permissions: None,
+            bot_permissions: None,
+            intents: Intents::empty(),
+            execution: Execution::All,
+            defer: false,
+            modal: false,
+            ephemeral: false,
+            public_errors: false,
+            checks: Vec::new(),
+            after: Vec::new(),
+            integration_types: vec![IntegrationType::GuildInstall],
+            contexts: vec![InteractionContext::Guild],
+            cache_result: None,
+            auto_delete: None,
         })
     }
 
@@ -518,9 +873,31 @@ impl BaseCommandBuilder {
         self
     }
 
+    /// Add a usage example to show with help, eg. `/roles add` or `!time tomorrow`.
+    pub fn example(mut self, example: &'static str) -> Self {
+        self.0.examples.push(example);
+        self
+    }
+
     /// Set command to be available in DMs.
-    pub const fn dm(mut self) -> Self {
+    pub fn dm(mut self) -> Self {
         self.0.dm_enabled = true;
+        if !self.0.contexts.contains(&InteractionContext::BotDm) {
+            self.0.contexts.push(InteractionContext::BotDm);
+        }
+        self
+    }
+
+    /// Set installation types where the command is available, default is guild install.
+    pub fn integration_types(mut self, types: impl IntoIterator<Item = IntegrationType>) -> Self {
+        self.0.integration_types = types.into_iter().collect();
+        self
+    }
+
+    /// Set places where the command can be used, default is guilds and DMs if [`Self::dm`] is set.
+    pub fn contexts(mut self, contexts: impl IntoIterator<Item = InteractionContext>) -> Self {
+        self.0.contexts = contexts.into_iter().collect();
+        self.0.dm_enabled = self.0.contexts.contains(&InteractionContext::BotDm);
         self
     }
 
@@ -530,6 +907,100 @@ impl BaseCommandBuilder {
         self
     }
 
+    /// Set permissions that the bot needs in the channel, checked before the command is executed.
+    /// Commands in DMs are not checked.
+    pub const fn bot_permissions(mut self, permissions: Permissions) -> Self {
+        self.0.bot_permissions = Some(permissions);
+        self
+    }
+
+    /// Set gateway intents that the command needs to work properly.
+    pub const fn intents(mut self, intents: Intents) -> Self {
+        self.0.intents = self.0.intents.union(intents);
+        self
+    }
+
+    /// Post a placeholder reply to classic commands before executing the functions,
+    /// so that slow commands look responsive. See [`ClassicRequest::reply`].
+    ///
+    /// [`ClassicRequest::reply`]: crate::commands::request::ClassicRequest::reply
+    pub const fn defer(mut self) -> Self {
+        self.0.defer = true;
+        self
+    }
+
+    /// Leave slash commands unacknowledged, so that the functions can respond with a modal,
+    /// see [`Response::modal`]. The functions have 3 seconds to respond.
+    ///
+    /// [`Response::modal`]: crate::commands::Response::modal
+    pub const fn modal(mut self) -> Self {
+        self.0.modal = true;
+        self
+    }
+
+    /// Make slash command responses only visible to the user, by deferring them ephemerally.
+    /// Functions update the deferred response as usual, which keeps it ephemeral.
+    /// Followup messages are separate and need their own ephemeral flag.
+    /// Message and user commands are always ephemeral.
+    pub const fn ephemeral(mut self) -> Self {
+        self.0.ephemeral = true;
+        self
+    }
+
+    /// Show errors and denials of public slash commands to everyone, like the responses.
+    /// Without this, they are only visible to the user. Has no effect on [`Self::ephemeral`] commands.
+    pub const fn public_errors(mut self) -> Self {
+        self.0.public_errors = true;
+        self
+    }
+
+    /// Add a precondition to the command, see [`checks`](crate::commands::checks).
+    /// Checks are run in the order they were added.
+    pub fn check(mut self, check: impl Into<Check>) -> Self {
+        self.0.checks.push(check.into());
+        self
+    }
+
+    /// Add a hook that is run after the command, see [`hooks`](crate::commands::hooks).
+    /// Hooks are run in the order they were added, even if the command failed,
+    /// but not if the command was stopped by its arguments or checks before it was executed.
+    pub fn after(mut self, hook: impl Into<After>) -> Self {
+        self.0.after.push(hook.into());
+        self
+    }
+
+    /// Reuse the reply for the same command and arguments for `ttl`, instead of executing again.
+    /// Only replies from [`Response::reply`] and [`Response::embed`] are cached.
+    ///
+    /// This is meant for expensive commands that give the same result for a while anyway.
+    /// Commands that depend on the user, change state or respond ephemerally should not use this.
+    ///
+    /// [`Response::reply`]: crate::commands::Response::reply
+    /// [`Response::embed`]: crate::commands::Response::embed
+    pub const fn cache_result(mut self, ttl: Duration) -> Self {
+        self.0.cache_result = Some(ttl);
+        self
+    }
+
+    /// Delete the responses of a successful command after `delay`,
+    /// unless the command responded with [`Response::none`].
+    /// Classic commands only delete the replies sent through the request, like the ones from
+    /// [`Response::reply`], while slash commands delete the interaction response.
+    /// Deletions that are still waiting on shutdown are cancelled.
+    ///
+    /// [`Response::none`]: crate::commands::Response::none
+    /// [`Response::reply`]: crate::commands::Response::reply
+    pub const fn auto_delete(mut self, delay: Duration) -> Self {
+        self.0.auto_delete = Some(delay);
+        self
+    }
+
+    /// Set how the attached functions are executed.
+    pub const fn execution(mut self, execution: Execution) -> Self {
+        self.0.execution = execution;
+        self
+    }
+
     // NOTE: Technically this should work with just `function: impl IntoFunction<R>` as parameter.
     // Though, without the additional bounds the compiler can sometimes generate "false" errors,
     // even if the problem is actually somewhere else. (Maybe related to incomplete features that are in use)
@@ -549,6 +1020,11 @@ impl BaseCommandBuilder {
         self
     }
 
+    /// Add the standard [`DRY_RUN`] option to the command, see [`Args::is_dry_run`](crate::commands::arg::Args::is_dry_run).
+    pub fn with_dry_run(self) -> Self {
+        self.option(dry_run())
+    }
+
     /// Validate the command.
     pub fn validate(&self) -> AnyResult<()> {
         self.0.validate()
@@ -567,9 +1043,21 @@ pub struct CommandFunction {
     pub description: &'static str,
     pub functions: Vec<Function>,
     pub options: Vec<CommandOption>,
+    /// Permissions that the bot needs in the channel for this subcommand,
+    /// in addition to those of its base command.
+    pub bot_permissions: Permissions,
 }
 
 impl CommandFunction {
+    /// Returns the kinds of the attached functions, without duplicates.
+    pub fn kinds(&self) -> impl Iterator<Item = FunctionKind> + '_ {
+        let mut seen = HashSet::new();
+        self.functions
+            .iter()
+            .map(Function::kind)
+            .filter(move |k| seen.insert(*k))
+    }
+
     /// Returns true if the command has classic functions.
     pub fn has_classic(&self) -> bool {
         self.functions.iter().any(Function::is_classic)
@@ -628,14 +1116,18 @@ impl CommandFunction {
     }
 
     /// Generate usage help text.
-    fn generate_help(&self, indent: usize) -> String {
+    pub(crate) fn generate_help(&self, indent: usize) -> String {
         let mut opt_help = String::new();
         for opt in self.options.iter() {
             opt_help.push('\n');
             opt_help.push_str(&"\t".repeat(indent + 1));
             opt_help.push_str(&opt.generate_help(indent + 1));
         }
-        format!("{:<16} {}{opt_help}", self.name, self.description)
+        format!(
+            "{:<16} {}{opt_help}",
+            help_line(self.name),
+            help_line(self.description)
+        )
     }
 }
 
@@ -660,9 +1152,17 @@ impl CommandFunctionBuilder {
             },
             functions: Vec::new(),
             options: Vec::new(),
+            bot_permissions: Permissions::empty(),
         })
     }
 
+    /// Set permissions that the bot needs in the channel for this subcommand,
+    /// checked along with those of the base command.
+    pub const fn bot_permissions(mut self, permissions: Permissions) -> Self {
+        self.0.bot_permissions = permissions;
+        self
+    }
+
     // NOTE: Technically this should work with just `function: impl IntoFunction<R>` as parameter.
     // Though, without the additional bounds the compiler can sometimes generate "false" errors,
     // even if the problem is actually somewhere else. (Maybe related to incomplete features that are in use)
@@ -682,6 +1182,11 @@ impl CommandFunctionBuilder {
         self
     }
 
+    /// Add the standard [`DRY_RUN`] option to the command, see [`Args::is_dry_run`](crate::commands::arg::Args::is_dry_run).
+    pub fn with_dry_run(self) -> Self {
+        self.option(dry_run())
+    }
+
     /// Finalize the command.
     pub fn build(self) -> CommandFunction {
         self.0
@@ -699,6 +1204,21 @@ impl CommandGroup {
     pub fn to_options(&self) -> Vec<CommandOption> {
         self.subs.iter().cloned().map(CommandOption::Sub).collect()
     }
+
+    /// Generate usage help text.
+    pub(crate) fn generate_help(&self, indent: usize) -> String {
+        let mut sub_help = format!(
+            "{:<16} {}",
+            help_line(self.name),
+            help_line(self.description)
+        );
+        for sub in self.subs.iter() {
+            sub_help.push('\n');
+            sub_help.push_str(&"\t".repeat(indent + 1));
+            sub_help.push_str(&sub.generate_help(indent + 1));
+        }
+        sub_help
+    }
 }
 
 impl From<CommandGroupBuilder> for CommandGroup {
@@ -770,19 +1290,28 @@ impl CommandOption {
         match self {
             Self::Arg(a) => {
                 let brackets = if a.required { ['<', '>'] } else { ['[', ']'] };
-                let name = format!("{}{}{}", brackets[0], a.name, brackets[1]);
-                format!("{name:<16} {}", a.description)
+                let name = format!("{}{}{}", brackets[0], help_line(a.name), brackets[1]);
+                let aliases = if a.aliases.is_empty() {
+                    String::new()
+                } else {
+                    format!(" (aliases: {})", help_line(&a.aliases.join(", ")))
+                };
+                let choices = a
+                    .kind
+                    .choice_values()
+                    .map(|c| {
+                        format!(
+                            "\n{}{:<16} {}",
+                            "\t".repeat(indent + 1),
+                            "choices:",
+                            help_choices(&c)
+                        )
+                    })
+                    .unwrap_or_default();
+                format!("{name:<16} {}{aliases}{choices}", help_line(a.description))
             },
             Self::Sub(s) => s.generate_help(indent),
-            Self::Group(g) => {
-                let mut sub_help = format!("{:<16} {}", g.name, g.description);
-                for sub in g.subs.iter() {
-                    sub_help.push('\n');
-                    sub_help.push_str(&"\t".repeat(indent + 1));
-                    sub_help.push_str(&sub.generate_help(indent + 1));
-                }
-                sub_help
-            },
+            Self::Group(g) => g.generate_help(indent),
         }
     }
 }
@@ -811,6 +1340,12 @@ impl From<ChannelOptionBuilder> for CommandOption {
     }
 }
 
+impl From<AttachmentOptionBuilder> for CommandOption {
+    fn from(value: AttachmentOptionBuilder) -> Self {
+        value.build().into()
+    }
+}
+
 impl From<ArgDesc> for CommandOption {
     fn from(value: ArgDesc) -> Self {
         Self::Arg(value)
@@ -841,6 +1376,39 @@ impl From<CommandGroupBuilder> for CommandOption {
     }
 }
 
+/// Most choices of an argument listed in help text.
+const MAX_HELP_CHOICES: usize = 10;
+
+/// List the values of choices for help text, with names that differ from the value.
+fn help_choices(choices: &[(&str, String)]) -> String {
+    let mut list = choices
+        .iter()
+        .take(MAX_HELP_CHOICES)
+        .map(|(name, value)| {
+            if *name == value {
+                help_line(value)
+            } else {
+                format!("{} ({})", help_line(value), help_line(name))
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    if choices.len() > MAX_HELP_CHOICES {
+        list.push_str(&format!(
+            ", ... ({} more)",
+            choices.len() - MAX_HELP_CHOICES
+        ));
+    }
+
+    list
+}
+
+/// Make a name or description safe to show on a single line of help text.
+fn help_line(text: &str) -> String {
+    utils::escape_code_block(text).replace(['\n', '\r'], " ")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -971,10 +1539,145 @@ mod tests {
     #[test]
     fn valid_commands() {
         // FIXME: Numerical choices must be in range of min and max, this should give some warning at least
-        commands()
+        if let Some((e, c)) = commands()
             .iter()
-            .filter_map(|c| Some((c.validate().err()?, c)))
-            .for_each(|(e, c)| panic!("\n{c:#?}\n\n{e}"));
+            .find_map(|c| Some((c.validate().err()?, c)))
+        {
+            panic!("\n{c:#?}\n\n{e}");
+        }
+    }
+
+    #[test]
+    fn invalid_options() {
+        let validate = |cmd: BaseCommandBuilder| {
+            cmd.attach(mock::classic)
+                .attach(mock::slash)
+                .build()
+                .validate()
+                .unwrap_err()
+                .downcast::<CommandValidationError>()
+                .unwrap()
+        };
+
+        let out_of_order = command("order", "description")
+            .option(bool("a", "description"))
+            .option(bool("b", "description").required());
+        assert!(matches!(
+            validate(out_of_order),
+            CommandValidationError::RequiredAfterOptional { .. }
+        ));
+
+        let duplicate = command("duplicate", "description")
+            .option(bool("a", "description"))
+            .option(string("a", "description"));
+        assert!(matches!(
+            validate(duplicate),
+            CommandValidationError::AmbiguousName(_)
+        ));
+
+        let nested_duplicate = command("nested", "description").option(
+            group("g", "description")
+                .option(sub("s", "description"))
+                .option(sub("s", "description")),
+        );
+        assert!(matches!(
+            validate(nested_duplicate),
+            CommandValidationError::AmbiguousName(_)
+        ));
+
+        let alias_collision = command("alias", "description")
+            .option(user("user", "description").alias("u"))
+            .option(string("u", "description"));
+        assert!(matches!(
+            validate(alias_collision),
+            CommandValidationError::AmbiguousName(_)
+        ));
+
+        let bad_name = command("Name", "description");
+        assert!(matches!(
+            validate(bad_name),
+            CommandValidationError::InvalidName(_)
+        ));
+
+        let bad_description = command("description", "description")
+            .option(bool("a", "long description ".repeat(10).leak()));
+        assert!(matches!(
+            validate(bad_description),
+            CommandValidationError::InvalidDescription(_)
+        ));
+    }
+
+    #[test]
+    fn required_after_optional() {
+        let err = command("order", "description")
+            .attach(mock::classic)
+            .option(bool("a", "description"))
+            .option(bool("b", "description").required())
+            .validate()
+            .unwrap_err();
+
+        assert!(err.to_string().contains("'order'"), "{err}");
+        match err.downcast::<CommandValidationError>().unwrap() {
+            CommandValidationError::RequiredAfterOptional {
+                required,
+                optional,
+                parent,
+            } => assert_eq!((required, optional, parent), ("b", "a", "order")),
+            e => panic!("Unexpected error: {e}"),
+        }
+    }
+
+    #[test]
+    fn interaction_contexts() {
+        use InteractionContext::*;
+
+        let cmd = || command("ctx", "description").attach(mock::slash);
+        cmd().validate().unwrap();
+
+        let dm = cmd().contexts([Guild, BotDm]);
+        assert!(dm.clone().build().dm_enabled);
+        dm.validate().unwrap();
+        cmd().contexts([BotDm]).validate().unwrap();
+        let user = cmd()
+            .integration_types([IntegrationType::UserInstall])
+            .contexts([Guild, BotDm, PrivateChannel]);
+        user.validate().unwrap();
+
+        // Serialized next to the fields of twilight's command.
+        let registered: Vec<_> = user.build().twilight_commands().try_collect().unwrap();
+        let json = serde_json::to_value(&registered).unwrap();
+        assert_eq!(json[0]["name"], "ctx");
+        assert_eq!(json[0]["integration_types"], serde_json::json!([1]));
+        assert_eq!(json[0]["contexts"], serde_json::json!([0, 1, 2]));
+
+        for invalid in [
+            cmd().contexts([]),
+            cmd().integration_types([]),
+            cmd().contexts([Guild, PrivateChannel]),
+        ] {
+            let err = invalid.validate().unwrap_err();
+            assert!(
+                matches!(
+                    err.downcast_ref::<CommandValidationError>(),
+                    Some(CommandValidationError::InvalidContexts(_))
+                ),
+                "{err:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn missing_intents() {
+        let cmd = command("intents", "description")
+            .attach(mock::classic)
+            .intents(Intents::GUILD_VOICE_STATES | Intents::GUILD_MESSAGE_REACTIONS)
+            .build();
+
+        assert_eq!(
+            cmd.missing_intents(Intents::GUILDS | Intents::GUILD_MESSAGE_REACTIONS),
+            Intents::GUILD_VOICE_STATES
+        );
+        assert!(cmd.missing_intents(Intents::all()).is_empty());
     }
 
     #[test]
@@ -983,4 +1686,98 @@ mod tests {
             .iter()
             .for_each(|c| println!("{}\n", c.generate_help()))
     }
+
+    #[test]
+    fn help_escapes_code_block() {
+        let help = command("a```b\nc", "`description`\n```")
+            .attach(mock::classic)
+            .option(string("x```", "y\n```").required())
+            .help("more ```help```".to_string())
+            .example("/a ```")
+            .build()
+            .generate_help();
+
+        // Only the enclosing code block fences remain.
+        assert_eq!(help.matches("```").count(), 2, "{help}");
+        assert!(
+            help.starts_with("```yaml\na`\u{200B}`\u{200B}`\u{200B}b c"),
+            "{help}"
+        );
+    }
+
+    #[test]
+    fn help_examples() {
+        let cmd = command("ex", "description").attach(mock::classic);
+        assert!(!cmd.clone().build().generate_help().contains("Examples:"));
+
+        let help = cmd
+            .example("/ex")
+            .example("!ex 1 2")
+            .build()
+            .generate_help();
+        assert!(help.contains("Examples:\n\t/ex\n\t!ex 1 2\n"), "{help}");
+    }
+
+    #[test]
+    fn help_lists_choices() {
+        let help = command("ch", "description")
+            .attach(mock::classic)
+            .option(integer("size", "description").choices([("small", 1), ("large", 2)]))
+            .option(
+                string("mode", "description")
+                    .choices((0..12).map(|i| (i.to_string(), i.to_string()))),
+            )
+            .build()
+            .generate_help();
+
+        assert!(
+            help.contains("choices:         1 (small), 2 (large)\n"),
+            "{help}"
+        );
+        assert!(
+            help.contains("choices:         0, 1, 2, 3, 4, 5, 6, 7, 8, 9, ... (2 more)"),
+            "{help}"
+        );
+    }
+
+    #[test]
+    fn attachment_limits() {
+        let file = |size: u64, content_type: Option<&str>| -> Attachment {
+            serde_json::from_value(serde_json::json!({
+                "content_type": content_type,
+                "filename": "file",
+                "id": "1",
+                "proxy_url": "",
+                "size": size,
+                "url": "",
+            }))
+            .unwrap()
+        };
+
+        let arg = attachment("image", "description")
+            .max_size(1000)
+            .content_types(["image/*", "application/json"])
+            .build();
+        let ArgKind::Attachment(data) = arg.kind else {
+            unreachable!()
+        };
+
+        assert!(data.validate(&file(1000, Some("image/png"))).is_ok());
+        assert!(data
+            .validate(&file(10, Some("application/json; charset=utf-8")))
+            .is_ok());
+
+        // Too large.
+        let err = data.validate(&file(1001, Some("image/png")));
+        assert_eq!(err, Err(CommandError::ArgsMismatch));
+
+        // Wrong or unknown type.
+        let err = data.validate(&file(10, Some("text/plain")));
+        assert_eq!(err, Err(CommandError::ArgsMismatch));
+        assert!(data.validate(&file(10, None)).is_err());
+
+        // No limits.
+        let data = AttachmentData::default();
+        assert!(data.validate(&file(u64::MAX, None)).is_ok());
+    }
 }
@@ -1,11 +1,47 @@
+use serde::{Serialize, Serializer};
 use thiserror::Error;
 use twilight_model::application::command::{Command, CommandOption, CommandType};
 use twilight_util::builder::command::*;
 
-use crate::commands::builder::BaseCommand;
+use crate::commands::builder::{BaseCommand, IntegrationType, InteractionContext};
 use crate::utils::prelude::*;
 
-pub type TwilightCommand = Command;
+/// Command in the form that is registered to Discord.
+///
+/// Twilight's command model lacks the installation types and contexts,
+/// so they are serialized next to it.
+#[derive(Debug, Clone, Serialize)]
+pub struct TwilightCommand {
+    #[serde(flatten)]
+    pub command: Command,
+    pub integration_types: Vec<IntegrationType>,
+    pub contexts: Vec<InteractionContext>,
+}
+
+impl TwilightCommand {
+    fn new(command: Command, base: BaseCommand) -> Self {
+        Self {
+            command,
+            integration_types: base.integration_types,
+            contexts: base.contexts,
+        }
+    }
+}
+
+impl Serialize for IntegrationType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u8(*self as u8)
+    }
+}
+
+impl Serialize for InteractionContext {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u8(*self as u8)
+    }
+}
+
+/// Maximum number of choices of an option.
+pub const MAX_CHOICES: usize = 25;
 
 /// Helper trait for twilight builders where the value may be optional.
 /// This trait lets you apply the optional value if it is present,
@@ -113,10 +149,33 @@ pub enum CommandValidationError {
     #[error("Option names must be locally unique: {0}")]
     AmbiguousName(String),
 
+    /// Name does not follow Discord's naming rules.
+    #[error("Invalid name: {0}")]
+    InvalidName(String),
+
+    /// Description is empty or too long.
+    #[error("Invalid description: {0}")]
+    InvalidDescription(String),
+
+    /// Required arguments must come before optional ones.
+    #[error(
+        "Required argument '{required}' must come before optional argument '{optional}' in \
+         '{parent}'"
+    )]
+    RequiredAfterOptional {
+        required: &'static str,
+        optional: &'static str,
+        parent: &'static str,
+    },
+
     /// Options in GUI-based commands.
     #[error("GUI-based commands cannot have options")]
     GuiOptions,
 
+    /// Combination of installation types and contexts that cannot be registered.
+    #[error("Invalid contexts: {0}")]
+    InvalidContexts(String),
+
     /// Twilight's validation error.
     #[error(transparent)]
     Twilight(#[from] twilight_validate::command::CommandValidationError),
@@ -126,12 +185,40 @@ pub enum CommandValidationError {
     Other(#[from] anyhow::Error),
 }
 
-pub struct SlashCommand(Command);
+/// Checks that the installation types and contexts can be registered.
+fn check_contexts(value: &BaseCommand) -> Result<(), CommandValidationError> {
+    let name = value.command.name;
+    let err = |text: &str| {
+        Err(CommandValidationError::InvalidContexts(format!(
+            "'{name}' {text}"
+        )))
+    };
+
+    if value.integration_types.is_empty() {
+        return err("must have at least one installation type");
+    }
+    if value.contexts.is_empty() {
+        return err("must have at least one context");
+    }
+    if value.contexts.contains(&InteractionContext::PrivateChannel)
+        && !value
+            .integration_types
+            .contains(&IntegrationType::UserInstall)
+    {
+        return err("can only be used in private channels when installed to a user");
+    }
+
+    Ok(())
+}
+
+pub struct SlashCommand(TwilightCommand);
 
 impl TryFrom<BaseCommand> for SlashCommand {
     type Error = CommandValidationError;
 
     fn try_from(value: BaseCommand) -> Result<Self, Self::Error> {
+        check_contexts(&value)?;
+
         let mut cmd = CommandBuilder::new(
             value.command.name,
             value.command.description,
@@ -139,7 +226,7 @@ impl TryFrom<BaseCommand> for SlashCommand {
         )
         .dm_permission(value.dm_enabled);
 
-        for opt in value.command.options {
+        for opt in value.command.options.iter().cloned() {
             if let Ok(opt) = CommandOption::try_from(opt) {
                 cmd = cmd.option(opt);
             }
@@ -151,22 +238,24 @@ impl TryFrom<BaseCommand> for SlashCommand {
 
         validate_command(&cmd).context("Failed to validate slash command")?;
 
-        Ok(Self(cmd))
+        Ok(Self(TwilightCommand::new(cmd, value)))
     }
 }
 
-impl From<SlashCommand> for Command {
+impl From<SlashCommand> for TwilightCommand {
     fn from(value: SlashCommand) -> Self {
         value.0
     }
 }
 
-pub struct MessageCommand(Command);
+pub struct MessageCommand(TwilightCommand);
 
 impl TryFrom<BaseCommand> for MessageCommand {
     type Error = CommandValidationError;
 
     fn try_from(value: BaseCommand) -> Result<Self, Self::Error> {
+        check_contexts(&value)?;
+
         let mut cmd = CommandBuilder::new(value.command.name, "", CommandType::Message)
             .dm_permission(value.dm_enabled)
             .build();
@@ -175,22 +264,24 @@ impl TryFrom<BaseCommand> for MessageCommand {
 
         validate_command(&cmd).context("Failed to validate message command")?;
 
-        Ok(Self(cmd))
+        Ok(Self(TwilightCommand::new(cmd, value)))
     }
 }
 
-impl From<MessageCommand> for Command {
+impl From<MessageCommand> for TwilightCommand {
     fn from(value: MessageCommand) -> Self {
         value.0
     }
 }
 
-pub struct UserCommand(Command);
+pub struct UserCommand(TwilightCommand);
 
 impl TryFrom<BaseCommand> for UserCommand {
     type Error = CommandValidationError;
 
     fn try_from(value: BaseCommand) -> Result<Self, Self::Error> {
+        check_contexts(&value)?;
+
         let mut cmd = CommandBuilder::new(value.command.name, "", CommandType::User)
             .dm_permission(value.dm_enabled)
             .build();
@@ -199,11 +290,11 @@ impl TryFrom<BaseCommand> for UserCommand {
 
         validate_command(&cmd).context("Failed to validate user command")?;
 
-        Ok(Self(cmd))
+        Ok(Self(TwilightCommand::new(cmd, value)))
     }
 }
 
-impl From<UserCommand> for Command {
+impl From<UserCommand> for TwilightCommand {
     fn from(value: UserCommand) -> Self {
         value.0
     }
@@ -265,12 +356,26 @@ impl From<super::ArgDesc> for CommandOption {
                 .optional(d.min, |b, v| b.min_value(v))
                 .optional(d.max, |b, v| b.max_value(v))
                 .build(),
-            super::ArgKind::String(d) => StringBuilder::new(value.name, value.description)
-                .required(value.required)
-                .choices(d.choices)
-                .optional(d.min_length, |b, v| b.min_length(v))
-                .optional(d.max_length, |b, v| b.max_length(v))
-                .build(),
+            super::ArgKind::String(mut d) => {
+                if d.autocomplete {
+                    d.choices.clear(); // Suggested by autocomplete instead.
+                } else if d.choices.len() > MAX_CHOICES {
+                    warn!(
+                        "Option '{}' has {} choices, only the first {MAX_CHOICES} are used",
+                        value.name,
+                        d.choices.len()
+                    );
+                    d.choices.truncate(MAX_CHOICES);
+                }
+
+                StringBuilder::new(value.name, value.description)
+                    .required(value.required)
+                    .autocomplete(d.autocomplete)
+                    .choices(d.choices)
+                    .optional(d.min_length, |b, v| b.min_length(v))
+                    .optional(d.max_length, |b, v| b.max_length(v))
+                    .build()
+            },
             super::ArgKind::Channel(d) => ChannelBuilder::new(value.name, value.description)
                 .required(value.required)
                 .channel_types(d.channel_types)
@@ -280,7 +385,7 @@ impl From<super::ArgDesc> for CommandOption {
                 .min_length(1)
                 .max_length(32)
                 .build(),
-            super::ArgKind::Attachment => AttachmentBuilder::new(value.name, value.description)
+            super::ArgKind::Attachment(_) => AttachmentBuilder::new(value.name, value.description)
                 .required(value.required)
                 .build(),
             super::ArgKind::User => UserBuilder::new(value.name, value.description)
@@ -0,0 +1,102 @@
+//! Cached replies of commands that opt in with `cache_result`.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use twilight_model::channel::message::{AllowedMentions, Embed};
+
+use crate::commands::request::Request;
+use crate::utils::prelude::*;
+use crate::Context;
+
+/// Reply message of a command, that can be sent again from the cache.
+#[derive(Debug, Clone, Default)]
+pub struct Reply {
+    pub content: Option<String>,
+    pub embeds: Vec<Embed>,
+}
+
+impl Reply {
+    /// Send the reply as the response to the request.
+    /// The reply can be sent again from the cache to someone else, so it pings no one.
+    pub async fn send(&self, ctx: &Context, req: Request) -> AnyResult<()> {
+        let mentions = AllowedMentions::default();
+        let token = match req {
+            Request::Classic(req) => {
+                let mut msg = ctx
+                    .http
+                    .create_message(req.message.channel_id)
+                    .reply(req.message.id)
+                    .allowed_mentions(Some(&mentions))
+                    .embeds(&self.embeds)?;
+                if let Some(content) = &self.content {
+                    msg = msg.content(content)?;
+                }
+                let reply = msg.send().await.context("Failed to send reply")?;
+                req.record_reply(reply.id);
+                return Ok(());
+            },
+            Request::Slash(req) => req.interaction.token.to_owned(),
+            Request::Message(req) => req.interaction.token.to_owned(),
+            Request::User(req) => req.interaction.token.to_owned(),
+        };
+
+        ctx.interaction()
+            .update_response(&token)
+            .allowed_mentions(Some(&mentions))
+            .content(self.content.as_deref())?
+            .embeds(Some(&self.embeds))?
+            .await
+            .context("Failed to update response")?;
+
+        Ok(())
+    }
+}
+
+/// Store of command replies by command path and arguments.
+#[derive(Debug, Default)]
+pub struct ResultCache {
+    entries: Mutex<HashMap<String, (Instant, Reply)>>,
+}
+
+impl ResultCache {
+    /// Get a reply that has not expired yet.
+    pub fn get(&self, key: &str) -> Option<Reply> {
+        let mut entries = self.entries.lock().unwrap();
+        let now = Instant::now();
+
+        // Drop the expired ones while at it.
+        entries.retain(|_, (expires, _)| *expires > now);
+        entries.get(key).map(|(_, reply)| reply.to_owned())
+    }
+
+    /// Store a reply for `ttl`.
+    pub fn insert(&self, key: String, reply: Reply, ttl: Duration) {
+        self.entries
+            .lock()
+            .unwrap()
+            .insert(key, (Instant::now() + ttl, reply));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cached_replies_expire() {
+        let cache = ResultCache::default();
+        let reply = |text: &str| Reply {
+            content: Some(text.to_string()),
+            embeds: Vec::new(),
+        };
+
+        cache.insert("fresh".to_string(), reply("a"), Duration::from_secs(60));
+        cache.insert("stale".to_string(), reply("b"), Duration::ZERO);
+
+        assert_eq!(cache.get("fresh").unwrap().content.as_deref(), Some("a"));
+        assert!(cache.get("stale").is_none());
+        assert!(cache.get("missing").is_none());
+    }
+}
@@ -0,0 +1,239 @@
+//! Preconditions that are checked before a command is executed.
+//!
+//! Checks are attached to a command with [`BaseCommandBuilder::check`] and run in order,
+//! the first failing check stops the command with its error.
+//!
+//! [`BaseCommandBuilder::check`]: crate::commands::builder::BaseCommandBuilder::check
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use twilight_model::guild::Permissions;
+use twilight_model::id::marker::UserMarker;
+use twilight_model::id::Id;
+use twilight_model::oauth::Application;
+
+use crate::commands::request::Request;
+use crate::commands::{CommandError, CommandResult};
+use crate::utils::prelude::*;
+use crate::Context;
+
+type CheckFn = dyn Fn(&Context, &Request) -> CommandResult<()> + Send + Sync;
+
+/// Precondition function of a command.
+#[derive(Clone)]
+pub struct Check(Arc<CheckFn>);
+
+impl Check {
+    pub fn new<F>(f: F) -> Self
+    where
+        F: Fn(&Context, &Request) -> CommandResult<()> + Send + Sync + 'static,
+    {
+        Self(Arc::new(f))
+    }
+
+    pub fn call(&self, ctx: &Context, req: &Request) -> CommandResult<()> {
+        (self.0)(ctx, req)
+    }
+}
+
+impl<F> From<F> for Check
+where
+    F: Fn(&Context, &Request) -> CommandResult<()> + Send + Sync + 'static,
+{
+    fn from(f: F) -> Self {
+        Self::new(f)
+    }
+}
+
+impl std::fmt::Debug for Check {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Check(_)")
+    }
+}
+
+/// Run the checks in order, stopping at the first failure.
+pub fn run(ctx: &Context, checks: &[Check], req: &Request) -> CommandResult<()> {
+    checks.iter().try_for_each(|c| c.call(ctx, req))
+}
+
+/// Only allow the application owner or its team members.
+pub fn owner_only() -> Check {
+    Check::new(|ctx, req| {
+        if req
+            .author_id()
+            .is_some_and(|id| is_owner(&ctx.application, id))
+        {
+            Ok(())
+        } else {
+            Err(CommandError::AccessDenied)
+        }
+    })
+}
+
+/// Returns `true` if the user is the application owner or a team member.
+/// The owner of a team application is a placeholder user for the team, so the team goes first.
+pub fn is_owner(application: &Application, user_id: Id<UserMarker>) -> bool {
+    match (&application.team, &application.owner) {
+        (Some(team), _) => team.members.iter().any(|m| m.user.id == user_id),
+        (None, Some(owner)) => owner.id == user_id,
+        (None, None) => false,
+    }
+}
+
+/// Only allow use in guilds.
+pub fn guild_only() -> Check {
+    Check::new(|_, req| match req.guild_id() {
+        Some(_) => Ok(()),
+        None => Err(CommandError::Disabled),
+    })
+}
+
+/// Only allow use in direct messages.
+pub fn dm_only() -> Check {
+    Check::new(|_, req| match req.guild_id() {
+        Some(_) => Err(CommandError::Disabled),
+        None => Ok(()),
+    })
+}
+
+/// Only allow members that have all of the `required` permissions in the channel.
+/// Anyone is allowed outside of guilds.
+pub fn has_permissions(required: Permissions) -> Check {
+    Check::new(move |ctx, req| {
+        let Some(perms) = req.permissions(ctx) else {
+            return Ok(());
+        };
+
+        if perms.contains(required) {
+            Ok(())
+        } else {
+            Err(CommandError::AccessDenied)
+        }
+    })
+}
+
+/// Only allow members that have any of the `allowed` permissions in the channel.
+/// Anyone is allowed outside of guilds.
+pub fn has_any_permissions(allowed: Permissions) -> Check {
+    Check::new(move |ctx, req| {
+        let Some(perms) = req.permissions(ctx) else {
+            return Ok(());
+        };
+
+        if perms.intersects(allowed) {
+            Ok(())
+        } else {
+            Err(CommandError::AccessDenied)
+        }
+    })
+}
+
+/// Only allow a user to use the command once per `duration`.
+pub fn cooldown(duration: Duration) -> Check {
+    let used = Mutex::new(HashMap::<Id<UserMarker>, Instant>::new());
+
+    Check::new(move |_, req| {
+        let Some(user_id) = req.author_id() else {
+            return Ok(());
+        };
+
+        let now = Instant::now();
+        let mut used = used.lock().unwrap();
+
+        // Forget users whose cooldown has passed.
+        used.retain(|_, last| now.duration_since(*last) < duration);
+
+        match used.get(&user_id) {
+            Some(last) => Err(CommandError::Cooldown(duration - now.duration_since(*last))),
+            None => {
+                used.insert(user_id, now);
+                Ok(())
+            },
+        }
+    })
+}
+
+impl Request {
+    /// Permissions of the user in the channel, `None` if not in a guild.
+    fn permissions(&self, ctx: &Context) -> Option<Permissions> {
+        match self {
+            Self::Classic(req) => {
+                req.message.guild_id?;
+                let perms = ctx
+                    .cache
+                    .permissions()
+                    .in_channel(req.message.author.id, req.message.channel_id);
+
+                // Deny if the permissions cannot be calculated.
+                Some(perms.unwrap_or_else(|e| {
+                    warn!("Failed to calculate permissions from cache: {e}");
+                    Permissions::empty()
+                }))
+            },
+            Self::Slash(req) => req.interaction.member.as_ref()?.permissions,
+            Self::Message(req) => req.interaction.member.as_ref()?.permissions,
+            Self::User(req) => req.interaction.member.as_ref()?.permissions,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn application(owner: Option<u64>, team: &[u64]) -> Application {
+        let user = |id: u64| {
+            serde_json::json!({
+                "id": id.to_string(),
+                "username": "user",
+                "discriminator": "0",
+                "avatar": null,
+            })
+        };
+        let team = (!team.is_empty()).then(|| {
+            serde_json::json!({
+                "id": "2",
+                "icon": null,
+                "name": "team",
+                "owner_user_id": team[0].to_string(),
+                "members": team.iter().map(|&id| serde_json::json!({
+                    "membership_state": 2,
+                    "permissions": ["*"],
+                    "team_id": "2",
+                    "user": user(id),
+                })).collect::<Vec<_>>(),
+            })
+        });
+
+        serde_json::from_value(serde_json::json!({
+            "bot_public": true,
+            "bot_require_code_grant": false,
+            "description": "",
+            "id": "1",
+            "name": "bot",
+            "owner": owner.map(user),
+            "team": team,
+            "verify_key": "",
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn owner_or_team_member() {
+        let app = application(Some(10), &[]);
+        assert!(is_owner(&app, Id::new(10)));
+        assert!(!is_owner(&app, Id::new(11)));
+
+        // Team applications have the team as a placeholder owner.
+        let app = application(Some(2), &[20, 21]);
+        assert!(is_owner(&app, Id::new(20)));
+        assert!(is_owner(&app, Id::new(21)));
+        assert!(!is_owner(&app, Id::new(2)));
+        assert!(!is_owner(&app, Id::new(10)));
+
+        let app = application(None, &[]);
+        assert!(!is_owner(&app, Id::new(10)));
+    }
+}
@@ -4,29 +4,29 @@ use derive_more::{IsVariant, Unwrap};
 
 use crate::commands::prelude::*;
 use crate::commands::{AsyncResponse, ResponseFuture};
-// use crate::utils::prelude::*;
+use crate::utils::prelude::*;
 use crate::Context;
 
 pub mod mock {
     use super::*;
 
     pub async fn classic(_ctx: Context, req: ClassicRequest) -> CommandResponse {
-        println!("CLASSIC REQ: {req:#?}");
+        trace!("CLASSIC REQ: {req:#?}");
         Ok(Response::none())
     }
 
     pub async fn slash(_ctx: Context, req: SlashRequest) -> CommandResponse {
-        println!("SLASH REQ: {req:#?}");
+        trace!("SLASH REQ: {req:#?}");
         Ok(Response::none())
     }
 
     pub async fn message(_ctx: Context, req: MessageRequest) -> CommandResponse {
-        println!("MESSAGE REQ: {req:#?}");
+        trace!("MESSAGE REQ: {req:#?}");
         Ok(Response::none())
     }
 
     pub async fn user(_ctx: Context, req: UserRequest) -> CommandResponse {
-        println!("USER REQ: {req:#?}");
+        trace!("USER REQ: {req:#?}");
         Ok(Response::none())
     }
 }
@@ -1,10 +1,15 @@
 use std::sync::Arc;
+use std::time::Duration;
 
 use tokio::task::JoinSet;
-use twilight_model::application::command::CommandType;
+use twilight_model::application::command::{
+    CommandOptionChoice, CommandOptionChoiceValue, CommandType,
+};
 use twilight_model::application::interaction::application_command::{
-    CommandData, CommandOptionValue,
+    CommandData, CommandDataOption, CommandInteractionDataResolved, CommandOptionValue,
 };
+use twilight_model::application::interaction::message_component::MessageComponentInteractionData;
+use twilight_model::application::interaction::modal::ModalInteractionData;
 use twilight_model::application::interaction::Interaction;
 use twilight_model::channel::message::MessageFlags;
 use twilight_model::channel::Message;
@@ -12,18 +17,22 @@ use twilight_model::guild::Permissions;
 use twilight_model::http::interaction::{
     InteractionResponse, InteractionResponseData, InteractionResponseType,
 };
-use twilight_model::id::marker::InteractionMarker;
+use twilight_model::id::marker::{
+    ChannelMarker, GuildMarker, InteractionMarker, MessageMarker, RoleMarker, UserMarker,
+};
 use twilight_model::id::Id;
 use twilight_util::permission_calculator::PermissionCalculator;
 
-use crate::commands::arg::{Arg, ArgValue, Ref};
-use crate::commands::builder::{ArgDesc, ArgKind, CommandFunction, CommandGroup, CommandOption};
-use crate::commands::function::{Callable, ClassicFunction, SlashFunction};
+use crate::commands::arg::{Arg, ArgValue, MemberName, MemberNames, Mentionable, Ref};
+use crate::commands::builder::twilight::MAX_CHOICES;
+use crate::commands::builder::{ArgDesc, ArgKind, CommandFunction, Execution, StringData};
+use crate::commands::cache::Reply;
+use crate::commands::function::Callable;
 use crate::commands::prelude::*;
-use crate::parser;
+use crate::commands::request::{Placeholder, Request};
+use crate::commands::{checks, hooks, Commands, Lookup, ResolvedCommand};
 use crate::utils::prelude::*;
-
-const ERROR_MESSAGE: &str = "The bot has encountered an error executing the command! 😕";
+use crate::{confirm, help, modals, paginator, parser, utils, verify};
 
 /// Handle interaction and execute command functions.
 pub async fn application_command(
@@ -40,176 +49,552 @@ pub async fn application_command(
     };
 
     let name = base.command.name;
+    let auto_delete = base.auto_delete;
     let base = Arc::clone(base);
     let inter = Arc::new(inter);
     let data = Arc::new(data);
 
-    // Process the command by kind.
-    let result = {
-        let inter = Arc::clone(&inter);
-        match data.kind {
-            CommandType::ChatInput => process_slash(ctx, base, inter, data).await,
-            CommandType::Message => process_message(ctx, base, inter, data).await,
-            CommandType::User => process_user(ctx, base, inter, data).await,
-            CommandType::Unknown(n) => panic!("Unknown command kind: {n}"),
-            other => panic!("Unhandled command kind: {other:?}"),
+    // Message and user commands are always ephemeral.
+    let ephemeral = base.ephemeral || data.kind != CommandType::ChatInput;
+    let error_flags = if base.public_errors && !ephemeral {
+        MessageFlags::empty()
+    } else {
+        MessageFlags::EPHEMERAL
+    };
+
+    // Reject before acknowledging, so that no loading state is left behind.
+    if let Some(key) = interaction_denial(ctx, &base, &inter).await? {
+        let resp = InteractionResponse {
+            kind: InteractionResponseType::ChannelMessageWithSource,
+            data: Some(InteractionResponseData {
+                content: Some(ctx.text(inter.guild_id, key, &[])),
+                flags: Some(error_flags),
+                ..Default::default()
+            }),
+        };
+        ctx.interaction()
+            .create_response(inter.id, &inter.token, &resp)
+            .await
+            .context("Failed to send denial")?;
+
+        return Ok(());
+    }
+
+    // Parse and check the request before acknowledging too, a failure is the first response.
+    let prepared = prepare(ctx, &base, &inter, &data).await;
+
+    // Commands that respond with a modal cannot be acknowledged before that.
+    let acknowledged = prepared.is_ok() && !(base.modal && data.kind == CommandType::ChatInput);
+
+    // Acknowledge the interaction, this decides the visibility of the response.
+    let ack = if !acknowledged {
+        Ok(())
+    } else if ephemeral {
+        ephemeral_acknowledge(ctx, inter.id, &inter.token).await
+    } else {
+        public_acknowledge(ctx, inter.id, &inter.token).await
+    };
+
+    if let Err(e) = ack {
+        if utils::api_error_code(&e) != Some(ALREADY_ACKNOWLEDGED) {
+            // Nothing to follow up on, so give up on the command.
+            return Err(e
+                .context(format!(
+                    "Failed to acknowledge application command '{name}'"
+                ))
+                .into());
         }
+        debug!("Interaction for '{name}' was already acknowledged, continuing");
+    }
+
+    let kind = match data.kind {
+        CommandType::ChatInput => "slash",
+        CommandType::Message => "message",
+        CommandType::User => "user",
+        _ => "application",
+    };
+
+    // Process the command by kind.
+    let result = match prepared {
+        Ok(Prepared::Slash(resolved, req)) => process_slash(ctx, &base, &resolved, req).await,
+        Ok(Prepared::Message(req)) => process_message(ctx, &base, req).await,
+        Ok(Prepared::User(req)) => process_user(ctx, &base, req).await,
+        Err(e) => Err(e),
     };
 
     // Handle execution result.
     // Catch erroneous execution and clear dangling response.
-    if result.is_err() {
-        ctx.interaction()
-            .create_followup(&inter.token)
-            .flags(MessageFlags::EPHEMERAL)
-            .content(ERROR_MESSAGE)?
-            .await
-            .context("Failed to send error message")?;
+    if let Err(e) = &result {
+        let denial = denial_text(ctx, inter.guild_id, e);
+
+        let content = denial
+            .clone()
+            .unwrap_or_else(|| ctx.text(inter.guild_id, "error", &[]));
+
+        if acknowledged {
+            if let Err(e) = ctx.interaction().delete_response(&inter.token).await {
+                debug!("Could not clear deferred response: {e}");
+            }
+
+            ctx.interaction()
+                .create_followup(&inter.token)
+                .flags(error_flags)
+                .content(&content)?
+                .await
+                .context("Failed to send error message")?;
+        } else {
+            // Nothing to clear, the error is the first response unless a modal was shown already.
+            let resp = InteractionResponse {
+                kind: InteractionResponseType::ChannelMessageWithSource,
+                data: Some(InteractionResponseData {
+                    content: Some(content),
+                    flags: Some(error_flags),
+                    ..Default::default()
+                }),
+            };
+            if let Err(e) = ctx
+                .interaction()
+                .create_response(inter.id, &inter.token, &resp)
+                .await
+            {
+                debug!("Could not send error response: {e}");
+            }
+        }
+
+        // A denial is not an error of the command.
+        if denial.is_some() {
+            return Ok(());
+        }
 
         return result
-            .with_context(|| format!("Error in application command '{name}'"))
+            .map(|_| ())
+            .with_context(|| error_context(kind, name, inter.author_id(), inter.guild_id))
             .map_err(Into::into);
     }
 
+    record_usage(ctx, inter.guild_id, name);
+
+    // Delete the response later, if the command wants that and responded.
+    if let (Some(delay), Ok(true)) = (auto_delete, result) {
+        let ctx = ctx.clone();
+        ctx.delayed.clone().spawn(delay, async move {
+            if let Err(e) = ctx.interaction().delete_response(&inter.token).await {
+                debug!("Could not delete response: {e}");
+            }
+        });
+    }
+
     Ok(())
 }
 
-/// Slash interaction commands.
-async fn process_slash(
+/// Error for an application command of a type that is not handled,
+/// such as a type that Discord added later. The deferred response is cleared like on any error.
+fn unsupported_kind(name: &str, kind: CommandType) -> CommandError {
+    warn!("Command '{name}' has an unsupported type: {kind:?}");
+    CommandError::NotFound(format!("Command '{name}' of type {kind:?}"))
+}
+
+/// Check if the interaction user can use the command here.
+/// Returns the text key of the denial, if the command should not be executed.
+async fn interaction_denial(
+    ctx: &Context,
+    base: &BaseCommand,
+    inter: &Interaction,
+) -> CommandResult<Option<&'static str>> {
+    let (Some(channel_id), Some(user_id)) =
+        (inter.channel.as_ref().map(|c| c.id), inter.author_id())
+    else {
+        return Ok(None); // Nothing to check against.
+    };
+
+    if ctx.in_maintenance() && !checks::is_owner(&ctx.application, user_id) {
+        return Ok(Some("maintenance"));
+    }
+
+    let roles = inter.member.as_ref().map_or(&[][..], |m| &m.roles);
+    let check = check_access(ctx, base, inter.guild_id, channel_id, user_id, roles).await?;
+    trace!(
+        "Access check for '{}': {:?}",
+        base.command.name,
+        check.trace
+    );
+
+    Ok(match check.access {
+        Access::Allowed => None,
+        Access::Disabled => Some("command-disabled"),
+        Access::Denied => Some("access-denied"),
+    })
+}
+
+/// Text to show the user for an error that denies the use of a command, if it is one.
+pub fn denial_text(
     ctx: &Context,
-    base: Arc<BaseCommand>,
-    inter: Arc<Interaction>,
-    data: Arc<CommandData>,
+    guild_id: Option<Id<GuildMarker>>,
+    error: &CommandError,
+) -> Option<String> {
+    match error {
+        CommandError::AccessDenied => Some(ctx.text(guild_id, "access-denied", &[])),
+        CommandError::Disabled => Some(ctx.text(guild_id, "command-disabled", &[])),
+        CommandError::Cooldown(left) => {
+            let seconds = (left.as_secs() + 1).to_string();
+            Some(ctx.text(guild_id, "on-cooldown", &[("seconds", &seconds)]))
+        },
+        CommandError::BotMissingPermissions(missing) => {
+            let permissions = format!("{missing:?}");
+            Some(ctx.text(guild_id, "bot-missing-permissions", &[(
+                "permissions",
+                &permissions,
+            )]))
+        },
+        _ => None,
+    }
+}
+
+/// Suggest choices of the focused string option, by what has been typed so far.
+pub async fn autocomplete(
+    ctx: &Context,
+    inter: Interaction,
+    data: CommandData,
 ) -> CommandResult<()> {
-    // Acknowledge the interaction.
-    public_acknowledge(ctx, inter.id, &inter.token).await?;
+    let mut path = vec![data.name.as_str()];
+    let mut opts = data.options.as_slice();
+
+    // Find the focused option, walking into subcommands and groups.
+    let (name, typed) = loop {
+        let mut nested = None;
+        let mut focused = None;
+
+        for opt in opts {
+            match &opt.value {
+                CommandOptionValue::SubCommand(next)
+                | CommandOptionValue::SubCommandGroup(next) => {
+                    nested = Some((opt.name.as_str(), next));
+                },
+                CommandOptionValue::Focused(value, _) => {
+                    focused = Some((opt.name.as_str(), value.to_lowercase()))
+                },
+                _ => (),
+            }
+        }
 
-    let mut args = Vec::new();
-    let mut last = Lookup::Command(&base.command);
-    let mut data_opts = data.options.to_vec();
-    let mut lookup_opts; // Declared here for lifetime reasons.
-
-    // Process interaction until last (sub)command is found.
-    // This processes options in reverse, it is fine however,
-    // because `CommandDataOption` is a nested structure and
-    // only holds one type of options in the `value` field.
-    while let Some(opt) = data_opts.pop() {
-        match opt.value {
-            CommandOptionValue::SubCommand(next) | CommandOptionValue::SubCommandGroup(next) => {
-                lookup_opts = match last {
-                    Lookup::Command(c) => c.options.to_vec(),
-                    Lookup::Group(g) => g.to_options(),
-                };
-
-                // lookup option from lookup_opts
-                let found = lookup_opts
-                    .iter()
-                    .filter_map(Lookup::from_option)
-                    .find(|s| s.name() == opt.name);
-
-                match found {
-                    Some(sub) => {
-                        data_opts = next.to_vec(); // Set next option to check.
-                        last = sub; // Set last command or group found.
-                    },
-                    None => {
-                        // TODO: This should return error.
-                        error!("Subcommand or group not found: {}", opt.name);
-                        panic!("Subcommand or group not found: {}", opt.name);
-                    },
-                }
-            },
-            arg => {
-                // Convert argument.
-                match ArgValue::try_from(arg.to_owned()) {
-                    Ok(mut arg) => {
-                        // Convert `string` type that should be `message` type.
-                        // (due to implementation of slash command args)
-                        if let Some(ArgDesc {
-                            kind: ArgKind::Message,
-                            ..
-                        }) = match last {
-                            Lookup::Command(c) => c.args().find(|a| a.name == opt.name),
-                            Lookup::Group(_) => None,
-                        } {
-                            if let Some(s) = arg.string() {
-                                arg = ArgValue::from_kind(&ArgKind::Message, &s)
-                                    .context("Failed to convert string to message type")?;
-                            }
-                        }
-
-                        // Args are still stored in reverse order.
-                        args.push(Arg {
-                            name: opt.name,
-                            value: arg,
-                        });
-                    },
-                    Err(e) => {
-                        // TODO: This should return error.
-                        error!("Could not process argument: '{}': {e}", arg.kind().kind());
-                        panic!("Could not process argument: '{}': {e}", arg.kind().kind());
-                    },
-                }
+        match (focused, nested) {
+            (Some(focused), _) => break focused,
+            (None, Some((name, next))) => {
+                path.push(name);
+                opts = next;
             },
+            (None, None) => return Err(CommandError::MissingArgs),
         }
+    };
+
+    let resolved = ctx
+        .commands
+        .resolve(&path)
+        .ok_or_else(|| CommandError::NotFound(format!("Command '{}'", path.join(" "))))?;
+
+    let choices = match resolved.target() {
+        Lookup::Command(c) => match c.args().find(|a| a.name == name) {
+            Some(ArgDesc {
+                kind: ArgKind::String(StringData { choices, .. }),
+                ..
+            }) => choices
+                .iter()
+                .filter(|(choice, _)| choice.to_lowercase().contains(&typed))
+                .take(MAX_CHOICES)
+                .map(|(choice, value)| CommandOptionChoice {
+                    name: choice.to_owned(),
+                    name_localizations: None,
+                    value: CommandOptionChoiceValue::String(value.to_owned()),
+                })
+                .collect(),
+            _ => Vec::new(),
+        },
+        Lookup::Group(_) => Vec::new(),
+    };
+
+    let resp = InteractionResponse {
+        kind: InteractionResponseType::ApplicationCommandAutocompleteResult,
+        data: Some(InteractionResponseData {
+            choices: Some(choices),
+            ..Default::default()
+        }),
+    };
+    ctx.interaction()
+        .create_response(inter.id, &inter.token, &resp)
+        .await
+        .context("Failed to send autocomplete choices")?;
+
+    Ok(())
+}
+
+/// Handle message component interaction, routed by the custom id prefix.
+pub async fn message_component(
+    ctx: &Context,
+    inter: Interaction,
+    data: MessageComponentInteractionData,
+) -> CommandResult<()> {
+    let prefix = data
+        .custom_id
+        .split_once(':')
+        .map_or(data.custom_id.as_str(), |(p, _)| p);
+
+    match prefix {
+        paginator::PREFIX => paginator::handle_component(ctx, &inter, &data).await?,
+        confirm::PREFIX => confirm::handle_component(ctx, &inter, &data).await?,
+        help::PREFIX => help::handle_component(ctx, &inter, &data).await?,
+        verify::PREFIX => verify::handle_component(ctx, &inter, &data).await?,
+        _ => debug!("Unhandled message component: '{}'", data.custom_id),
     }
 
-    // Reverse the args to the correct order for arbitrary reasons.
-    args.reverse();
+    Ok(())
+}
+
+/// Handle a modal submission by its custom id prefix.
+pub async fn modal_submit(
+    ctx: &Context,
+    inter: Interaction,
+    data: ModalInteractionData,
+) -> CommandResult<()> {
+    let prefix = data
+        .custom_id
+        .split_once(':')
+        .map_or(data.custom_id.as_str(), |(p, _)| p);
+
+    match prefix {
+        modals::PREFIX => modals::handle_submit(ctx, inter, data).await?,
+        _ => debug!("Unhandled modal submit: '{}'", data.custom_id),
+    }
+
+    Ok(())
+}
+
+/// Request of an application command, parsed and checked before it is acknowledged.
+enum Prepared {
+    Slash(ResolvedCommand, SlashRequest),
+    Message(MessageRequest),
+    User(UserRequest),
+}
+
+/// Build the request of an application command by its kind, then run the command checks
+/// and check that the bot has the permissions for the (sub)command.
+async fn prepare(
+    ctx: &Context,
+    base: &Arc<BaseCommand>,
+    inter: &Arc<Interaction>,
+    data: &Arc<CommandData>,
+) -> CommandResult<Prepared> {
+    let mut required = base.bot_permissions.unwrap_or_else(Permissions::empty);
+    let prepared = match data.kind {
+        CommandType::ChatInput => {
+            let (resolved, args) = slash_options(
+                &ctx.commands,
+                &data.name,
+                &data.options,
+                data.resolved.as_ref(),
+            )?;
+            let req = SlashRequest::new(
+                Arc::clone(base),
+                Arc::clone(inter),
+                Arc::clone(data),
+                Args::from(args),
+            );
+            required = resolved.bot_permissions();
+            Prepared::Slash(resolved, req)
+        },
+        CommandType::Message => {
+            let target = data.target_id.ok_or(CommandError::MissingArgs)?.cast();
+            let req = MessageRequest::new(
+                Arc::clone(base),
+                Arc::clone(inter),
+                Arc::clone(data),
+                target,
+            );
+            Prepared::Message(req)
+        },
+        CommandType::User => {
+            let target = data.target_id.ok_or(CommandError::MissingArgs)?.cast();
+            let req = UserRequest::new(
+                Arc::clone(base),
+                Arc::clone(inter),
+                Arc::clone(data),
+                target,
+            );
+            Prepared::User(req)
+        },
+        other => return Err(unsupported_kind(base.command.name, other)),
+    };
+
+    let req = match &prepared {
+        Prepared::Slash(_, req) => req.clone().into(),
+        Prepared::Message(req) => req.clone().into(),
+        Prepared::User(req) => req.clone().into(),
+    };
+    checks::run(ctx, &base.checks, &req)?;
+    check_bot_permissions(ctx, required, &req).await?;
+
+    Ok(prepared)
+}
 
-    let funcs = last
+/// Slash interaction commands.
+async fn process_slash(
+    ctx: &Context,
+    base: &BaseCommand,
+    resolved: &ResolvedCommand,
+    req: SlashRequest,
+) -> CommandResult<bool> {
+    let funcs = resolved
+        .target()
         .slash_functions()
         .context("Failed to get slash functions")?;
 
-    let req = SlashRequest::new(
-        Arc::clone(&base),
-        Arc::clone(&inter),
-        data,
-        Args::from(args),
+    let key = cache_key(
+        base,
+        &req.clone().into(),
+        &resolved.path().join(" "),
+        &req.args,
     );
+    execute(ctx, funcs, req, base, key).await
+}
+
+/// Find the last (sub)command and collect its arguments from interaction options.
+///
+/// Subcommands and groups are nested options, so each level is walked in order and
+/// any arguments found along the way are collected, regardless of their position.
+fn slash_options(
+    commands: &Commands,
+    name: &str,
+    options: &[CommandDataOption],
+    data: Option<&CommandInteractionDataResolved>,
+) -> CommandResult<(ResolvedCommand, Vec<Arg>)> {
+    let mut args = Vec::new();
+    let mut path = vec![name];
+    let mut opts = options;
+
+    loop {
+        let resolved = commands
+            .resolve(&path)
+            .ok_or_else(|| CommandError::NotFound(format!("Command '{}'", path.join(" "))))?;
+        let mut nested = None;
+
+        for opt in opts {
+            match &opt.value {
+                CommandOptionValue::SubCommand(next)
+                | CommandOptionValue::SubCommandGroup(next) => {
+                    if nested.replace((opt.name.as_str(), next)).is_some() {
+                        return Err(CommandError::UnexpectedArgs(format!(
+                            "Multiple subcommands or groups found in '{}'",
+                            path.join(" ")
+                        )));
+                    }
+                },
+                value => args.push(slash_arg(resolved.target(), &opt.name, value, data)?),
+            }
+        }
+
+        let Some((name, next)) = nested else {
+            return Ok((resolved, args));
+        };
 
-    execute(ctx, funcs, req).await
+        path.push(name);
+        opts = next;
+    }
+}
+
+/// Convert an interaction option value to an argument of the (sub)command.
+fn slash_arg(
+    last: Lookup,
+    name: &str,
+    value: &CommandOptionValue,
+    data: Option<&CommandInteractionDataResolved>,
+) -> CommandResult<Arg> {
+    // Mentionables need the resolved data to tell users and roles apart.
+    if let CommandOptionValue::Mentionable(id) = value {
+        let mention = Mentionable::resolve(*id, data).ok_or_else(|| {
+            CommandError::UnexpectedArgs(format!(
+                "Could not resolve argument '{name}' as a user or a role"
+            ))
+        })?;
+        return Ok(Arg {
+            name: name.to_string(),
+            value: ArgValue::Mention(mention),
+        });
+    }
+
+    let mut arg = ArgValue::try_from(value.to_owned()).map_err(|e| {
+        CommandError::UnexpectedArgs(format!(
+            "Could not process argument '{name}' of type '{}': {e}",
+            value.kind().kind()
+        ))
+    })?;
+
+    let desc = match last {
+        Lookup::Command(c) => c.args().find(|a| a.name == name),
+        Lookup::Group(_) => None,
+    };
+
+    // Use the resolved attachment, which has the file details.
+    if let ArgValue::Attachment(Ref::Id(id)) = arg {
+        if let Some(a) = data.and_then(|d| d.attachments.get(&id)) {
+            arg = ArgValue::Attachment(Ref::from_obj(a.to_owned()));
+        }
+    }
+    validate_attachment(desc, &arg)?;
+
+    // Convert `string` type that should be `message` type.
+    // (due to implementation of slash command args)
+    if let Some(ArgDesc {
+        kind: ArgKind::Message,
+        ..
+    }) = desc
+    {
+        if let Some(s) = arg.string() {
+            arg = ArgValue::from_kind(&ArgKind::Message, &s)
+                .context("Failed to convert string to message type")?;
+        }
+    }
+
+    Ok(Arg {
+        name: name.to_string(),
+        value: arg,
+    })
 }
 
 // TODO: See if any twilight resolved data can be used as objects instead of ids.
 /// Message GUI interaction commands.
 async fn process_message(
     ctx: &Context,
-    base: Arc<BaseCommand>,
-    inter: Arc<Interaction>,
-    data: Arc<CommandData>,
-) -> CommandResult<()> {
-    // Acknowledge the interaction.
-    ephemeral_acknowledge(ctx, inter.id, &inter.token).await?;
-
+    base: &BaseCommand,
+    req: MessageRequest,
+) -> CommandResult<bool> {
     // let data = data.resolved.as_ref().expect("Empty resolve error");
     // for _message in &data.messages {} // Globally.
 
-    let target = data.target_id.ok_or(CommandError::MissingArgs)?.cast();
-    let req = MessageRequest::new(Arc::clone(&base), inter, data, target);
-    execute(ctx, base.command.message(), req).await
+    let funcs = Lookup::Command(&base.command)
+        .message_functions()
+        .context("Failed to get message functions")?;
+
+    let key = cache_key(base, &req.clone().into(), base.command.name, req.target_id);
+    execute(ctx, funcs, req, base, key).await
 }
 
 // TODO: See if any twilight resolved data can be used as objects instead of ids.
 /// User GUI interaction commands.
-async fn process_user(
-    ctx: &Context,
-    base: Arc<BaseCommand>,
-    inter: Arc<Interaction>,
-    data: Arc<CommandData>,
-) -> CommandResult<()> {
-    // Acknowledge the interaction.
-    ephemeral_acknowledge(ctx, inter.id, &inter.token).await?;
-
+async fn process_user(ctx: &Context, base: &BaseCommand, req: UserRequest) -> CommandResult<bool> {
     // let data = data.resolved.as_ref().expect("Empty resolve error");
     // for _user in &data.users {} // Globally.
     // for _member in &data.members {} // Guilds only.
 
-    let target = data.target_id.ok_or(CommandError::MissingArgs)?.cast();
-    let req = UserRequest::new(Arc::clone(&base), inter, data, target);
-    execute(ctx, base.command.user(), req).await
+    let funcs = Lookup::Command(&base.command)
+        .user_functions()
+        .context("Failed to get user functions")?;
+
+    let key = cache_key(base, &req.clone().into(), base.command.name, req.target_id);
+    execute(ctx, funcs, req, base, key).await
 }
 
+/// Discord error code for an interaction that has already been acknowledged.
+const ALREADY_ACKNOWLEDGED: u64 = 40060;
+
 /// Creates a publicly visible loading state message.
 pub async fn public_acknowledge(
     ctx: &Context,
@@ -249,9 +634,15 @@ pub async fn ephemeral_acknowledge(
 
 /// Parse message and execute command functions.
 pub async fn classic_command(ctx: &Context, msg: Arc<Message>) -> CommandResult<()> {
-    // Unprefix the message contents.
+    // Unprefix the message contents, direct messages can optionally skip the prefix.
     let prefix = ctx.config.classic_prefix(msg.guild_id)?;
-    let Some((_, unprefixed)) = parser::unprefix_with([prefix], &msg.content) else {
+    let no_prefix = msg.guild_id.is_none() && ctx.config.global().dm_no_prefix()?;
+    let prefixes: &[&str] = if no_prefix {
+        &[prefix.as_ref(), ""]
+    } else {
+        &[prefix.as_ref()]
+    };
+    let Some((used, unprefixed)) = parser::unprefix_with(prefixes, &msg.content) else {
         return Err(CommandError::NotPrefixed);
     };
 
@@ -262,57 +653,75 @@ pub async fn classic_command(ctx: &Context, msg: Arc<Message>) -> CommandResult<
     }
 
     // Lookup command from context.
-    let Some(base) = ctx.commands.get(name) else {
+    let mut path = vec![name];
+    let Some(mut resolved) = ctx.commands.resolve(&path) else {
+        if used.is_empty() {
+            return Err(CommandError::NotPrefixed); // Just a message, not a command.
+        }
         return Err(CommandError::NotFound(format!(
             "Command '{name}' does not exist"
         )));
     };
 
-    // Check if command should run in DMs.
-    if !base.dm_enabled && msg.guild_id.is_none() {
-        return Err(CommandError::Disabled);
-    }
+    // Only owners can use commands during maintenance.
+    if ctx.in_maintenance() && !checks::is_owner(&ctx.application, msg.author.id) {
+        ctx.http
+            .create_message(msg.channel_id)
+            .reply(msg.id)
+            .content(&ctx.text(msg.guild_id, "maintenance", &[]))?
+            .await?;
 
-    // Continue with access if there is no permission requirements.
-    if let Some(perms) = base.member_permissions {
-        // Return with error if the user does not have the permissions.
-        if !sender_has_permissions(ctx, &msg, perms).await? {
-            return Err(CommandError::AccessDenied);
-        }
+        return Ok(());
     }
 
-    let base = Arc::new(base.to_owned());
-    let mut lookup = Lookup::Command(&base.command);
+    // Check if the sender can use the command here.
+    let roles = msg.member.as_ref().map_or(&[][..], |m| &m.roles);
+    let check = check_access(
+        ctx,
+        &resolved.base,
+        msg.guild_id,
+        msg.channel_id,
+        msg.author.id,
+        roles,
+    )
+    .await?;
+    match check.access {
+        Access::Allowed => (),
+        Access::Disabled => return Err(CommandError::Disabled),
+        Access::Denied => return Err(CommandError::AccessDenied),
+    }
 
     // Parse contents until last (sub)command is found.
     loop {
         let (name, next) = parser::split_once_whitespace(rest.unwrap_or(""));
+        path.push(name);
 
-        let found = match lookup {
-            Lookup::Command(f) => f
-                .options
-                .iter()
-                .filter_map(Lookup::from_option)
-                .find(|t| t.name() == name),
-            Lookup::Group(g) => g.subs.iter().find(|s| s.name == name).map(Lookup::Command),
-        };
-
-        if let Some(t) = found {
-            lookup = t;
-            rest = next;
-            continue;
+        match ctx.commands.resolve(&path) {
+            Some(r) => {
+                resolved = r;
+                rest = next;
+            },
+            None => break,
         }
-
-        break;
     }
 
+    let lookup = resolved.target();
+
     let args = match lookup {
-        Lookup::Command(c) => parse_classic_args(c, &msg, rest)?,
-        Lookup::Group(g) => {
-            return Err(CommandError::UnexpectedArgs(format!(
-                "Expected command, found group '{}'",
-                g.name
-            )));
+        Lookup::Command(c) if !needs_subcommand(lookup) => {
+            let max_args = ctx.config.global().max_classic_args()?;
+            let members = member_names(ctx, &msg, c);
+            parse_classic_args(c, &msg, rest, max_args, members.as_ref())?
+        },
+        _ => {
+            // Only the subcommands can be executed, so show what there are instead.
+            ctx.http
+                .create_message(msg.channel_id)
+                .reply(msg.id)
+                .content(&group_usage(&resolved))?
+                .await?;
+
+            return Ok(());
         },
     };
 
@@ -325,11 +734,48 @@ pub async fn classic_command(ctx: &Context, msg: Arc<Message>) -> CommandResult<
         msg.author.id
     );
 
-    let req = ClassicRequest::new(Arc::clone(&base), Arc::clone(&msg), args);
+    let mut req = ClassicRequest::new(Arc::clone(&resolved.base), Arc::clone(&msg), args);
+    checks::run(ctx, &resolved.base.checks, &req.clone().into())?;
+    check_bot_permissions(ctx, resolved.bot_permissions(), &req.clone().into()).await?;
+
+    // Let the user know that the command is being worked on.
+    let placeholder = if resolved.base.defer {
+        let reply = ctx
+            .http
+            .create_message(msg.channel_id)
+            .reply(msg.id)
+            .content("Working on it... ⏳")?
+            .send()
+            .await
+            .context("Failed to post placeholder")?;
+        let placeholder = Arc::new(Placeholder::new(reply.id));
+        req = req.with_placeholder(Arc::clone(&placeholder));
+        Some(placeholder)
+    } else {
+        None
+    };
 
     debug!("Executing '{name}' by user '{}'", msg.author.id);
 
-    let result = execute(ctx, funcs, req).await;
+    let key = cache_key(
+        &resolved.base,
+        &req.clone().into(),
+        &resolved.path().join(" "),
+        &req.args,
+    );
+    let sent = req.clone();
+    let result = execute(ctx, funcs, req, &resolved.base, key).await;
+
+    // Remove the placeholder if it was not replaced by a response.
+    if let Some(placeholder) = placeholder.filter(|p| !p.is_used()) {
+        if let Err(e) = ctx
+            .http
+            .delete_message(msg.channel_id, placeholder.id)
+            .await
+        {
+            warn!("Failed to delete placeholder: {e}");
+        }
+    }
 
     trace!("Completing '{name}' by user '{}'", msg.author.id);
 
@@ -337,18 +783,191 @@ pub async fn classic_command(ctx: &Context, msg: Arc<Message>) -> CommandResult<
     if result.is_err() {
         ctx.http
             .create_message(msg.channel_id)
-            .content(ERROR_MESSAGE)?
+            .content(&ctx.text(msg.guild_id, "error", &[]))?
             .await
             .context("Failed to send error message")?;
 
         return result
-            .with_context(|| format!("Error in classic command '{name}'"))
+            .map(|_| ())
+            .with_context(|| {
+                error_context(
+                    "classic",
+                    &resolved.path().join(" "),
+                    Some(msg.author.id),
+                    msg.guild_id,
+                )
+            })
             .map_err(Into::into);
     }
 
+    record_usage(ctx, msg.guild_id, resolved.base.command.name);
+
+    // Delete the replies later, if the command wants that.
+    if let Some((delay, replies)) = auto_deleted_replies(&resolved.base, &sent, &result) {
+        let (http, channel_id) = (Arc::clone(&ctx.http), msg.channel_id);
+        ctx.delayed.spawn(delay, async move {
+            for message_id in replies {
+                if let Err(e) = http.delete_message(channel_id, message_id).await {
+                    debug!("Could not delete reply '{message_id}': {e}");
+                }
+            }
+        });
+    }
+
     Ok(())
 }
 
+/// Replies of a classic command to delete and the delay, if the command wants that.
+/// Nothing is deleted if the command failed or responded with [`Response::none`].
+fn auto_deleted_replies(
+    base: &BaseCommand,
+    req: &ClassicRequest,
+    result: &CommandResult<bool>,
+) -> Option<(Duration, Vec<Id<MessageMarker>>)> {
+    let (Some(delay), Ok(true)) = (base.auto_delete, result) else {
+        return None;
+    };
+
+    let replies = req.replies();
+    (!replies.is_empty()).then_some((delay, replies))
+}
+
+/// Describe which invocation of a command failed, eg. by whom and where.
+fn error_context(
+    kind: &str,
+    path: &str,
+    user_id: Option<Id<UserMarker>>,
+    guild_id: Option<Id<GuildMarker>>,
+) -> String {
+    let user = user_id.map_or_else(|| "unknown user".to_string(), |id| format!("user '{id}'"));
+    let place = guild_id.map_or_else(|| "DMs".to_string(), |id| format!("guild '{id}'"));
+    format!("Error in {kind} command '{path}' by {user} in {place}")
+}
+
+/// Count a successful command execution towards the guild usage statistics.
+fn record_usage(ctx: &Context, guild_id: Option<Id<GuildMarker>>, name: &str) {
+    let Some(guild_id) = guild_id else {
+        return;
+    };
+
+    if let Err(e) = ctx.config.guild(guild_id).add_command_usage(name) {
+        warn!("Failed to record usage of '{name}': {e}");
+    }
+}
+
+/// Decision of an access check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Access {
+    Allowed,
+    /// The command is not available in this context.
+    Disabled,
+    /// The user does not have the required permissions.
+    Denied,
+}
+
+/// Result of an access check, with the steps that lead to the decision.
+#[derive(Debug, Clone)]
+pub struct AccessCheck {
+    pub access: Access,
+    pub trace: Vec<String>,
+}
+
+/// Check if a user with `roles` can use a command in a channel.
+/// This is the same check that is done before executing classic commands.
+pub async fn check_access(
+    ctx: &Context,
+    base: &BaseCommand,
+    guild_id: Option<Id<GuildMarker>>,
+    channel_id: Id<ChannelMarker>,
+    user_id: Id<UserMarker>,
+    roles: &[Id<RoleMarker>],
+) -> CommandResult<AccessCheck> {
+    let mut trace = Vec::new();
+    let done = |access, trace| Ok(AccessCheck { access, trace });
+
+    // Check if command should run in DMs.
+    let Some(guild_id) = guild_id else {
+        if base.dm_enabled {
+            trace.push("Not in a guild, command is enabled in DMs".to_string());
+            return done(Access::Allowed, trace);
+        }
+        trace.push("Not in a guild, command is disabled in DMs".to_string());
+        return done(Access::Disabled, trace);
+    };
+
+    // Check if commands are disabled in the channel.
+    let disabled_channels = ctx.config.guild(guild_id).disabled_channels()?;
+    if disabled_channels.contains(&channel_id) {
+        trace.push("Commands are disabled in the channel".to_string());
+        return done(Access::Disabled, trace);
+    }
+
+    // Continue with access if there is no permission requirements.
+    let Some(required) = base.member_permissions else {
+        trace.push("No member permissions required".to_string());
+        return done(Access::Allowed, trace);
+    };
+
+    trace.push(format!("Member permissions required: {required:?}"));
+
+    let perms = member_channel_permissions(ctx, guild_id, channel_id, user_id, roles).await?;
+    if perms.contains(required) {
+        trace.push("Member has the required permissions in the channel".to_string());
+        done(Access::Allowed, trace)
+    } else {
+        trace.push(format!(
+            "Member is missing permissions in the channel: {:?}",
+            required.difference(perms)
+        ));
+        done(Access::Denied, trace)
+    }
+}
+
+/// Check that the bot has the `required` permissions in the channel.
+async fn check_bot_permissions(
+    ctx: &Context,
+    required: Permissions,
+    req: &Request,
+) -> CommandResult<()> {
+    let (false, Some(guild_id), Some(channel_id)) =
+        (required.is_empty(), req.guild_id(), req.channel_id())
+    else {
+        return Ok(()); // Nothing to check.
+    };
+
+    let perms = match req.app_permissions() {
+        Some(perms) => perms,
+        None => bot_channel_permissions(ctx, guild_id, channel_id).await?,
+    };
+
+    let missing = required.difference(perms);
+    if missing.is_empty() {
+        Ok(())
+    } else {
+        Err(CommandError::BotMissingPermissions(missing))
+    }
+}
+
+/// Calculate the permissions of the bot in a channel.
+pub async fn bot_channel_permissions(
+    ctx: &Context,
+    guild_id: Id<GuildMarker>,
+    channel_id: Id<ChannelMarker>,
+) -> CommandResult<Permissions> {
+    let roles = match ctx.cache.member(guild_id, ctx.user.id) {
+        Some(m) => m.roles().to_vec(),
+        None => {
+            ctx.http
+                .guild_member(guild_id, ctx.user.id)
+                .send()
+                .await?
+                .roles
+        },
+    };
+
+    member_channel_permissions(ctx, guild_id, channel_id, ctx.user.id, &roles).await
+}
+
 /// Calculate if the message sender has the `required` permissions.
 pub async fn sender_has_permissions(
     ctx: &Context,
@@ -364,12 +983,27 @@ pub async fn sender_has_permissions(
         return Ok(true); // Return true if not in a guild.
     };
 
+    Ok(
+        member_channel_permissions(ctx, *guild_id, msg.channel_id, msg.author.id, &member.roles)
+            .await?
+            .contains(required),
+    )
+}
+
+/// Calculate the permissions of a member with `roles` in a channel.
+pub async fn member_channel_permissions(
+    ctx: &Context,
+    guild_id: Id<GuildMarker>,
+    channel_id: Id<ChannelMarker>,
+    user_id: Id<UserMarker>,
+    roles: &[Id<RoleMarker>],
+) -> CommandResult<Permissions> {
     // `@everyone` role id is the same as the guild's id.
     let everyone_id = guild_id.cast();
 
     // Permissions that are given by `@everyone` role
     let everyone_perm = ctx
-        .roles_from(*guild_id, &[everyone_id])
+        .roles_from(guild_id, &[everyone_id])
         .await?
         .pop()
         .ok_or_else(|| anyhow::anyhow!("'@everyone' role not found"))?
@@ -377,7 +1011,7 @@ pub async fn sender_has_permissions(
 
     // The member's assigned roles' ids.
     let roles: Vec<_> = ctx
-        .roles_from(*guild_id, &member.roles)
+        .roles_from(guild_id, roles)
         .await?
         .into_iter()
         // Map roles into a `PermissionCalculator` happy format.
@@ -385,28 +1019,91 @@ pub async fn sender_has_permissions(
         .collect();
 
     // Create a calculator.
-    let calc = PermissionCalculator::new(*guild_id, msg.author.id, everyone_perm, &roles);
+    let calc = PermissionCalculator::new(guild_id, user_id, everyone_perm, &roles);
 
     // Get the channel in which the message was sent.
-    let channel = ctx.channel_from(msg.channel_id).await?;
+    let channel = ctx.channel_from(channel_id).await?;
 
     // Get channel specific permission overwrites.
     let overwrites = channel.permission_overwrites.unwrap_or_default();
 
-    Ok(calc
-        .in_channel(channel.kind, &overwrites)
-        .contains(required))
+    Ok(calc.in_channel(channel.kind, &overwrites))
+}
+
+/// Returns `true` if only the subcommands of the target can be executed,
+/// which is the case for groups and commands with subcommands but no arguments.
+fn needs_subcommand(lookup: Lookup) -> bool {
+    match lookup {
+        Lookup::Group(_) => true,
+        Lookup::Command(c) => !c.options.is_empty() && c.args().next().is_none(),
+    }
+}
+
+/// Usage message for a group or command that was called without a subcommand.
+fn group_usage(resolved: &ResolvedCommand) -> String {
+    format!(
+        "Expected a subcommand of '{}':\n{}",
+        resolved.path().join(" "),
+        resolved.generate_help()
+    )
+}
+
+/// Names of the cached guild members, if the command takes user arguments in a guild.
+/// The names are only collected if a user argument is given as a plain name.
+fn member_names<'a>(
+    ctx: &'a Context,
+    msg: &Message,
+    cmd_fn: &CommandFunction,
+) -> Option<MemberNames<'a>> {
+    let guild_id = msg.guild_id?;
+    if !cmd_fn.args().any(|a| matches!(a.kind, ArgKind::User)) {
+        return None;
+    }
+
+    Some(MemberNames::new(move || {
+        let Some(user_ids) = ctx.cache.guild_members(guild_id) else {
+            return Vec::new();
+        };
+
+        user_ids
+            .iter()
+            .filter_map(|&user_id| {
+                let user = ctx.cache.user(user_id)?;
+                let nick = ctx
+                    .cache
+                    .member(guild_id, user_id)
+                    .and_then(|m| m.nick().map(str::to_string));
+                Some(MemberName {
+                    id: user_id,
+                    name: user.name.to_owned(),
+                    discriminator: user.discriminator,
+                    global_name: user.global_name.to_owned(),
+                    nick,
+                })
+            })
+            .collect()
+    }))
 }
 
 fn parse_classic_args(
     cmd_fn: &CommandFunction,
     msg: &Message,
     rest: Option<&str>,
+    max_args: usize,
+    members: Option<&MemberNames>,
 ) -> Result<Args, CommandError> {
-    let mut parsed = Vec::new();
     let args: Vec<_> = cmd_fn.args().collect();
-    let mut split = args.iter().position(|a| !a.required).unwrap_or(args.len());
-    let mut parser = MessageParser::new(msg, rest);
+
+    // Take named arguments out first, the rest are parsed by position.
+    let (mut parsed, rest) = take_named_args(&args, rest.unwrap_or(""), max_args, members)?;
+    let args: Vec<_> = args
+        .into_iter()
+        .filter(|a| !parsed.iter().any(|p| p.name == a.name))
+        .collect();
+    let rest = Some(rest.as_str()).filter(|r| !r.trim().is_empty());
+
+    let split = args.iter().position(|a| !a.required).unwrap_or(args.len());
+    let mut parser = MessageParser::new(msg, rest, members);
 
     // TODO: Generate help for this.
     // Process all the required args.
@@ -426,37 +1123,158 @@ fn parse_classic_args(
         };
 
         parsed.push(arg);
-        split += 1;
+    }
+
+    for arg in &parsed {
+        validate_attachment(cmd_fn.args().find(|a| a.name == arg.name), &arg.value)?;
     }
 
     Ok(Args::from(parsed))
 }
 
+/// Check an attachment argument against the size and type limits of its description.
+fn validate_attachment(desc: Option<&ArgDesc>, value: &ArgValue) -> Result<(), CommandError> {
+    match (desc.map(|d| &d.kind), value) {
+        (Some(ArgKind::Attachment(data)), ArgValue::Attachment(Ref::Obj(a))) => data.validate(a),
+        _ => Ok(()),
+    }
+}
+
+/// Parse arguments given by name or alias, as `name:value` or `--name value`.
+/// Bool arguments can be given as flags, eg. `--name` alone means `true`.
+/// Text for a rest string argument is kept as is, so names are not looked for in it.
+/// Fails if the text has more than `max_args` arguments.
+/// Returns the named arguments and the text without them.
+fn take_named_args(
+    args: &[&ArgDesc],
+    mut input: &str,
+    max_args: usize,
+    members: Option<&MemberNames>,
+) -> Result<(Vec<Arg>, String), CommandError> {
+    let mut named = Vec::new();
+    let mut rest = String::new();
+    let mut count = 0;
+
+    let find = |key: &str| args.iter().find(|a| a.is_named(key)).copied();
+
+    // Position of the rest string among the arguments that are left for the text.
+    let rest_position = |named: &[Arg]| {
+        args.iter()
+            .filter(|a| !matches!(a.kind, ArgKind::Attachment(_)))
+            .filter(|a| !named.iter().any(|n| n.name == a.name))
+            .position(|a| matches!(a.kind, ArgKind::String(StringData { rest: true, .. })))
+    };
+    let mut positional = 0;
+
+    loop {
+        let trimmed = input.trim_start();
+        if trimmed.is_empty() {
+            break;
+        }
+
+        if rest_position(&named) == Some(positional) {
+            rest.push_str(trimmed);
+            break;
+        }
+
+        if count >= max_args {
+            return Err(CommandError::UnexpectedArgs(format!(
+                "Too many arguments, up to {max_args} allowed"
+            )));
+        }
+        count += 1;
+
+        let (word, after_word) = parser::split_once_whitespace(trimmed);
+        let found = match word.strip_prefix("--") {
+            Some(key) => find(key).map(|desc| (desc, after_word.unwrap_or(""), true)),
+            None => word
+                .split_once(':')
+                .filter(|(_, value)| !value.is_empty())
+                .and_then(|(key, _)| {
+                    find(key).map(|desc| (desc, &trimmed[key.len() + 1..], false))
+                }),
+        };
+
+        let Some((desc, value_text, is_flag)) = found else {
+            // Not named, keep it for positional parsing.
+            let (_, next) = parser::maybe_quoted_arg(trimmed)
+                .map_err(|e| CommandError::ParseError(e.to_string()))?;
+            let end = trimmed.len() - next.map_or(0, str::len);
+            rest.push_str(trimmed[..end].trim_end());
+            rest.push(' ');
+            input = next.unwrap_or("");
+            positional += 1;
+            continue;
+        };
+
+        if named.iter().any(|a: &Arg| a.name == desc.name) {
+            return Err(CommandError::UnexpectedArgs(format!(
+                "Argument '{}' was given more than once",
+                desc.name
+            )));
+        }
+
+        // A bool flag without an explicit value.
+        let explicit = parser::maybe_quoted_arg(value_text).ok();
+        let (value, next) = match explicit {
+            Some((v, next)) if !is_flag || !matches!(desc.kind, ArgKind::Bool) => (v, next),
+            Some((v, next)) if v.parse::<bool>().is_ok() => (v, next),
+            _ if matches!(desc.kind, ArgKind::Bool) => ("true", Some(value_text)),
+            _ => return Err(CommandError::MissingArgs),
+        };
+
+        let value = ArgValue::from_kind_in(&desc.kind, value, members).map_err(|e| {
+            CommandError::UnexpectedArgs(format!("Invalid argument '{}': {e}", desc.name))
+        })?;
+        named.push(Arg {
+            name: desc.name.to_string(),
+            value,
+        });
+        input = next.unwrap_or("");
+    }
+
+    Ok((named, rest))
+}
+
 /// Helper type for parsing args from a chat message.
 struct MessageParser<'a> {
     msg: &'a Message,
     rest: Option<&'a str>,
     attachment_idx: usize,
+    reply_author_used: bool,
+    members: Option<&'a MemberNames<'a>>,
 }
 
 impl<'a> MessageParser<'a> {
-    const fn new(msg: &'a Message, rest: Option<&'a str>) -> Self {
+    const fn new(
+        msg: &'a Message,
+        rest: Option<&'a str>,
+        members: Option<&'a MemberNames<'a>>,
+    ) -> Self {
         Self {
             msg,
             rest,
             attachment_idx: 0,
+            reply_author_used: false,
+            members,
         }
     }
 
     /// Parse next argument with parser. Tries special parsing first, then baseline parsing.
+    /// A required user argument missing from the text is taken from the replied message, if any.
     fn parse_next(&mut self, desc: &ArgDesc) -> AnyResult<Arg> {
         self.parse_special(&desc.kind)
             .context("Special arg parsing error")
             .and_then(|v| {
                 v.map_or_else(
                     || {
+                        let missing = self.rest.is_none();
                         self.parse_baseline(&desc.kind)
                             .context("Baseline arg parsing error")
+                            .or_else(|e| match self.parse_reply(&desc.kind) {
+                                Some(value) if missing && desc.required => Ok(value),
+                                _ => Err(e),
+                            })
                     },
                     Ok,
                 )
@@ -476,14 +1294,8 @@ impl<'a> MessageParser<'a> {
     /// Try to parse a special argument from message.
     fn parse_special(&mut self, kind: &ArgKind) -> AnyResult<Option<ArgValue>> {
         match kind {
-            ArgKind::Message => self
-                .msg
-                .referenced_message
-                .as_ref()
-                .map_or(Ok(None), |replied| {
-                    Ok(Some(ArgValue::Message(Ref::from_obj(*replied.to_owned()))))
-                }),
-            ArgKind::Attachment => {
+            ArgKind::Message => Ok(self.parse_reply(kind)),
+            ArgKind::Attachment(_) => {
                 let result = self
                     .msg
                     .attachments
@@ -498,70 +1310,94 @@ impl<'a> MessageParser<'a> {
         }
     }
 
+    /// Take an argument from the replied message, the message itself or its author.
+    /// The author is only taken once, for the first user argument that needs it.
+    fn parse_reply(&mut self, kind: &ArgKind) -> Option<ArgValue> {
+        let replied = self.msg.referenced_message.as_ref()?;
+        match kind {
+            ArgKind::Message => Some(ArgValue::Message(Ref::from_obj(*replied.to_owned()))),
+            ArgKind::User if !self.reply_author_used => {
+                self.reply_author_used = true;
+                Some(ArgValue::User(Ref::from_obj(replied.author.to_owned())))
+            },
+            _ => None,
+        }
+    }
+
     // Parse text as a normal argument.
     fn parse_baseline(&mut self, kind: &ArgKind) -> AnyResult<ArgValue> {
         let unparsed = self.rest.ok_or(CommandError::MissingArgs)?;
+
+        // Take everything that is left.
+        if let ArgKind::String(StringData { rest: true, .. }) = kind {
+            let value = unparsed.trim();
+            anyhow::ensure!(!value.is_empty(), CommandError::MissingArgs);
+            self.rest = None;
+            return ArgValue::from_kind_in(kind, value, self.members);
+        }
+
         let (value, next) = parser::maybe_quoted_arg(unparsed)
             .with_context(|| format!("Failed to parse next argument from content '{unparsed}'"))?;
         self.rest = next;
-        ArgValue::from_kind(kind, value)
+        ArgValue::from_kind_in(kind, value, self.members)
     }
 }
 
-enum Lookup<'a> {
-    Command(&'a CommandFunction),
-    Group(&'a CommandGroup),
-}
-
-impl<'a> Lookup<'a> {
-    const fn from_option(opt: &'a CommandOption) -> Option<Self> {
-        match opt {
-            CommandOption::Sub(s) => Some(Self::Command(s)),
-            CommandOption::Group(g) => Some(Self::Group(g)),
-            _ => None,
-        }
-    }
-
-    const fn name(&self) -> &str {
-        match self {
-            Lookup::Command(t) => t.name,
-            Lookup::Group(t) => t.name,
-        }
-    }
-
-    fn classic_functions(&self) -> AnyResult<impl Iterator<Item = ClassicFunction> + '_> {
-        match self {
-            Lookup::Command(c) if c.has_classic() => Ok(c.classic()),
-            Lookup::Command(c) => {
-                anyhow::bail!("No classic commands found for command call: '{}'", c.name)
-            },
-            Lookup::Group(g) => {
-                // TODO: This should be usage error or considered as an arg to previous command.
-                anyhow::bail!("Expected a subcommand, found group: '{}'", g.name)
-            },
-        }
+/// Execute tasks, then run the after hooks of the command with the result.
+/// Returns `true` if anything other than [`Response::none`] was responded.
+async fn execute<I, F, R>(
+    ctx: &Context,
+    funcs: I,
+    req: R,
+    base: &BaseCommand,
+    key: Option<String>,
+) -> CommandResult<bool>
+where
+    I: Iterator<Item = F> + Send,
+    F: Callable<(Context, R)>,
+    R: Clone + Send + Into<Request>,
+{
+    if base.after.is_empty() {
+        return execute_functions(ctx, funcs, req, base, key).await;
     }
 
-    fn slash_functions(&self) -> AnyResult<impl Iterator<Item = SlashFunction> + '_> {
-        match self {
-            Lookup::Command(c) if c.has_slash() => Ok(c.slash()),
-            Lookup::Command(c) => {
-                anyhow::bail!("No slash commands found for command call: '{}'", c.name)
-            },
-            Lookup::Group(g) => {
-                anyhow::bail!("Expected a subcommand, found group: '{}'", g.name)
-            },
-        }
-    }
+    let summary = req.clone().into();
+    let (result, responded) = match execute_functions(ctx, funcs, req, base, key).await {
+        Ok(responded) => (Ok(()), responded),
+        Err(e) => (Err(e), false),
+    };
+    hooks::run(ctx, &base.after, &summary, &result);
+    result.map(|()| responded)
 }
 
 /// Execute tasks.
-async fn execute<I, F, R>(ctx: &Context, funcs: I, req: R) -> CommandResult<()>
+/// Returns `true` if anything other than [`Response::none`] was responded.
+async fn execute_functions<I, F, R>(
+    ctx: &Context,
+    funcs: I,
+    req: R,
+    base: &BaseCommand,
+    key: Option<String>,
+) -> CommandResult<bool>
 where
     I: Iterator<Item = F> + Send,
     F: Callable<(Context, R)>,
-    R: Clone + Send,
+    R: Clone + Send + Into<Request>,
 {
+    // Send the cached reply instead, if there is one.
+    if let Some(reply) = key.as_deref().and_then(|k| ctx.results.get(k)) {
+        trace!("Using cached reply of '{}'", base.command.name);
+        reply.send(ctx, req.into()).await?;
+        return Ok(true);
+    }
+
+    // Successful replies are cached for next time.
+    let store = |reply: Option<Reply>| {
+        if let (Some(key), Some(ttl), Some(reply)) = (&key, base.cache_result, reply) {
+            ctx.results.insert(key.to_owned(), reply, ttl);
+        }
+    };
+
     let mut set = JoinSet::<CommandResponse>::new();
     let mut results = Vec::new();
 
@@ -569,17 +1405,450 @@ where
         set.spawn(func.call((ctx.to_owned(), req.to_owned())));
     }
 
+    if base.execution == Execution::FirstSuccess {
+        let Some(response) = first_success(set).await? else {
+            return Ok(false);
+        };
+        let responded = !response.is_none();
+        let reply = response.reply_message().cloned();
+        response.await.context("Response error")?;
+        store(reply);
+        return Ok(responded);
+    }
+
     // Wait for completion.
     while let Some(task) = set.join_next().await {
         results.push(task);
     }
 
+    let mut responded = false;
     for r in results {
-        r.context("Execution task join error")?
-            .context("Execution error")?
-            .await
-            .context("Response error")?;
+        let response = r
+            .context("Execution task join error")?
+            .context("Execution error")?;
+        responded |= !response.is_none();
+        let reply = response.reply_message().cloned();
+        response.await.context("Response error")?;
+        store(reply);
     }
 
-    Ok(())
+    Ok(responded)
+}
+
+/// Wait for the first successful response of the tasks, the rest are cancelled.
+/// Returns the last error if every task failed, or `None` if there were no tasks.
+async fn first_success(mut set: JoinSet<CommandResponse>) -> CommandResult<Option<Response>> {
+    let mut last_error = None;
+
+    while let Some(task) = set.join_next().await {
+        match task.context("Execution task join error")? {
+            Ok(response) => {
+                // Cancel the rest, this one wins.
+                set.abort_all();
+                return Ok(Some(response));
+            },
+            Err(e) => last_error = Some(e),
+        }
+    }
+
+    last_error.map_or(Ok(None), |e| {
+        Err(anyhow::Error::from(e).context("Execution error").into())
+    })
+}
+
+/// Key of the cached reply, if the command caches its results.
+fn cache_key(
+    base: &BaseCommand,
+    req: &Request,
+    path: &str,
+    args: impl std::fmt::Debug,
+) -> Option<String> {
+    // Replies are only reused in the same channel, as they can depend on where they were sent.
+    base.cache_result.map(|_| {
+        let (guild_id, channel_id) = (req.guild_id(), req.channel_id());
+        format!("{guild_id:?} {channel_id:?} {path} {args:?}")
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use twilight_model::application::command::CommandOptionType;
+
+    use super::*;
+    use crate::commands::builder::*;
+    use crate::commands::function::mock;
+    use crate::commands::CommandsBuilder;
+    use crate::test_utils::chat_message;
+
+    fn commands() -> Commands {
+        let mut commands = CommandsBuilder::new();
+        commands.bind(
+            command("test", "description")
+                .attach(mock::slash)
+                .option(integer("top", "description"))
+                .option(mention("who", "description"))
+                .option(
+                    group("group", "description").option(
+                        sub("sub", "description")
+                            .attach(mock::slash)
+                            .option(string("text", "description").required())
+                            .option(integer("count", "description")),
+                    ),
+                ),
+        );
+        commands.build()
+    }
+
+    fn opt(name: &str, value: CommandOptionValue) -> CommandDataOption {
+        CommandDataOption {
+            name: name.to_string(),
+            value,
+        }
+    }
+
+    #[test]
+    fn slash_group_sub_args() {
+        let commands = commands();
+        let options = [opt(
+            "group",
+            CommandOptionValue::SubCommandGroup(vec![opt(
+                "sub",
+                CommandOptionValue::SubCommand(vec![
+                    opt("count", CommandOptionValue::Integer(3)),
+                    opt("text", CommandOptionValue::String("hello".to_string())),
+                ]),
+            )]),
+        )];
+
+        let (resolved, args) = slash_options(&commands, "test", &options, None).unwrap();
+        let args = Args::from(args);
+
+        assert_eq!(resolved.path(), ["test", "group", "sub"]);
+        assert_eq!(args.string("text").unwrap().as_ref(), "hello");
+        assert_eq!(args.integer("count").unwrap(), 3);
+    }
+
+    #[test]
+    fn slash_args_around_sub() {
+        let commands = commands();
+        let options = [
+            opt(
+                "group",
+                CommandOptionValue::SubCommandGroup(vec![opt(
+                    "sub",
+                    CommandOptionValue::SubCommand(vec![opt(
+                        "text",
+                        CommandOptionValue::String("hello".to_string()),
+                    )]),
+                )]),
+            ),
+            opt("top", CommandOptionValue::Integer(1)),
+        ];
+
+        let (resolved, args) = slash_options(&commands, "test", &options, None).unwrap();
+        let args = Args::from(args);
+
+        assert_eq!(resolved.path(), ["test", "group", "sub"]);
+        assert_eq!(args.integer("top").unwrap(), 1);
+        assert_eq!(args.string("text").unwrap().as_ref(), "hello");
+    }
+
+    #[test]
+    fn slash_unknown_sub() {
+        let commands = commands();
+        let options = [opt(
+            "group",
+            CommandOptionValue::SubCommandGroup(vec![opt(
+                "unknown",
+                CommandOptionValue::SubCommand(vec![]),
+            )]),
+        )];
+
+        let err = slash_options(&commands, "test", &options, None).unwrap_err();
+        assert_eq!(err, CommandError::NotFound(String::new()));
+    }
+
+    #[test]
+    fn unknown_command_type() {
+        let err = unsupported_kind("test", CommandType::Unknown(99));
+        assert_eq!(err, CommandError::NotFound(String::new()));
+        assert!(err.to_string().contains("Unknown(99)"));
+    }
+
+    #[test]
+    fn slash_unsupported_arg() {
+        let commands = commands();
+        let options = [opt(
+            "top",
+            CommandOptionValue::Focused("1".to_string(), CommandOptionType::Integer),
+        )];
+
+        let err = slash_options(&commands, "test", &options, None).unwrap_err();
+        assert_eq!(err, CommandError::UnexpectedArgs(String::new()));
+    }
+
+    #[test]
+    fn slash_mentionable_user_or_role() {
+        let commands = commands();
+        let resolved: CommandInteractionDataResolved = serde_json::from_value(serde_json::json!({
+            "users": {
+                "10": { "id": "10", "username": "user", "discriminator": "0", "avatar": null },
+            },
+            "roles": {
+                "20": {
+                    "id": "20",
+                    "name": "role",
+                    "color": 0,
+                    "flags": 0,
+                    "hoist": false,
+                    "position": 1,
+                    "permissions": "0",
+                    "managed": false,
+                    "mentionable": true,
+                },
+            },
+        }))
+        .unwrap();
+
+        let mention = |id| {
+            let options = [opt("who", CommandOptionValue::Mentionable(Id::new(id)))];
+            slash_options(&commands, "test", &options, Some(&resolved))
+                .map(|(_, args)| Args::from(args).mention("who").unwrap())
+        };
+
+        assert_eq!(mention(10).unwrap(), Mentionable::User(Id::new(10)));
+        assert_eq!(mention(20).unwrap(), Mentionable::Role(Id::new(20)));
+        assert_eq!(
+            mention(30).unwrap_err(),
+            CommandError::UnexpectedArgs(String::new())
+        );
+    }
+
+    #[test]
+    fn group_without_sub_usage() {
+        let commands = commands();
+        let resolved = commands.resolve(&["test", "group"]).unwrap();
+        let usage = group_usage(&resolved);
+
+        assert!(usage.starts_with("Expected a subcommand of 'test group':"));
+        assert!(usage.contains("sub"));
+        assert!(usage.contains("text"));
+
+        assert!(needs_subcommand(resolved.target()));
+        let base = commands.resolve(&["test"]).unwrap();
+        assert!(!needs_subcommand(base.target())); // Has arguments of its own.
+        let leaf = commands.resolve(&["test", "group", "sub"]).unwrap();
+        assert!(!needs_subcommand(leaf.target()));
+
+        let parent = command("parent", "description")
+            .attach(mock::classic)
+            .option(sub("sub", "description").attach(mock::classic))
+            .build();
+        assert!(needs_subcommand(Lookup::Command(&parent.command)));
+    }
+
+    #[test]
+    fn classic_named_args() {
+        use crate::utils::consts::MAX_ARGS;
+
+        let user = user("user", "description").alias("u");
+        let count = integer("count", "description").build();
+        let bots = bool("bots", "description");
+        let args = [&user, &count, &bots];
+
+        let (named, rest) = take_named_args(
+            &args,
+            "first u:<@123> \"second one\" --count 5 --bots",
+            MAX_ARGS,
+            None,
+        )
+        .unwrap();
+        let named = Args::from(named);
+
+        assert_eq!(named.user("user").unwrap().id(), Id::new(123));
+        assert_eq!(named.integer("count").unwrap(), 5);
+        assert!(named.bool("bots").unwrap());
+        assert_eq!(rest.split_whitespace().collect::<Vec<_>>(), [
+            "first", "\"second", "one\""
+        ]);
+
+        assert!(take_named_args(&args, "--count 1 count:2", MAX_ARGS, None).is_err());
+        assert!(take_named_args(&args, "--count", MAX_ARGS, None).is_err());
+
+        // Text of a rest string is left alone.
+        let text = string("text", "description").rest().build();
+        let args = [&count, &text];
+        let (named, rest) =
+            take_named_args(&args, "--count 2 note: it's  count:3 \"", MAX_ARGS, None).unwrap();
+        assert_eq!(Args::from(named).integer("count").unwrap(), 2);
+        assert_eq!(rest, "note: it's  count:3 \"");
+
+        let (named, rest) = take_named_args(&args, "1 text count:3", MAX_ARGS, None).unwrap();
+        assert!(named.is_empty());
+        assert_eq!(rest, "1 text count:3");
+
+        // Arguments past the limit are not parsed, but a rest string is only one.
+        let many = "\"a\" ".repeat(10_000);
+        let args = [&user, &count, &bots];
+        assert!(matches!(
+            take_named_args(&args, &many, 3, None),
+            Err(CommandError::UnexpectedArgs(_))
+        ));
+        assert!(take_named_args(&args, "a b --bots", 3, None).is_ok());
+        assert!(take_named_args(&[&text], &many, 3, None).is_ok());
+    }
+
+    #[test]
+    fn cache_key_by_channel() {
+        let base = Arc::new(
+            command("cached", "description")
+                .attach(mock::classic)
+                .cache_result(std::time::Duration::from_secs(60))
+                .build(),
+        );
+        let key = |guild: Option<u64>, channel: u64| {
+            let mut msg = chat_message(1, 2, "!cached", None);
+            msg.guild_id = guild.map(Id::new);
+            msg.channel_id = Id::new(channel);
+            let req = ClassicRequest::new(Arc::clone(&base), Arc::new(msg), Args::default());
+            cache_key(&base, &req.into(), "cached", "args").unwrap()
+        };
+
+        assert_eq!(key(Some(1), 1), key(Some(1), 1));
+        assert_ne!(key(Some(1), 1), key(Some(1), 2));
+        assert_ne!(key(Some(1), 1), key(None, 1));
+
+        let uncached = command("uncached", "description")
+            .attach(mock::classic)
+            .build();
+        let req = ClassicRequest::new(
+            Arc::clone(&base),
+            Arc::new(chat_message(1, 2, "!uncached", None)),
+            Args::default(),
+        );
+        assert!(cache_key(&uncached, &req.into(), "uncached", "args").is_none());
+    }
+
+    #[test]
+    fn auto_delete_classic_replies() {
+        let base = command("temp", "description")
+            .attach(mock::classic)
+            .auto_delete(Duration::from_secs(10))
+            .build();
+        let req = ClassicRequest::new(
+            Arc::new(base.clone()),
+            Arc::new(chat_message(1, 2, "!temp", None)),
+            Args::default(),
+        );
+
+        // Nothing was replied yet.
+        assert!(auto_deleted_replies(&base, &req, &Ok(true)).is_none());
+
+        req.record_reply(Id::new(3));
+        assert_eq!(
+            auto_deleted_replies(&base, &req, &Ok(true)),
+            Some((Duration::from_secs(10), vec![Id::new(3)]))
+        );
+
+        // Kept if the command responded with nothing or failed.
+        assert!(auto_deleted_replies(&base, &req, &Ok(false)).is_none());
+        assert!(auto_deleted_replies(&base, &req, &Err(CommandError::Disabled)).is_none());
+
+        let kept = command("kept", "description").attach(mock::classic).build();
+        assert!(auto_deleted_replies(&kept, &req, &Ok(true)).is_none());
+
+        assert!(Response::none().is_none());
+        assert!(!Response::new(|| async { Ok(()) }).is_none());
+    }
+
+    #[test]
+    fn classic_args_from_reply() {
+        let cmd = sub("cmd", "description")
+            .attach(mock::classic)
+            .option(user("user", "description").required())
+            .option(mention("other", "description"))
+            .option(message("message", "description").required())
+            .build();
+        let replied = chat_message(10, 20, "original", None);
+        let reply = chat_message(11, 30, "!cmd", Some(replied));
+
+        let args = parse_classic_args(&cmd, &reply, None, 8, None).unwrap();
+        assert_eq!(args.user("user").unwrap().id(), Id::new(20));
+        assert_eq!(args.message("message").unwrap().id(), Id::new(10));
+
+        // Given users take precedence.
+        let args = parse_classic_args(&cmd, &reply, Some("<@40>"), 8, None).unwrap();
+        assert_eq!(args.user("user").unwrap().id(), Id::new(40));
+
+        // Nothing to fill from without a reply.
+        let plain = chat_message(12, 30, "!cmd", None);
+        assert!(parse_classic_args(&cmd, &plain, None, 8, None).is_err());
+
+        // Optional users are not filled.
+        let cmd = sub("cmd", "description")
+            .attach(mock::classic)
+            .option(integer("count", "description").required())
+            .option(user("user", "description"))
+            .build();
+        let args = parse_classic_args(&cmd, &reply, Some("10"), 8, None).unwrap();
+        assert!(args.user("user").is_err());
+    }
+
+    #[test]
+    fn error_context_identifies_invocation() {
+        assert_eq!(
+            error_context("classic", "roles add", Some(Id::new(1)), Some(Id::new(2))),
+            "Error in classic command 'roles add' by user '1' in guild '2'"
+        );
+        assert_eq!(
+            error_context("user", "mute", None, None),
+            "Error in user command 'mute' by unknown user in DMs"
+        );
+    }
+
+    #[tokio::test]
+    async fn first_success_wins() {
+        use std::sync::atomic::{AtomicBool, Ordering};
+
+        async fn fails(error: CommandError) -> CommandResponse {
+            Err(error)
+        }
+
+        async fn succeeds() -> CommandResponse {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            Ok(Response::none())
+        }
+
+        async fn slow(done: Arc<AtomicBool>) -> CommandResponse {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            done.store(true, Ordering::SeqCst);
+            Ok(Response::none())
+        }
+
+        // The failure comes first, but the first success is the one that wins.
+        let done = Arc::new(AtomicBool::new(false));
+        let mut set = JoinSet::new();
+        set.spawn(fails(CommandError::MissingArgs));
+        set.spawn(succeeds());
+        set.spawn(slow(Arc::clone(&done)));
+        assert!(first_success(set).await.unwrap().is_some());
+
+        // The slow one was aborted.
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(!done.load(Ordering::SeqCst));
+
+        // All failed, the last error is returned.
+        let mut set = JoinSet::new();
+        set.spawn(fails(CommandError::MissingArgs));
+        set.spawn(async {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            fails(CommandError::Disabled).await
+        });
+        let Err(CommandError::Other(e)) = first_success(set).await else {
+            panic!("expected an execution error");
+        };
+        assert_eq!(e.downcast_ref(), Some(&CommandError::Disabled));
+
+        assert!(first_success(JoinSet::new()).await.unwrap().is_none());
+    }
 }
@@ -0,0 +1,114 @@
+//! Hooks that are run after a command is executed.
+//!
+//! Hooks are attached to a command with [`BaseCommandBuilder::after`] and run in order,
+//! whether the command succeeded or not. A failing hook is only logged,
+//! the result of the command is kept as is.
+//!
+//! Hooks only see commands that were executed. A command that is stopped before that,
+//! because its arguments could not be parsed or a check denied it, does not run its hooks.
+//!
+//! [`BaseCommandBuilder::after`]: crate::commands::builder::BaseCommandBuilder::after
+
+use std::sync::Arc;
+
+use crate::commands::request::Request;
+use crate::commands::CommandResult;
+use crate::utils::prelude::*;
+use crate::Context;
+
+type AfterFn = dyn Fn(&Context, &Request, &CommandResult<()>) -> AnyResult<()> + Send + Sync;
+
+/// Function called after a command was executed, with the request and its result.
+#[derive(Clone)]
+pub struct After(Arc<AfterFn>);
+
+impl After {
+    pub fn new<F>(f: F) -> Self
+    where
+        F: Fn(&Context, &Request, &CommandResult<()>) -> AnyResult<()> + Send + Sync + 'static,
+    {
+        Self(Arc::new(f))
+    }
+
+    pub fn call(&self, ctx: &Context, req: &Request, result: &CommandResult<()>) -> AnyResult<()> {
+        (self.0)(ctx, req, result)
+    }
+}
+
+impl<F> From<F> for After
+where
+    F: Fn(&Context, &Request, &CommandResult<()>) -> AnyResult<()> + Send + Sync + 'static,
+{
+    fn from(f: F) -> Self {
+        Self::new(f)
+    }
+}
+
+impl std::fmt::Debug for After {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "After(_)")
+    }
+}
+
+/// Run all the hooks in order, logging the ones that fail.
+/// Returns the number of failed hooks.
+pub fn run(ctx: &Context, hooks: &[After], req: &Request, result: &CommandResult<()>) -> usize {
+    hooks
+        .iter()
+        .filter_map(|h| h.call(ctx, req, result).err())
+        .inspect(|e| warn!("After hook failed: {e:?}"))
+        .count()
+}
+
+/// Hook that logs who used the command and how it went.
+pub fn audit() -> After {
+    After::new(|_, req, result| {
+        info!("{}", audit_line(req, result));
+        Ok(())
+    })
+}
+
+fn audit_line(req: &Request, result: &CommandResult<()>) -> String {
+    let name = &req.command().command.name;
+    let user = req
+        .author_id()
+        .map_or_else(|| "unknown".to_string(), |id| id.to_string());
+    let place = match req.guild_id() {
+        Some(guild_id) => format!("guild '{guild_id}'"),
+        None => "direct messages".to_string(),
+    };
+    let outcome = match result {
+        Ok(()) => "done".to_string(),
+        Err(e) => format!("failed: {e}"),
+    };
+
+    format!("Command '{name}' used by user '{user}' in {place}: {outcome}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::builder::command;
+    use crate::commands::function::mock;
+    use crate::commands::prelude::*;
+    use crate::test_utils::chat_message;
+
+    #[test]
+    fn audit_command_result() {
+        let message = chat_message(1, 2, "!restart", None);
+        let base = command("restart", "description")
+            .attach(mock::classic)
+            .build();
+        let req = ClassicRequest::new(Arc::new(base), Arc::new(message), Args::default()).into();
+
+        assert_eq!(
+            audit_line(&req, &Ok(())),
+            "Command 'restart' used by user '2' in direct messages: done"
+        );
+        assert_eq!(
+            audit_line(&req, &Err(CommandError::Disabled)),
+            "Command 'restart' used by user '2' in direct messages: failed: Command or action \
+             disabled"
+        );
+    }
+}
@@ -30,30 +30,45 @@
 //!                                       └─────────────────────┘
 //! ```
 //!
+//! Application commands are acknowledged with a deferred response before executing.
+//! A command that responds with a single message should edit that deferred response
+//! (`update_response`), so no dangling loading message is left behind.
+//! Followups (`create_followup`) are reserved for any additional messages after that.
+//!
 
-use std::collections::{BTreeMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fmt::Write;
 use std::mem;
 use std::pin::Pin;
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
 
-use derive_more::{Deref, DerefMut, Index, IntoIterator};
+use derive_more::{Index, IntoIterator};
 use futures::Future;
 use thiserror::Error;
+use twilight_model::channel::message::Embed;
+use twilight_model::guild::Permissions;
+use twilight_model::http::attachment::Attachment;
 use twilight_model::id::marker::GuildMarker;
 use twilight_model::id::Id;
 
 use crate::commands::builder::twilight::{CommandValidationError, TwilightCommand};
-use crate::commands::builder::BaseCommand;
-use crate::commands::request::Request;
+use crate::commands::builder::{BaseCommand, CommandFunction, CommandGroup, CommandOption};
+use crate::commands::cache::Reply;
+use crate::commands::function::{ClassicFunction, MessageFunction, SlashFunction, UserFunction};
+use crate::commands::request::{Request, SlashRequest};
+use crate::modals::{self, Modal, ModalRequest};
 use crate::utils::prelude::*;
-use crate::{BotEvent, Context};
+use crate::{utils, BotEvent, Context};
 
 pub mod arg;
 pub mod builder;
+pub mod cache;
+pub mod checks;
 pub mod function;
 pub mod handle;
+pub mod hooks;
 pub mod request;
+pub mod schema;
 
 /// Prelude module for command things.
 pub mod prelude {
@@ -110,6 +125,14 @@ pub enum CommandError {
     #[error("Permission requirements not met")]
     AccessDenied,
 
+    /// The bot does not have the permissions that the command needs.
+    #[error("Bot is missing permissions: {0:?}")]
+    BotMissingPermissions(twilight_model::guild::Permissions),
+
+    /// The sender has to wait before using the command again.
+    #[error("Command on cooldown for {0:?}")]
+    Cooldown(std::time::Duration),
+
     /// Other errors that are or can be converted to `anyhow::Error`.
     #[error(transparent)]
     Other(#[from] anyhow::Error), // Source and Display delegate to `anyhow::Error`
@@ -144,9 +167,16 @@ impl_into_command_error!(Other; twilight_http::Error);
 impl_into_command_error!(Other; twilight_http::response::DeserializeBodyError);
 impl_into_command_error!(Other; twilight_standby::future::Canceled);
 impl_into_command_error!(Other; twilight_util::builder::embed::image_source::ImageSourceUrlError);
+impl_into_command_error!(Other; twilight_validate::channel::ChannelValidationError);
 impl_into_command_error!(Other; twilight_validate::message::MessageValidationError);
 impl_into_command_error!(Other; twilight_validate::request::ValidationError);
 
+/// Maximum length of message content.
+pub const MAX_MESSAGE_LENGTH: usize = 2000;
+
+/// Maximum size of a file upload in bytes, without any server boosts.
+pub const MAX_UPLOAD_SIZE: usize = 10 * 1024 * 1024;
+
 // Function -> CommandResponse -> Response -> CommandResult
 
 /// Trait alias for a command response future.
@@ -165,13 +195,20 @@ pub type CommandResponse = Result<Response, CommandError>;
 pub type CommandResult<T> = Result<T, CommandError>;
 
 /// Command function response type.
-#[derive(Deref, DerefMut)]
-pub struct Response(Pin<Box<dyn CommandFuture>>);
+pub struct Response {
+    future: Pin<Box<dyn CommandFuture>>,
+    /// The reply that this response sends, if it can be cached.
+    reply: Option<Reply>,
+    /// Whether this is [`Response::none`].
+    none: bool,
+}
 
 impl Response {
     /// Noop.
     pub fn none() -> Self {
-        Self::new(move || async move { Ok(()) })
+        let mut response = Self::new(move || async move { Ok(()) });
+        response.none = true;
+        response
     }
 
     /// Deletes original message or response. This will ignore any errors from the deletion.
@@ -187,13 +224,161 @@ impl Response {
         })
     }
 
+    /// Uploads a file as the response.
+    /// Fails if the file is larger than Discord allows to upload.
+    pub fn file(
+        ctx: Context,
+        req: impl Into<Request> + Send + 'static,
+        filename: String,
+        bytes: Vec<u8>,
+    ) -> Self {
+        Self::new(move || async move {
+            if bytes.len() > MAX_UPLOAD_SIZE {
+                return Err(anyhow::anyhow!(
+                    "File '{filename}' is {} bytes, which exceeds the upload limit of \
+                     {MAX_UPLOAD_SIZE} bytes",
+                    bytes.len()
+                )
+                .into());
+            }
+
+            let file = Attachment::from_bytes(filename, bytes, 0);
+            match req.into() {
+                Request::Classic(req) => req.upload(&ctx, file).await,
+                Request::Slash(req) => req.upload(&ctx, file).await,
+                Request::Message(req) => req.upload(&ctx, file).await,
+                Request::User(req) => req.upload(&ctx, file).await,
+            }
+            .map_err(Into::into)
+        })
+    }
+
+    /// Replies with a text message.
+    /// This is the response that commands with `cache_result` should use.
+    pub fn reply(ctx: Context, req: impl Into<Request> + Send + 'static, content: String) -> Self {
+        Self::with_reply(ctx, req, Reply {
+            content: Some(content),
+            embeds: Vec::new(),
+        })
+    }
+
+    /// Replies with an embed.
+    /// This is the response that commands with `cache_result` should use.
+    pub fn embed(ctx: Context, req: impl Into<Request> + Send + 'static, embed: Embed) -> Self {
+        Self::with_reply(ctx, req, Reply {
+            content: None,
+            embeds: vec![embed],
+        })
+    }
+
+    /// Replies with several text messages, which are sent one at a time in the given order.
+    /// Messages longer than [`MAX_MESSAGE_LENGTH`] are split into several, at line breaks
+    /// when possible, and empty messages are skipped.
+    ///
+    /// The first message is the reply to a classic command, or the interaction response,
+    /// and each of the rest is sent to the channel or as a followup only after the previous
+    /// one was sent. Sending stops at the first message that fails.
+    pub fn messages(
+        ctx: Context,
+        req: impl Into<Request> + Send + 'static,
+        messages: Vec<String>,
+    ) -> Self {
+        Self::new(move || async move {
+            let mut parts = messages
+                .iter()
+                .flat_map(|m| utils::split_message(m, MAX_MESSAGE_LENGTH))
+                .filter(|p| !p.trim().is_empty());
+
+            let token = match req.into() {
+                Request::Classic(req) => {
+                    if let Some(first) = parts.next() {
+                        req.reply(&ctx, first).await?;
+                    }
+                    for part in parts {
+                        let msg = ctx
+                            .http
+                            .create_message(req.message.channel_id)
+                            .content(part)?
+                            .send()
+                            .await
+                            .context("Failed to send message")?;
+                        req.record_reply(msg.id);
+                    }
+                    return Ok(());
+                },
+                Request::Slash(req) => req.interaction.token.to_owned(),
+                Request::Message(req) => req.interaction.token.to_owned(),
+                Request::User(req) => req.interaction.token.to_owned(),
+            };
+
+            let interaction = ctx.interaction();
+            if let Some(first) = parts.next() {
+                interaction
+                    .update_response(&token)
+                    .content(Some(first))?
+                    .await
+                    .context("Failed to update response")?;
+            }
+            for part in parts {
+                interaction
+                    .create_followup(&token)
+                    .content(part)?
+                    .await
+                    .context("Failed to send followup")?;
+            }
+
+            Ok(())
+        })
+    }
+
+    /// Shows a modal to collect more input, and calls `continuation` with the submission.
+    /// The continuation responds to the submission like a slash command function to its request.
+    /// It is dropped if the modal is not submitted in time, see [`modals`].
+    ///
+    /// The command must be built with [`modal`](builder::BaseCommandBuilder::modal),
+    /// since a modal has to be the first response to the interaction.
+    pub fn modal<F, Fut>(ctx: Context, req: SlashRequest, modal: Modal, continuation: F) -> Self
+    where
+        F: FnOnce(Context, ModalRequest) -> Fut + Send + 'static,
+        Fut: ResponseFuture + 'static,
+    {
+        Self::new(move || async move {
+            let ephemeral = req.command.ephemeral;
+            modals::show(&ctx, &req.interaction, ephemeral, modal, continuation)
+                .await
+                .map_err(Into::into)
+        })
+    }
+
+    fn with_reply(ctx: Context, req: impl Into<Request> + Send + 'static, reply: Reply) -> Self {
+        let sent = reply.clone();
+        let mut response =
+            Self::new(move || async move { sent.send(&ctx, req.into()).await.map_err(Into::into) });
+        response.reply = Some(reply);
+        response
+    }
+
     /// Creates a new response from a function.
     pub fn new<F, Fut>(f: F) -> Self
     where
         F: FnOnce() -> Fut + Send + 'static,
         Fut: CommandFuture + 'static,
     {
-        Self(Box::pin(f()))
+        Self {
+            future: Box::pin(f()),
+            reply: None,
+            none: false,
+        }
+    }
+
+    /// Returns `true` if this is [`Response::none`].
+    pub const fn is_none(&self) -> bool {
+        self.none
+    }
+
+    /// The reply that this response sends, if it can be cached.
+    pub const fn reply_message(&self) -> Option<&Reply> {
+        self.reply.as_ref()
     }
 }
 
@@ -204,23 +389,92 @@ impl Future for Response {
         mut self: Pin<&mut Self>,
         cx: &mut std::task::Context<'_>,
     ) -> std::task::Poll<Self::Output> {
-        Future::poll(self.0.as_mut(), cx)
+        Future::poll(self.future.as_mut(), cx)
     }
 }
 
+/// Cache of resolved command paths, as option indices from the base command.
+type LookupCache = Arc<RwLock<HashMap<String, Arc<[usize]>>>>;
+
 /// Newtype for commands collection.
 #[derive(Debug, Default, Clone, IntoIterator, Index)]
-pub struct Commands(BTreeMap<&'static str, Arc<BaseCommand>>);
+pub struct Commands {
+    #[into_iterator(owned, ref)]
+    #[index]
+    list: BTreeMap<&'static str, Arc<BaseCommand>>,
+    lookups: LookupCache,
+}
 
 impl Commands {
     /// Get base command by name.
     pub fn get(&self, id: &str) -> Option<&Arc<BaseCommand>> {
-        self.0.get(id)
+        self.list.get(id)
+    }
+
+    /// Iterate over all base commands, in the order of their names.
+    pub fn iter(&self) -> impl Iterator<Item = &BaseCommand> {
+        self.list.values().map(AsRef::as_ref)
+    }
+
+    /// Describe all commands as pretty printed JSON, see [`schema`].
+    pub fn to_json_schema(&self) -> AnyResult<String> {
+        let schema: Vec<_> = self.iter().map(schema::CommandSchema::from).collect();
+        serde_json::to_string_pretty(&schema).context("Failed to serialize command schema")
+    }
+
+    /// Number of base commands.
+    pub fn len(&self) -> usize {
+        self.list.len()
+    }
+
+    /// Returns `true` if there are no commands.
+    pub fn is_empty(&self) -> bool {
+        self.list.is_empty()
+    }
+
+    /// Resolve a (sub)command or group by its full path of names, e.g. `["roles", "setup"]`.
+    /// Resolved paths are cached, so repeated lookups skip walking the option tree.
+    pub fn resolve(&self, path: &[&str]) -> Option<ResolvedCommand> {
+        let (name, parent) = path.split_last()?;
+        if parent.is_empty() {
+            let base = Arc::clone(self.get(name)?);
+            return Some(ResolvedCommand::new(base, Arc::from([])));
+        }
+
+        let key = path.join(" ");
+        if let Some(indices) = self.lookups.read().unwrap().get(&key) {
+            let base = Arc::clone(self.get(path[0])?);
+            return Some(ResolvedCommand::new(base, Arc::clone(indices)));
+        }
+
+        // Resolve the parent first, then search only its immediate options.
+        let parent = self.resolve(parent)?;
+        let idx = match parent.target() {
+            Lookup::Command(c) => c
+                .options
+                .iter()
+                .position(|o| Lookup::from_option(o).is_some_and(|l| l.name() == *name)),
+            Lookup::Group(g) => g.subs.iter().position(|s| s.name == *name),
+        }?;
+
+        let indices: Arc<[usize]> = parent.indices.iter().copied().chain([idx]).collect();
+        self.lookups
+            .write()
+            .unwrap()
+            .insert(key, Arc::clone(&indices));
+
+        Some(ResolvedCommand::new(parent.base, indices))
+    }
+
+    /// Clear cached command lookups, done when the settings are reloaded.
+    /// Restarting builds new commands, which start with an empty cache.
+    pub fn clear_lookups(&self) {
+        self.lookups.write().unwrap().clear();
     }
 
     /// Convert commands to Discord compatible list.
     pub fn twilight_commands(&self) -> Result<Vec<TwilightCommand>, CommandValidationError> {
-        self.0
+        self.list
             .values()
             .flat_map(|b| b.twilight_commands())
             .try_collect()
@@ -228,17 +482,23 @@ impl Commands {
 
     /// Get reference to the inner list.
     pub const fn inner(&self) -> &BTreeMap<&'static str, Arc<BaseCommand>> {
-        &self.0
+        &self.list
     }
 }
 
 impl Commands {
-    pub fn display(&self, ctx: &Context, guild_id: Option<Id<GuildMarker>>) -> AnyResult<String> {
+    /// List the commands usable in the guild, or in DMs if `None`.
+    /// The list is split into code blocks that each fit in a message, between commands.
+    pub fn display(
+        &self,
+        ctx: &Context,
+        guild_id: Option<Id<GuildMarker>>,
+    ) -> AnyResult<Vec<String>> {
         let mut slash = vec![];
         let mut classic = vec![];
         let mut gui = vec![];
 
-        for (&k, v) in self.0.iter() {
+        for (&k, v) in self.list.iter() {
             if guild_id.is_none() && !v.dm_enabled {
                 continue;
             }
@@ -253,25 +513,56 @@ impl Commands {
             }
         }
 
-        let slash = slash.join(", ");
-        let classic = classic.join(", ");
-        let gui = gui.join(", ");
+        let prefix = ctx.config.classic_prefix(guild_id)?;
+        let header = format!(
+            "Prefix: '/' or '{}'\nCommands:\n",
+            utils::escape_code_block(&prefix)
+        );
+        let sections = [("/", slash), (&prefix[..], classic), ("🖱", gui)];
 
-        let mut s = String::new();
+        Ok(display_pages(&header, &sections, MAX_MESSAGE_LENGTH))
+    }
+}
 
-        if !slash.is_empty() {
-            writeln!(s, "/\t{slash}")?;
-        }
-        if !classic.is_empty() {
-            let prefix = ctx.config.classic_prefix(guild_id)?;
-            writeln!(s, "{prefix}\t{classic}")?;
-        }
-        if !gui.is_empty() {
-            writeln!(s, "🖱   {gui}")?;
+/// Pack labeled lists of command names into code blocks of at most `max` bytes.
+/// Each page starts with `header`, and a list continues on the next page with its label.
+fn display_pages(header: &str, sections: &[(&str, Vec<&str>)], max: usize) -> Vec<String> {
+    const CLOSE: &str = "```";
+    let open = format!("```yaml\n{header}");
+
+    let mut pages = Vec::new();
+    let mut page = open.to_owned();
+
+    for (label, names) in sections.iter().filter(|(_, n)| !n.is_empty()) {
+        let label = utils::escape_code_block(label);
+
+        for (i, name) in names.iter().enumerate() {
+            let name = utils::escape_code_block(name);
+            let item = if i == 0 {
+                format!("{label}\t{name}")
+            } else {
+                format!(", {name}")
+            };
+
+            // Room for the item, the line break and the closing fence.
+            if page.len() + item.len() + 1 + CLOSE.len() > max && page.len() > open.len() {
+                if !page.ends_with('\n') {
+                    page.push('\n');
+                }
+                page.push_str(CLOSE);
+                pages.push(mem::replace(&mut page, open.to_owned()));
+                write!(page, "{label}\t{name}").ok();
+            } else {
+                page.push_str(&item);
+            }
         }
 
-        Ok(s)
+        page.push('\n');
     }
+
+    page.push_str(CLOSE);
+    pages.push(page);
+    pages
 }
 
 /// A type for creating a collection of commands and validating them.
@@ -292,6 +583,14 @@ impl CommandsBuilder {
         self
     }
 
+    /// Assign a command to the list, only if `condition` is `true`.
+    pub fn bind_if(&mut self, condition: bool, cmd: impl Into<BaseCommand>) -> &mut Self {
+        if condition {
+            self.bind(cmd);
+        }
+        self
+    }
+
     /// Validate the list of commands.
     pub fn validate(&self) -> AnyResult<()> {
         let mut set = HashSet::with_capacity(self.list.len());
@@ -306,6 +605,14 @@ impl CommandsBuilder {
                 "Duplicate command found: {}",
                 cmd.command.name
             );
+
+            // Ensure the required gateway intents are enabled.
+            let missing = cmd.missing_intents(crate::intents());
+            anyhow::ensure!(
+                missing.is_empty(),
+                "Command '{}' requires intents that are not enabled: {missing:?}",
+                cmd.command.name
+            );
         }
 
         Ok(())
@@ -313,11 +620,328 @@ impl CommandsBuilder {
 
     /// Finalize the list of commands.
     pub fn build(self) -> Commands {
-        Commands(
-            self.list
+        Commands {
+            list: self
+                .list
                 .into_iter()
                 .map(|b| (b.command.name, Arc::new(b)))
                 .collect(),
-        )
+            lookups: LookupCache::default(),
+        }
+    }
+}
+
+/// A (sub)command or group resolved from a command path.
+#[derive(Debug, Clone)]
+pub struct ResolvedCommand {
+    pub base: Arc<BaseCommand>,
+    indices: Arc<[usize]>,
+}
+
+impl ResolvedCommand {
+    const fn new(base: Arc<BaseCommand>, indices: Arc<[usize]>) -> Self {
+        Self { base, indices }
+    }
+
+    /// Get the resolved (sub)command or group.
+    pub fn target(&self) -> Lookup<'_> {
+        *self
+            .ancestry()
+            .last()
+            .expect("Ancestry should contain the base command")
+    }
+
+    /// Get the resolved (sub)command, or `None` if the path ends with a group.
+    pub fn function(&self) -> Option<&CommandFunction> {
+        match self.target() {
+            Lookup::Command(c) => Some(c),
+            Lookup::Group(_) => None,
+        }
+    }
+
+    /// Get every (sub)command or group along the path, from the base command to the target.
+    pub fn ancestry(&self) -> Vec<Lookup<'_>> {
+        let mut ancestry = Vec::with_capacity(self.indices.len() + 1);
+        ancestry.push(Lookup::Command(&self.base.command));
+
+        // Indices are only created from the same base command, so they are always in bounds.
+        for &i in self.indices.iter() {
+            let next = match ancestry[ancestry.len() - 1] {
+                Lookup::Command(c) => Lookup::from_option(&c.options[i])
+                    .expect("Resolved index should point to a subcommand or group"),
+                Lookup::Group(g) => Lookup::Command(&g.subs[i]),
+            };
+            ancestry.push(next);
+        }
+
+        ancestry
+    }
+
+    /// Permissions that the bot needs for the resolved (sub)command,
+    /// including those of the base command and the subcommands along the path.
+    pub fn bot_permissions(&self) -> Permissions {
+        let base = self.base.bot_permissions.unwrap_or_else(Permissions::empty);
+        self.ancestry()
+            .iter()
+            .fold(base, |perms, lookup| match lookup {
+                Lookup::Command(c) => perms | c.bot_permissions,
+                Lookup::Group(_) => perms,
+            })
+    }
+
+    /// Get the names along the path, e.g. `["roles", "setup"]`.
+    pub fn path(&self) -> Vec<&'static str> {
+        self.ancestry().iter().map(Lookup::name).collect()
+    }
+
+    /// Generate help text for the resolved (sub)command or group.
+    pub fn generate_help(&self) -> String {
+        if self.indices.is_empty() {
+            return self.base.generate_help();
+        }
+
+        let path = self.path();
+        let parents = path[..path.len() - 1].join(" ");
+
+        let help = match self.target() {
+            Lookup::Command(c) => c.generate_help(0),
+            Lookup::Group(g) => g.generate_help(0),
+        };
+
+        format!("```yaml\n{parents} {help}\n```")
+    }
+}
+
+/// Reference to a (sub)command or group.
+#[derive(Debug, Clone, Copy)]
+pub enum Lookup<'a> {
+    Command(&'a CommandFunction),
+    Group(&'a CommandGroup),
+}
+
+impl<'a> Lookup<'a> {
+    pub const fn from_option(opt: &'a CommandOption) -> Option<Self> {
+        match opt {
+            CommandOption::Sub(s) => Some(Self::Command(s)),
+            CommandOption::Group(g) => Some(Self::Group(g)),
+            _ => None,
+        }
+    }
+
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Lookup::Command(t) => t.name,
+            Lookup::Group(t) => t.name,
+        }
+    }
+
+    /// Find a subcommand or group by name.
+    pub fn child(&self, name: &str) -> Option<Self> {
+        match *self {
+            Lookup::Command(c) => c
+                .options
+                .iter()
+                .filter_map(Lookup::from_option)
+                .find(|l| l.name() == name),
+            Lookup::Group(g) => g.subs.iter().find(|s| s.name == name).map(Lookup::Command),
+        }
+    }
+
+    pub fn classic_functions(&self) -> AnyResult<impl Iterator<Item = ClassicFunction> + 'a> {
+        match *self {
+            Lookup::Command(c) if c.has_classic() => Ok(c.classic()),
+            Lookup::Command(c) => {
+                anyhow::bail!("No classic commands found for command call: '{}'", c.name)
+            },
+            Lookup::Group(g) => {
+                // TODO: This should be usage error or considered as an arg to previous command.
+                anyhow::bail!("Expected a subcommand, found group: '{}'", g.name)
+            },
+        }
+    }
+
+    pub fn slash_functions(&self) -> AnyResult<impl Iterator<Item = SlashFunction> + 'a> {
+        match *self {
+            Lookup::Command(c) if c.has_slash() => Ok(c.slash()),
+            Lookup::Command(c) => {
+                anyhow::bail!("No slash commands found for command call: '{}'", c.name)
+            },
+            Lookup::Group(g) => {
+                anyhow::bail!("Expected a subcommand, found group: '{}'", g.name)
+            },
+        }
+    }
+
+    pub fn message_functions(&self) -> AnyResult<impl Iterator<Item = MessageFunction> + 'a> {
+        match *self {
+            Lookup::Command(c) if c.has_message() => Ok(c.message()),
+            Lookup::Command(c) => {
+                anyhow::bail!("No message commands found for command call: '{}'", c.name)
+            },
+            Lookup::Group(g) => {
+                anyhow::bail!("Message commands cannot be groups: '{}'", g.name)
+            },
+        }
+    }
+
+    pub fn user_functions(&self) -> AnyResult<impl Iterator<Item = UserFunction> + 'a> {
+        match *self {
+            Lookup::Command(c) if c.has_user() => Ok(c.user()),
+            Lookup::Command(c) => {
+                anyhow::bail!("No user commands found for command call: '{}'", c.name)
+            },
+            Lookup::Group(g) => {
+                anyhow::bail!("User commands cannot be groups: '{}'", g.name)
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::builder::*;
+    use crate::commands::function::{mock, FunctionKind};
+
+    fn commands() -> Commands {
+        let mut commands = CommandsBuilder::new();
+        commands
+            .bind(command("a", "description").attach(mock::classic))
+            .bind(command("m", "description").attach(mock::message))
+            .bind(
+                command("b", "description")
+                    .attach(mock::classic)
+                    .bot_permissions(Permissions::SEND_MESSAGES)
+                    .option(
+                        sub("ba", "description")
+                            .attach(mock::classic)
+                            .bot_permissions(Permissions::MANAGE_ROLES),
+                    )
+                    .option(
+                        group("bb", "description")
+                            .option(sub("bba", "description").attach(mock::classic))
+                            .option(sub("bbb", "description").attach(mock::classic)),
+                    ),
+            );
+        commands.build()
+    }
+
+    #[test]
+    fn iterate_commands() {
+        let commands = commands();
+        assert_eq!(commands.len(), 3);
+
+        let names: Vec<_> = commands.iter().map(BaseCommand::name).collect();
+        assert_eq!(names, ["a", "b", "m"]);
+
+        let b = commands.iter().find(|c| c.name() == "b").unwrap();
+        assert_eq!(b.options().len(), 2);
+        assert_eq!(b.kinds().collect::<Vec<_>>(), [FunctionKind::Classic]);
+        assert_eq!(b.permissions(), None);
+    }
+
+    #[test]
+    fn json_schema() {
+        let schema: serde_json::Value =
+            serde_json::from_str(&commands().to_json_schema().unwrap()).unwrap();
+
+        assert_eq!(schema[0]["name"], "a");
+        assert_eq!(schema[0]["kinds"], serde_json::json!(["classic"]));
+        assert_eq!(schema[1]["options"][0]["type"], "sub");
+        assert_eq!(schema[1]["options"][1]["type"], "group");
+        assert_eq!(schema[1]["options"][1]["subs"][1]["name"], "bbb");
+        assert_eq!(schema[2]["kinds"], serde_json::json!(["message"]));
+    }
+
+    #[test]
+    fn resolve_paths() {
+        let commands = commands();
+
+        for path in [&["a"][..], &["b"], &["b", "ba"], &["b", "bb"], &[
+            "b", "bb", "bbb",
+        ]] {
+            let resolved = commands.resolve(path).unwrap();
+            assert_eq!(resolved.path(), path);
+            assert_eq!(resolved.target().name(), *path.last().unwrap());
+        }
+
+        // Cached lookup gives the same result.
+        let resolved = commands.resolve(&["b", "bb", "bbb"]).unwrap();
+        assert_eq!(resolved.path(), ["b", "bb", "bbb"]);
+        assert!(resolved.function().is_some());
+        assert!(commands.resolve(&["b", "bb"]).unwrap().function().is_none());
+
+        // Only subcommand paths are cached, and the cache is cleared on reload.
+        assert_eq!(commands.lookups.read().unwrap().len(), 3);
+        commands.clear_lookups();
+        assert!(commands.lookups.read().unwrap().is_empty());
+        assert!(commands.resolve(&["b", "bb", "bbb"]).is_some());
+    }
+
+    #[test]
+    fn bot_permissions_by_subcommand() {
+        let commands = commands();
+        let perms = |path: &[&str]| commands.resolve(path).unwrap().bot_permissions();
+
+        assert_eq!(perms(&["a"]), Permissions::empty());
+        assert_eq!(perms(&["b"]), Permissions::SEND_MESSAGES);
+        assert_eq!(
+            perms(&["b", "ba"]),
+            Permissions::SEND_MESSAGES | Permissions::MANAGE_ROLES
+        );
+        assert_eq!(perms(&["b", "bb", "bba"]), Permissions::SEND_MESSAGES);
+    }
+
+    #[test]
+    fn resolve_invalid_paths() {
+        let commands = commands();
+
+        assert!(commands.resolve(&[]).is_none());
+        assert!(commands.resolve(&["c"]).is_none());
+        assert!(commands.resolve(&["a", "ba"]).is_none());
+        assert!(commands.resolve(&["b", "bba"]).is_none());
+        assert!(commands.resolve(&["b", "bb", "bba", "x"]).is_none());
+    }
+
+    #[test]
+    fn missing_gui_functions() {
+        let commands = commands();
+        let target = |name| Lookup::Command(&commands.get(name).unwrap().command);
+
+        assert!(target("m").message_functions().is_ok());
+        assert!(target("m").user_functions().is_err());
+        assert!(target("a").message_functions().is_err());
+        assert!(target("a").user_functions().is_err());
+
+        let group = commands.resolve(&["b", "bb"]).unwrap();
+        assert!(group.target().message_functions().is_err());
+    }
+
+    #[test]
+    fn display_splits_into_pages() {
+        let names: Vec<_> = (0..400).map(|i| &*format!("command{i}").leak()).collect();
+        let sections = [
+            ("/", names.to_owned()),
+            ("!", vec!["classic"]),
+            ("🖱", vec![]),
+        ];
+        let pages = display_pages("Commands:\n", &sections, MAX_MESSAGE_LENGTH);
+
+        assert!(pages.len() > 1, "{pages:?}");
+        for page in pages.iter() {
+            assert!(page.len() <= MAX_MESSAGE_LENGTH);
+            assert!(page.starts_with("```yaml\nCommands:\n/\t"), "{page}");
+            assert!(page.ends_with("\n```"), "{page}");
+        }
+
+        // Every command is listed once and whole.
+        let listed: Vec<_> = pages
+            .iter()
+            .flat_map(|p| p.lines().filter_map(|l| l.strip_prefix("/\t")))
+            .flat_map(|l| l.split(", "))
+            .collect();
+        assert_eq!(listed, names);
+        assert!(pages.last().unwrap().ends_with("!\tclassic\n```"));
+        assert!(!pages.iter().any(|p| p.contains("🖱")));
     }
 }
@@ -1,10 +1,13 @@
-use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
 
 use derive_more::From;
 use twilight_model::application::interaction::application_command::CommandData;
 use twilight_model::application::interaction::Interaction;
 use twilight_model::channel::Message;
-use twilight_model::id::marker::{MessageMarker, UserMarker};
+use twilight_model::guild::Permissions;
+use twilight_model::http::attachment::Attachment;
+use twilight_model::id::marker::{ChannelMarker, GuildMarker, MessageMarker, UserMarker};
 use twilight_model::id::Id;
 
 use crate::commands::arg::Args;
@@ -12,21 +15,91 @@ use crate::commands::builder::BaseCommand;
 use crate::utils::prelude::*;
 use crate::Context;
 
+/// Placeholder reply of a deferred classic command.
+#[derive(Debug)]
+pub struct Placeholder {
+    pub id: Id<MessageMarker>,
+    used: AtomicBool,
+}
+
+impl Placeholder {
+    pub const fn new(id: Id<MessageMarker>) -> Self {
+        Self {
+            id,
+            used: AtomicBool::new(false),
+        }
+    }
+
+    /// Returns `true` if the placeholder was edited to a response.
+    pub fn is_used(&self) -> bool {
+        self.used.load(Ordering::Relaxed)
+    }
+}
+
 /// Classic command request with preprocessed arguments and original message.
 #[derive(Debug, Clone)]
 pub struct ClassicRequest {
     pub command: Arc<BaseCommand>,
     pub message: Arc<Message>,
     pub args: Args,
+    /// Placeholder reply, if the command is deferred.
+    pub placeholder: Option<Arc<Placeholder>>,
+    /// Replies sent through the request.
+    replies: Arc<Mutex<Vec<Id<MessageMarker>>>>,
 }
 
 impl ClassicRequest {
-    pub const fn new(command: Arc<BaseCommand>, message: Arc<Message>, args: Args) -> Self {
+    pub fn new(command: Arc<BaseCommand>, message: Arc<Message>, args: Args) -> Self {
         Self {
             command,
             message,
             args,
+            placeholder: None,
+            replies: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    /// Set the placeholder reply to be edited by [`Self::reply`].
+    pub fn with_placeholder(mut self, placeholder: Arc<Placeholder>) -> Self {
+        self.placeholder = Some(placeholder);
+        self
+    }
+
+    /// Replies to the command call message, or edits the placeholder reply if there is one.
+    pub async fn reply(&self, ctx: &Context, content: &str) -> AnyResult<()> {
+        match &self.placeholder {
+            Some(placeholder) => {
+                placeholder.used.store(true, Ordering::Relaxed);
+                ctx.http
+                    .update_message(self.message.channel_id, placeholder.id)
+                    .content(Some(content))?
+                    .await
+                    .context("Failed to edit placeholder")?;
+                self.record_reply(placeholder.id);
+            },
+            None => {
+                let reply = ctx
+                    .http
+                    .create_message(self.message.channel_id)
+                    .reply(self.message.id)
+                    .content(content)?
+                    .send()
+                    .await
+                    .context("Failed to reply")?;
+                self.record_reply(reply.id);
+            },
         }
+        Ok(())
+    }
+
+    /// Remember a reply that was sent for the command.
+    pub fn record_reply(&self, message_id: Id<MessageMarker>) {
+        self.replies.lock().unwrap().push(message_id);
+    }
+
+    /// Replies that were sent through the request, in the order they were sent.
+    pub fn replies(&self) -> Vec<Id<MessageMarker>> {
+        self.replies.lock().unwrap().to_owned()
     }
 
     /// Deletes the command call message.
@@ -37,6 +110,20 @@ impl ClassicRequest {
             .context("Failed to clear command message")
             .map(|_| ())
     }
+
+    /// Uploads a file as a reply to the command call message.
+    pub async fn upload(&self, ctx: &Context, file: Attachment) -> AnyResult<()> {
+        let reply = ctx
+            .http
+            .create_message(self.message.channel_id)
+            .reply(self.message.id)
+            .attachments(&[file])?
+            .send()
+            .await
+            .context("Failed to upload file")?;
+        self.record_reply(reply.id);
+        Ok(())
+    }
 }
 
 /// Slash command request with preprocessed arguments and interaction data.
@@ -71,6 +158,16 @@ impl SlashRequest {
             .context("Failed to clear interaction")
             .map(|_| ())
     }
+
+    /// Uploads a file to the interaction response.
+    pub async fn upload(&self, ctx: &Context, file: Attachment) -> AnyResult<()> {
+        ctx.interaction()
+            .update_response(&self.interaction.token)
+            .attachments(&[file])?
+            .await
+            .context("Failed to upload file")
+            .map(|_| ())
+    }
 }
 
 /// Message command request with command and interaction data.
@@ -105,6 +202,16 @@ impl MessageRequest {
             .context("Failed to clear interaction")
             .map(|_| ())
     }
+
+    /// Uploads a file to the interaction response.
+    pub async fn upload(&self, ctx: &Context, file: Attachment) -> AnyResult<()> {
+        ctx.interaction()
+            .update_response(&self.interaction.token)
+            .attachments(&[file])?
+            .await
+            .context("Failed to upload file")
+            .map(|_| ())
+    }
 }
 
 /// User command request with command and interaction data.
@@ -139,6 +246,16 @@ impl UserRequest {
             .context("Failed to clear interaction")
             .map(|_| ())
     }
+
+    /// Uploads a file to the interaction response.
+    pub async fn upload(&self, ctx: &Context, file: Attachment) -> AnyResult<()> {
+        ctx.interaction()
+            .update_response(&self.interaction.token)
+            .attachments(&[file])?
+            .await
+            .context("Failed to upload file")
+            .map(|_| ())
+    }
 }
 
 #[derive(Debug, From)]
@@ -148,3 +265,54 @@ pub enum Request {
     Message(MessageRequest),
     User(UserRequest),
 }
+
+impl Request {
+    /// Interaction of the request, if not a classic command.
+    fn interaction(&self) -> Option<&Interaction> {
+        match self {
+            Self::Classic(_) => None,
+            Self::Slash(req) => Some(&req.interaction),
+            Self::Message(req) => Some(&req.interaction),
+            Self::User(req) => Some(&req.interaction),
+        }
+    }
+
+    /// The command that was invoked.
+    pub fn command(&self) -> &BaseCommand {
+        match self {
+            Self::Classic(req) => &req.command,
+            Self::Slash(req) => &req.command,
+            Self::Message(req) => &req.command,
+            Self::User(req) => &req.command,
+        }
+    }
+
+    /// The user who invoked the command.
+    pub fn author_id(&self) -> Option<Id<UserMarker>> {
+        match self {
+            Self::Classic(req) => Some(req.message.author.id),
+            _ => self.interaction()?.author_id(),
+        }
+    }
+
+    /// The guild where the command was invoked, if any.
+    pub fn guild_id(&self) -> Option<Id<GuildMarker>> {
+        match self {
+            Self::Classic(req) => req.message.guild_id,
+            _ => self.interaction()?.guild_id,
+        }
+    }
+
+    /// The channel where the command was invoked.
+    pub fn channel_id(&self) -> Option<Id<ChannelMarker>> {
+        match self {
+            Self::Classic(req) => Some(req.message.channel_id),
+            _ => self.interaction()?.channel.as_ref().map(|c| c.id),
+        }
+    }
+
+    /// Permissions of the bot in the channel, as given by Discord with interactions.
+    pub fn app_permissions(&self) -> Option<Permissions> {
+        self.interaction()?.app_permissions
+    }
+}
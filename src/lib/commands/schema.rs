@@ -0,0 +1,163 @@
+//! Serializable description of the commands, for documentation and external tools.
+//!
+//! The schema mirrors the builder types, but is kept separate from them,
+//! so that the exported structure only changes on purpose.
+
+use serde::Serialize;
+use twilight_model::guild::Permissions;
+
+use crate::commands::builder::{ArgDesc, BaseCommand, CommandFunction, CommandOption};
+use crate::commands::function::FunctionKind;
+
+/// Description of a base command.
+#[derive(Debug, Clone, Serialize)]
+pub struct CommandSchema {
+    pub name: &'static str,
+    pub description: &'static str,
+    /// Kinds of functions the command has, eg. `classic` or `slash`.
+    pub kinds: Vec<&'static str>,
+    /// If the command can be used in DMs.
+    pub dm: bool,
+    /// Names of the default member permissions, `None` if anyone can use the command,
+    /// or empty if only administrators can.
+    pub permissions: Option<Vec<String>>,
+    /// Names of the permissions that the bot needs, if any.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub bot_permissions: Vec<String>,
+    pub options: Vec<OptionSchema>,
+}
+
+impl From<&BaseCommand> for CommandSchema {
+    fn from(base: &BaseCommand) -> Self {
+        Self {
+            name: base.name(),
+            description: base.description(),
+            kinds: base.kinds().map(kind_name).collect(),
+            dm: base.dm_enabled,
+            permissions: base.permissions().map(permission_names),
+            bot_permissions: base
+                .bot_permissions
+                .map(permission_names)
+                .unwrap_or_default(),
+            options: base.options().iter().map(Into::into).collect(),
+        }
+    }
+}
+
+/// Description of an argument, a subcommand or a group of subcommands.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum OptionSchema {
+    Arg(ArgSchema),
+    Sub(SubSchema),
+    Group(GroupSchema),
+}
+
+impl From<&CommandOption> for OptionSchema {
+    fn from(opt: &CommandOption) -> Self {
+        match opt {
+            CommandOption::Arg(a) => Self::Arg(a.into()),
+            CommandOption::Sub(s) => Self::Sub(s.into()),
+            CommandOption::Group(g) => Self::Group(GroupSchema {
+                name: g.name,
+                description: g.description,
+                subs: g.subs.iter().map(Into::into).collect(),
+            }),
+        }
+    }
+}
+
+/// Description of a command argument.
+#[derive(Debug, Clone, Serialize)]
+pub struct ArgSchema {
+    pub name: &'static str,
+    pub description: &'static str,
+    /// Kind of the value, eg. `string` or `user`.
+    pub kind: String,
+    pub required: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub aliases: Vec<&'static str>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub choices: Vec<ChoiceSchema>,
+}
+
+impl From<&ArgDesc> for ArgSchema {
+    fn from(arg: &ArgDesc) -> Self {
+        let choices = arg
+            .kind
+            .choice_values()
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(name, value)| ChoiceSchema {
+                name: name.to_string(),
+                value,
+            })
+            .collect();
+
+        Self {
+            name: arg.name,
+            description: arg.description,
+            kind: arg.kind.to_string(),
+            required: arg.required,
+            aliases: arg.aliases.to_owned(),
+            choices,
+        }
+    }
+}
+
+/// A predefined value of an argument.
+#[derive(Debug, Clone, Serialize)]
+pub struct ChoiceSchema {
+    pub name: String,
+    pub value: String,
+}
+
+/// Description of a subcommand.
+#[derive(Debug, Clone, Serialize)]
+pub struct SubSchema {
+    pub name: &'static str,
+    pub description: &'static str,
+    /// Names of the permissions that the bot needs for the subcommand, if any.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub bot_permissions: Vec<String>,
+    pub options: Vec<OptionSchema>,
+}
+
+impl From<&CommandFunction> for SubSchema {
+    fn from(sub: &CommandFunction) -> Self {
+        Self {
+            name: sub.name,
+            description: sub.description,
+            bot_permissions: permission_names(sub.bot_permissions),
+            options: sub.options.iter().map(Into::into).collect(),
+        }
+    }
+}
+
+/// Description of a group of subcommands.
+#[derive(Debug, Clone, Serialize)]
+pub struct GroupSchema {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub subs: Vec<SubSchema>,
+}
+
+const fn kind_name(kind: FunctionKind) -> &'static str {
+    match kind {
+        FunctionKind::Classic => "classic",
+        FunctionKind::Slash => "slash",
+        FunctionKind::Message => "message",
+        FunctionKind::User => "user",
+    }
+}
+
+fn permission_names(perms: Permissions) -> Vec<String> {
+    if perms.is_empty() {
+        return Vec::new();
+    }
+    // Flags are formatted like `BAN_MEMBERS | KICK_MEMBERS`.
+    format!("{perms:?}")
+        .split(" | ")
+        .map(ToOwned::to_owned)
+        .collect()
+}
@@ -0,0 +1,237 @@
+//! Helpers for building message components.
+//!
+//! Components are laid out in action rows, which are checked against Discord's limits:
+//! at most 5 rows per message, 5 buttons per row and a single select menu (with 1-25 options) per row.
+//! Modals use the same rows, with a single text input per row.
+
+use thiserror::Error;
+use twilight_model::channel::message::component::{
+    ActionRow, Button, ButtonStyle, SelectMenu, SelectMenuOption, TextInput, TextInputStyle,
+};
+use twilight_model::channel::message::Component;
+
+/// Maximum number of action rows in a message.
+pub const MAX_ROWS: usize = 5;
+
+/// Maximum number of buttons in an action row.
+pub const MAX_BUTTONS: usize = 5;
+
+/// Maximum number of options in a select menu.
+pub const MAX_OPTIONS: usize = 25;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum ComponentError {
+    #[error("Too many action rows, up to {MAX_ROWS} allowed")]
+    TooManyRows,
+
+    #[error("Too many buttons in a row, up to {MAX_BUTTONS} allowed")]
+    TooManyButtons,
+
+    #[error("Too many select menu options, up to {MAX_OPTIONS} allowed")]
+    TooManyOptions,
+
+    #[error("Action rows and select menus cannot be empty")]
+    Empty,
+}
+
+/// Create an interactive button.
+pub fn button(
+    custom_id: impl Into<String>,
+    label: impl Into<String>,
+    style: ButtonStyle,
+) -> Button {
+    Button {
+        custom_id: Some(custom_id.into()),
+        disabled: false,
+        emoji: None,
+        label: Some(label.into()),
+        style,
+        url: None,
+    }
+}
+
+/// Create a button that opens a link.
+pub fn link_button(url: impl Into<String>, label: impl Into<String>) -> Button {
+    Button {
+        custom_id: None,
+        disabled: false,
+        emoji: None,
+        label: Some(label.into()),
+        style: ButtonStyle::Link,
+        url: Some(url.into()),
+    }
+}
+
+/// Create a text input of a modal.
+pub fn text_input(
+    custom_id: impl Into<String>,
+    label: impl Into<String>,
+    style: TextInputStyle,
+) -> TextInput {
+    TextInput {
+        custom_id: custom_id.into(),
+        label: label.into(),
+        max_length: None,
+        min_length: None,
+        placeholder: None,
+        required: None,
+        style,
+        value: None,
+    }
+}
+
+/// Create a select menu builder.
+pub fn select_menu(custom_id: impl Into<String>) -> SelectMenuBuilder {
+    SelectMenuBuilder(SelectMenu {
+        custom_id: custom_id.into(),
+        disabled: false,
+        max_values: None,
+        min_values: None,
+        options: Vec::new(),
+        placeholder: None,
+    })
+}
+
+#[derive(Debug, Clone)]
+pub struct SelectMenuBuilder(SelectMenu);
+
+impl SelectMenuBuilder {
+    /// Add an option with a label and a value.
+    pub fn option(mut self, label: impl Into<String>, value: impl Into<String>) -> Self {
+        self.0.options.push(SelectMenuOption {
+            default: false,
+            description: None,
+            emoji: None,
+            label: label.into(),
+            value: value.into(),
+        });
+        self
+    }
+
+    /// Set the text shown when nothing is selected.
+    pub fn placeholder(mut self, text: impl Into<String>) -> Self {
+        self.0.placeholder = Some(text.into());
+        self
+    }
+
+    /// Set how many options can be selected at once.
+    pub const fn values(mut self, min: u8, max: u8) -> Self {
+        self.0.min_values = Some(min);
+        self.0.max_values = Some(max);
+        self
+    }
+
+    /// Finalize the select menu.
+    pub fn build(self) -> Result<SelectMenu, ComponentError> {
+        match self.0.options.len() {
+            0 => Err(ComponentError::Empty),
+            n if n > MAX_OPTIONS => Err(ComponentError::TooManyOptions),
+            _ => Ok(self.0),
+        }
+    }
+}
+
+/// Builder for the action rows of a message.
+#[derive(Debug, Clone, Default)]
+pub struct ComponentsBuilder {
+    rows: Vec<ActionRow>,
+}
+
+impl ComponentsBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a row of buttons.
+    pub fn buttons(
+        mut self,
+        buttons: impl IntoIterator<Item = Button>,
+    ) -> Result<Self, ComponentError> {
+        let components: Vec<_> = buttons.into_iter().map(Component::Button).collect();
+
+        if components.is_empty() {
+            return Err(ComponentError::Empty);
+        }
+
+        if components.len() > MAX_BUTTONS {
+            return Err(ComponentError::TooManyButtons);
+        }
+
+        self.push(ActionRow { components })?;
+        Ok(self)
+    }
+
+    /// Add a row with a select menu.
+    pub fn select_menu(mut self, menu: SelectMenuBuilder) -> Result<Self, ComponentError> {
+        let menu = menu.build()?;
+        self.push(ActionRow {
+            components: vec![Component::SelectMenu(menu)],
+        })?;
+        Ok(self)
+    }
+
+    /// Add a row with a text input, for modals.
+    pub fn text_input(mut self, input: TextInput) -> Result<Self, ComponentError> {
+        self.push(ActionRow {
+            components: vec![Component::TextInput(input)],
+        })?;
+        Ok(self)
+    }
+
+    /// Finalize the components.
+    pub fn build(self) -> Vec<Component> {
+        self.rows.into_iter().map(Component::ActionRow).collect()
+    }
+
+    fn push(&mut self, row: ActionRow) -> Result<(), ComponentError> {
+        if self.rows.len() >= MAX_ROWS {
+            return Err(ComponentError::TooManyRows);
+        }
+
+        self.rows.push(row);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn buttons(n: usize) -> Vec<Button> {
+        (0..n)
+            .map(|i| button(i.to_string(), "label", ButtonStyle::Primary))
+            .collect()
+    }
+
+    #[test]
+    fn component_limits() {
+        let mut builder = ComponentsBuilder::new();
+        for _ in 0..MAX_ROWS {
+            builder = builder.buttons(buttons(MAX_BUTTONS)).unwrap();
+        }
+        assert_eq!(builder.clone().build().len(), MAX_ROWS);
+        assert_eq!(
+            builder.buttons(buttons(1)).unwrap_err(),
+            ComponentError::TooManyRows
+        );
+
+        assert_eq!(
+            ComponentsBuilder::new()
+                .buttons(buttons(MAX_BUTTONS + 1))
+                .unwrap_err(),
+            ComponentError::TooManyButtons
+        );
+        assert_eq!(
+            ComponentsBuilder::new().buttons([]).unwrap_err(),
+            ComponentError::Empty
+        );
+
+        let menu = (0..=MAX_OPTIONS).fold(select_menu("menu"), |m, i| {
+            m.option(i.to_string(), i.to_string())
+        });
+        assert_eq!(
+            ComponentsBuilder::new().select_menu(menu).unwrap_err(),
+            ComponentError::TooManyOptions
+        );
+    }
+}
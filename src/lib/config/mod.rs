@@ -1,13 +1,17 @@
 use std::any;
 use std::collections::hash_map::Entry;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::time::Duration;
 
 use derive_more::{Deref, Display};
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
+use twilight_gateway::Session;
 use twilight_model::channel::message::ReactionType;
-use twilight_model::id::marker::{ChannelMarker, GuildMarker, MessageMarker, RoleMarker};
+use twilight_model::id::marker::{
+    ChannelMarker, GuildMarker, MessageMarker, RoleMarker, UserMarker,
+};
 use twilight_model::id::Id;
 
 use crate::config::storage::{Directory, Storage};
@@ -27,6 +31,9 @@ pub type Custom = HashMap<String, serde_json::Value>;
 /// Whitelist collection type.
 pub type Whitelist = HashSet<Id<GuildMarker>>;
 
+/// Gateway sessions by shard number.
+pub type Sessions = HashMap<u64, Session>;
+
 /// Global bot settings.
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct GlobalSettings {
@@ -37,6 +44,115 @@ pub struct GlobalSettings {
     /// Whitelisted guilds, disabled if `None`.
     #[serde(default)]
     pub whitelist: Option<Whitelist>,
+
+    /// Keep the whitelist, but do not leave guilds that are not in it.
+    #[serde(default)]
+    pub whitelist_paused: bool,
+
+    /// Enabled commands by name, all commands are enabled if `None`.
+    /// The basic and bot owner commands are always enabled.
+    #[serde(default)]
+    pub enabled_commands: Option<HashSet<String>>,
+
+    /// Close shards on shutdown in a way that allows the sessions to be resumed on next startup.
+    ///
+    /// This skips re-identifying with Discord on a quick restart, so the bot is back sooner and
+    /// missed events are replayed instead of lost. However, if the bot is not restarted soon
+    /// enough, Discord will have invalidated the sessions and they are simply discarded.
+    /// Discord also considers the bot online until the sessions time out.
+    #[serde(default)]
+    pub resume_sessions: bool,
+
+    /// Seconds to wait for running commands and closing connections on shutdown,
+    /// default is used if `None`.
+    #[serde(default)]
+    pub shutdown_timeout: Option<u64>,
+
+    /// Joke command API settings.
+    #[serde(default)]
+    pub joke: JokeSettings,
+
+    /// Translation API settings, translate command is disabled if `None`.
+    #[serde(default)]
+    pub translate: Option<TranslateSettings>,
+
+    /// Limit of arguments parsed from a classic command, default is used if `None`.
+    #[serde(default)]
+    pub max_classic_args: Option<usize>,
+
+    /// Limit of messages deleted by a single purge, default is used if `None`.
+    #[serde(default)]
+    pub max_purge: Option<usize>,
+
+    /// Treat direct messages as classic commands even without the prefix.
+    #[serde(default)]
+    pub dm_no_prefix: bool,
+
+    /// Rotating bot activities, a static activity is used if `None`.
+    #[serde(default)]
+    pub activity: Option<ActivitySettings>,
+}
+
+/// Bot activity rotation settings.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActivitySettings {
+    /// Activities to cycle through, `{guilds}` and `{version}` are replaced with their values.
+    pub list: Vec<String>,
+
+    /// Seconds between activity changes.
+    #[serde(default = "ActivitySettings::default_interval")]
+    pub interval: u64,
+}
+
+impl ActivitySettings {
+    const fn default_interval() -> u64 {
+        300
+    }
+}
+
+/// LibreTranslate compatible API settings.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranslateSettings {
+    /// API endpoint for translation requests.
+    pub url: String,
+
+    /// API key, if required. Environment variable `TRANSLATE_API_KEY` is used if this is `None`.
+    #[serde(default)]
+    pub api_key: Option<String>,
+}
+
+/// Joke API endpoint and query settings.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JokeSettings {
+    /// API endpoint, the joke category is appended as a path segment.
+    pub url: String,
+
+    /// Category used when none is given.
+    #[serde(default)]
+    pub default_category: Option<String>,
+
+    /// Flags of jokes to filter out, e.g. `nsfw` or `racist`.
+    #[serde(default)]
+    pub blacklist: Vec<String>,
+}
+
+impl Default for JokeSettings {
+    fn default() -> Self {
+        Self {
+            url: String::from("https://v2.jokeapi.dev/joke"),
+            default_category: None,
+            blacklist: Vec::new(),
+        }
+    }
+}
+
+impl GlobalSettings {
+    /// Returns `true` if a command with `name` is enabled.
+    pub fn is_command_enabled(&self, name: &str) -> bool {
+        self.enabled_commands
+            .as_ref()
+            .is_none_or(|list| list.contains(name))
+    }
 }
 
 /// General guild settings.
@@ -53,6 +169,165 @@ pub struct GuildSettings {
     /// Guild reaction-role mappings.
     #[serde(default)]
     pub reaction_roles: HashMap<String, Vec<ReactionRole>>,
+
+    /// Nickname of the bot, reapplied when the guild becomes available.
+    #[serde(default)]
+    pub nickname: Option<String>,
+
+    /// Locale of the standard responses, English is used if `None`.
+    #[serde(default)]
+    pub locale: Option<String>,
+
+    /// Channel for moderation action logs, disabled if `None`.
+    #[serde(default)]
+    pub mod_log: Option<Id<ChannelMarker>>,
+
+    /// Channel where new members are welcomed, disabled if `None`.
+    #[serde(default)]
+    pub welcome_channel: Option<Id<ChannelMarker>>,
+
+    /// Role of the server moderators, if any.
+    #[serde(default)]
+    pub moderator_role: Option<Id<RoleMarker>>,
+
+    /// Channel for message edit logs, disabled if `None`.
+    #[serde(default)]
+    pub edit_log: Option<Id<ChannelMarker>>,
+
+    /// Channel for message deletion logs, disabled if `None`.
+    #[serde(default)]
+    pub delete_log: Option<Id<ChannelMarker>>,
+
+    /// Allow sniping of deleted messages, disabled by default.
+    #[serde(default)]
+    pub snipe: bool,
+
+    /// Buffer recent messages in memory for sniping and message logs, disabled by default.
+    #[serde(default)]
+    pub message_buffer: bool,
+
+    /// Join-gate for new members, disabled if `None`.
+    #[serde(default)]
+    pub verification: Option<Verification>,
+
+    /// Channels where commands are disabled.
+    #[serde(default)]
+    pub disabled_channels: HashSet<Id<ChannelMarker>>,
+
+    /// Channel for command error reports, the bot dev channel is used if `None`.
+    #[serde(default)]
+    pub error_channel: Option<Id<ChannelMarker>>,
+
+    /// Report the whole error chain in the error channel, instead of only what failed.
+    #[serde(default)]
+    pub verbose_errors: bool,
+}
+
+/// Join-gate settings, new members must click a button to be verified.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Verification {
+    /// Role given to new members until they are verified.
+    pub unverified_role: Id<RoleMarker>,
+
+    /// Role given to members once they are verified, if any.
+    #[serde(default)]
+    pub verified_role: Option<Id<RoleMarker>>,
+
+    /// Channel of the verification message.
+    pub channel: Id<ChannelMarker>,
+
+    /// Current verification message, posted again if missing.
+    #[serde(default)]
+    pub message: Option<Id<MessageMarker>>,
+}
+
+impl GuildSettings {
+    /// Parse settings from json, rejecting unknown fields.
+    pub fn from_json_strict(json: &[u8]) -> AnyResult<Self> {
+        let value: serde_json::Value = serde_json::from_slice(json).context("Invalid json")?;
+        let settings: Self =
+            serde_json::from_value(value.clone()).context("Invalid guild settings")?;
+
+        // Known fields are the ones that serialize back.
+        let known = serde_json::to_value(&settings)?;
+        let (Some(given), Some(known)) = (value.as_object(), known.as_object()) else {
+            anyhow::bail!("Expected a json object");
+        };
+
+        let unknown: Vec<_> = given.keys().filter(|k| !known.contains_key(*k)).collect();
+        anyhow::ensure!(
+            unknown.is_empty(),
+            "Unknown guild settings: {}",
+            utils::nice_list(&unknown)
+        );
+
+        Ok(settings)
+    }
+
+    /// Channels referred to by the settings, sorted and without duplicates.
+    pub fn channel_ids(&self) -> Vec<Id<ChannelMarker>> {
+        let mut ids: Vec<_> = [
+            self.mod_log,
+            self.welcome_channel,
+            self.edit_log,
+            self.delete_log,
+            self.error_channel,
+            self.verification.as_ref().map(|v| v.channel),
+        ]
+        .into_iter()
+        .flatten()
+        .chain(self.disabled_channels.iter().copied())
+        .collect();
+        ids.sort_unstable();
+        ids.dedup();
+        ids
+    }
+
+    /// Roles referred to by the settings, sorted and without duplicates.
+    pub fn role_ids(&self) -> Vec<Id<RoleMarker>> {
+        let verification = self.verification.as_ref();
+        let mut ids: Vec<_> = [
+            self.moderator_role,
+            verification.map(|v| v.unverified_role),
+            verification.and_then(|v| v.verified_role),
+        ]
+        .into_iter()
+        .flatten()
+        .chain(self.reaction_roles.values().flatten().map(|r| r.role))
+        .collect();
+        ids.sort_unstable();
+        ids.dedup();
+        ids
+    }
+}
+
+/// Guild moderation warnings.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct Warnings {
+    /// Id of the most recently added warning.
+    #[serde(default)]
+    pub last_id: u64,
+
+    /// Warnings by id.
+    #[serde(default)]
+    pub list: BTreeMap<u64, Warning>,
+}
+
+/// Guild command usage counts.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct CommandUsage {
+    /// Number of executions by command name.
+    #[serde(default)]
+    pub counts: HashMap<String, u64>,
+}
+
+/// Changes made by reloading the settings.
+#[derive(Debug, Default)]
+pub struct ReloadSummary {
+    /// Number of guilds with settings files.
+    pub guilds: usize,
+    /// Changed classic prefixes as `(guild, old, new)`, `None` for the global prefix.
+    pub prefixes: Vec<(Option<Id<GuildMarker>>, Prefix, Prefix)>,
 }
 
 #[derive(Debug)]
@@ -68,6 +343,9 @@ impl BotConfig {
         storage.bind::<GlobalSettings>("bot")?;
         storage.bind::<GuildSettings>("guild")?;
         storage.bind::<Custom>("custom")?;
+        storage.bind::<Sessions>("sessions")?;
+        storage.bind::<Warnings>("warnings")?;
+        storage.bind::<CommandUsage>("usage")?;
 
         Ok(Self {
             storage: storage.validated()?,
@@ -80,12 +358,12 @@ impl BotConfig {
     }
 
     /// Return general bot configuration directory.
-    pub fn global(&self) -> Global {
+    pub fn global(&self) -> Global<'_> {
         Global::new(self.storage.global())
     }
 
     /// Return guild configuration directory.
-    pub fn guild(&self, guild_id: Id<GuildMarker>) -> Guild {
+    pub fn guild(&self, guild_id: Id<GuildMarker>) -> Guild<'_> {
         Guild::new(self.storage.by_guild_id(guild_id), guild_id)
     }
 
@@ -110,8 +388,58 @@ impl BotConfig {
         self.storage.by_guild_id(guild_id).save_with(f)
     }
 
+    /// Read the global and guild settings again from their files, replacing them in memory.
+    /// If any of the files cannot be read, nothing is replaced.
+    pub fn reload(&self) -> AnyResult<ReloadSummary> {
+        let global = self
+            .storage
+            .global()
+            .read::<GlobalSettings>()
+            .context("Failed to reload bot settings")?;
+
+        let mut guilds = Vec::new();
+        for guild_id in self.storage.guild_ids()? {
+            let dir = self.storage.by_guild_id(guild_id);
+
+            // Guilds without settings use the defaults.
+            if !dir.path::<GuildSettings>()?.exists() {
+                continue;
+            }
+
+            let settings = dir
+                .read::<GuildSettings>()
+                .with_context(|| format!("Failed to reload settings of guild '{guild_id}'"))?;
+            guilds.push((guild_id, settings));
+        }
+
+        let mut summary = ReloadSummary {
+            guilds: guilds.len(),
+            ..Default::default()
+        };
+
+        let new_prefix = global.prefix.to_owned();
+        if let Some(old) = self.storage.global().replace(global) {
+            if old.prefix != new_prefix {
+                summary.prefixes.push((None, old.prefix, new_prefix));
+            }
+        }
+
+        for (guild_id, settings) in guilds {
+            let new_prefix = settings.prefix.to_owned();
+            if let Some(old) = self.storage.by_guild_id(guild_id).replace(settings) {
+                if old.prefix != new_prefix {
+                    summary
+                        .prefixes
+                        .push((Some(guild_id), old.prefix, new_prefix));
+                }
+            }
+        }
+
+        Ok(summary)
+    }
+
     /// Access custom data config.
-    pub fn custom_entry(&self, guild_id: Option<Id<GuildMarker>>) -> CustomEntry {
+    pub fn custom_entry(&self, guild_id: Option<Id<GuildMarker>>) -> CustomEntry<'_> {
         CustomEntry::new(self.directory(guild_id))
     }
 
@@ -132,7 +460,7 @@ impl BotConfig {
 
     /// Returns global storage directory if `guild_id` is `None`,
     /// otherwise returns guild storage directory by guild id.
-    fn directory(&self, guild_id: Option<Id<GuildMarker>>) -> Directory {
+    fn directory(&self, guild_id: Option<Id<GuildMarker>>) -> Directory<'_> {
         guild_id.map_or_else(
             || self.storage.global(),
             |guild_id| self.storage.by_guild_id(guild_id),
@@ -164,10 +492,74 @@ impl<'a> Global<'a> {
         Ok(&self.bot_settings()?.whitelist)
     }
 
+    /// Returns `true` if non-whitelisted guilds are not left.
+    pub fn whitelist_paused(&mut self) -> AnyResult<bool> {
+        Ok(self.bot_settings()?.whitelist_paused)
+    }
+
     /// Get global classic command prefix.
     pub fn classic_prefix(&mut self) -> AnyResult<&Prefix> {
         Ok(&self.bot_settings()?.prefix)
     }
+
+    /// Get joke command API settings.
+    pub fn joke_settings(&mut self) -> AnyResult<&JokeSettings> {
+        Ok(&self.bot_settings()?.joke)
+    }
+
+    /// Get translation API settings.
+    pub fn translate_settings(&mut self) -> AnyResult<&Option<TranslateSettings>> {
+        Ok(&self.bot_settings()?.translate)
+    }
+
+    /// Get activity rotation settings.
+    pub fn activity_settings(&mut self) -> AnyResult<&Option<ActivitySettings>> {
+        Ok(&self.bot_settings()?.activity)
+    }
+
+    /// Get the limit of arguments parsed from a classic command.
+    pub fn max_classic_args(&mut self) -> AnyResult<usize> {
+        Ok(self
+            .bot_settings()?
+            .max_classic_args
+            .unwrap_or(utils::consts::MAX_ARGS))
+    }
+
+    /// Get the limit of messages deleted by a single purge.
+    pub fn max_purge(&mut self) -> AnyResult<usize> {
+        Ok(self
+            .bot_settings()?
+            .max_purge
+            .unwrap_or(utils::consts::MAX_PURGE))
+    }
+
+    /// Get the time to wait for running commands and closing connections on shutdown.
+    pub fn shutdown_timeout(&mut self) -> AnyResult<Duration> {
+        Ok(Duration::from_secs(
+            self.bot_settings()?
+                .shutdown_timeout
+                .unwrap_or(utils::consts::SHUTDOWN_TIMEOUT),
+        ))
+    }
+
+    /// Returns `true` if direct messages do not need the classic prefix.
+    pub fn dm_no_prefix(&mut self) -> AnyResult<bool> {
+        Ok(self.bot_settings()?.dm_no_prefix)
+    }
+
+    /// Get stored gateway sessions.
+    pub fn sessions(&mut self) -> AnyResult<&Sessions> {
+        self.dir
+            .load_or_default()
+            .context("Failed to load gateway sessions")
+    }
+
+    /// Replace stored gateway sessions.
+    pub fn save_sessions(&mut self, sessions: Sessions) -> AnyResult<()> {
+        self.dir
+            .save(sessions)
+            .context("Failed to save gateway sessions")
+    }
 }
 
 /// Guild data entry guard.
@@ -195,6 +587,142 @@ impl<'a> Guild<'a> {
         Ok(&self.settings()?.prefix)
     }
 
+    /// Get the bot's nickname in the guild, if set.
+    pub fn nickname(&mut self) -> AnyResult<Option<String>> {
+        Ok(self.settings()?.nickname.to_owned())
+    }
+
+    /// Set or clear the bot's nickname in the guild.
+    pub fn set_nickname(&mut self, nickname: Option<String>) -> AnyResult<()> {
+        self.dir.save_with::<GuildSettings, _>(|s| {
+            s.nickname = nickname;
+            Ok(())
+        })
+    }
+
+    /// Set the current verification message of the join-gate, if it is enabled.
+    pub fn set_verification_message(&mut self, message_id: Id<MessageMarker>) -> AnyResult<()> {
+        self.dir.save_with::<GuildSettings, _>(|s| {
+            if let Some(v) = s.verification.as_mut() {
+                v.message = Some(message_id);
+            }
+            Ok(())
+        })
+    }
+
+    /// Get guild response locale, if set.
+    pub fn locale(&mut self) -> AnyResult<Option<String>> {
+        Ok(self.settings()?.locale.to_owned())
+    }
+
+    /// Get guild moderation log channel, if set.
+    pub fn mod_log(&mut self) -> AnyResult<Option<Id<ChannelMarker>>> {
+        Ok(self.settings()?.mod_log)
+    }
+
+    /// Get guild message edit log channel, if set.
+    pub fn edit_log(&mut self) -> AnyResult<Option<Id<ChannelMarker>>> {
+        Ok(self.settings()?.edit_log)
+    }
+
+    /// Get guild message deletion log channel, if set.
+    pub fn delete_log(&mut self) -> AnyResult<Option<Id<ChannelMarker>>> {
+        Ok(self.settings()?.delete_log)
+    }
+
+    /// Get guild error report channel and verbosity, if the channel is set.
+    pub fn error_channel(&mut self) -> AnyResult<Option<(Id<ChannelMarker>, bool)>> {
+        let settings = self.settings()?;
+        Ok(settings.error_channel.map(|c| (c, settings.verbose_errors)))
+    }
+
+    /// Check if sniping deleted messages is enabled.
+    pub fn snipe_enabled(&mut self) -> AnyResult<bool> {
+        Ok(self.settings()?.snipe)
+    }
+
+    /// Check if recent messages should be buffered.
+    pub fn message_buffer_enabled(&mut self) -> AnyResult<bool> {
+        Ok(self.settings()?.message_buffer)
+    }
+
+    /// Get join-gate settings.
+    pub fn verification(&mut self) -> AnyResult<Option<Verification>> {
+        Ok(self.settings()?.verification.to_owned())
+    }
+
+    /// Get channels where commands are disabled.
+    pub fn disabled_channels(&mut self) -> AnyResult<HashSet<Id<ChannelMarker>>> {
+        Ok(self.settings()?.disabled_channels.to_owned())
+    }
+
+    /// Disable or enable commands in a channel. Returns `false` if nothing changed.
+    pub fn set_channel_disabled(
+        &mut self,
+        channel_id: Id<ChannelMarker>,
+        disabled: bool,
+    ) -> AnyResult<bool> {
+        let mut changed = false;
+        self.dir.save_with::<GuildSettings, _>(|s| {
+            changed = if disabled {
+                s.disabled_channels.insert(channel_id)
+            } else {
+                s.disabled_channels.remove(&channel_id)
+            };
+            Ok(())
+        })?;
+        Ok(changed)
+    }
+
+    /// Get warnings of a user, ordered by id.
+    pub fn warnings(&mut self, user_id: Id<UserMarker>) -> AnyResult<Vec<(u64, Warning)>> {
+        self.dir
+            .load_or_default::<Warnings>()
+            .context("Failed to load warnings")
+            .map(|w| {
+                w.list
+                    .iter()
+                    .filter(|(_, w)| w.user_id == user_id)
+                    .map(|(&id, w)| (id, w.to_owned()))
+                    .collect()
+            })
+    }
+
+    /// Add a warning and return its id.
+    pub fn add_warning(&mut self, warning: Warning) -> AnyResult<u64> {
+        self.dir.save_with::<Warnings, _>(|w| {
+            w.last_id += 1;
+            w.list.insert(w.last_id, warning);
+            Ok(w.last_id)
+        })
+    }
+
+    /// Remove a warning by id, returns the removed warning if it existed.
+    pub fn remove_warning(&mut self, id: u64) -> AnyResult<Option<Warning>> {
+        self.dir
+            .save_with::<Warnings, _>(|w| Ok(w.list.remove(&id)))
+    }
+
+    /// Get command usage counts, most used first.
+    pub fn command_usage(&mut self) -> AnyResult<Vec<(String, u64)>> {
+        self.dir
+            .load_or_default::<CommandUsage>()
+            .context("Failed to load command usage")
+            .map(|u| {
+                let mut counts: Vec<_> = u.counts.iter().map(|(n, &c)| (n.to_owned(), c)).collect();
+                counts.sort_unstable_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+                counts
+            })
+    }
+
+    /// Increment the usage count of a command.
+    pub fn add_command_usage(&mut self, name: &str) -> AnyResult<()> {
+        self.dir.save_with::<CommandUsage, _>(|u| {
+            *u.counts.entry(name.to_string()).or_default() += 1;
+            Ok(())
+        })
+    }
+
     /// Get a reaction-roles configuration by channel and message ids.
     pub fn reaction_roles(
         &mut self,
@@ -368,10 +896,16 @@ impl<'a> CustomEntry<'a> {
 }
 
 /// Bot classic command prefix.
-#[derive(Debug, Clone, Deref, Display, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Deref, Display, Serialize, Deserialize)]
 pub struct Prefix(String);
 
 impl Prefix {
+    /// Create a prefix, `None` if it is empty or contains whitespace.
+    pub fn new(prefix: &str) -> Option<Self> {
+        (!prefix.is_empty() && !prefix.contains(char::is_whitespace))
+            .then(|| Self(prefix.to_string()))
+    }
+
     pub fn into_inner(self) -> String {
         self.0
     }
@@ -407,3 +941,52 @@ impl PartialEq for ReactionRole {
         utils::reaction_type_eq(&self.emoji, &other.emoji) && self.role == other.role
     }
 }
+
+/// Moderation warning given to a user.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Warning {
+    pub user_id: Id<UserMarker>,
+    pub moderator_id: Id<UserMarker>,
+    pub reason: String,
+    /// Unix timestamp in seconds.
+    pub timestamp: i64,
+}
+
+impl Warning {
+    pub fn new(user_id: Id<UserMarker>, moderator_id: Id<UserMarker>, reason: String) -> Self {
+        Self {
+            user_id,
+            moderator_id,
+            reason,
+            timestamp: chrono::Utc::now().timestamp(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strict_guild_settings() {
+        let settings =
+            GuildSettings::from_json_strict(br#"{ "prefix": "?", "snipe": true }"#).unwrap();
+        assert_eq!(settings.prefix.as_ref(), "?");
+        assert!(settings.snipe);
+
+        assert!(GuildSettings::from_json_strict(br#"{ "prefx": "?" }"#).is_err());
+        assert!(GuildSettings::from_json_strict(br#"{ "snipe": "yes" }"#).is_err());
+        assert!(GuildSettings::from_json_strict(b"[]").is_err());
+
+        let settings = GuildSettings::from_json_strict(
+            br#"{
+                "mod_log": "2", "error_channel": "1", "disabled_channels": ["2"],
+                "moderator_role": "5",
+                "verification": { "unverified_role": "4", "verified_role": "5", "channel": "3" }
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(settings.channel_ids(), [Id::new(1), Id::new(2), Id::new(3)]);
+        assert_eq!(settings.role_ids(), [Id::new(4), Id::new(5)]);
+    }
+}
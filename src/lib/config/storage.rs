@@ -106,7 +106,7 @@ impl Storage {
     ///
     /// # Panics
     /// If something goes wrong with internal mutex.
-    pub fn global(&self) -> Directory {
+    pub fn global(&self) -> Directory<'_> {
         Directory {
             dir: PathBuf::from(Self::GLOBAL),
             names: &self.names,
@@ -121,7 +121,7 @@ impl Storage {
     ///
     /// # Panics
     /// If something goes wrong with internal mutex.
-    pub fn by_guild_id(&self, guild_id: Id<GuildMarker>) -> Directory {
+    pub fn by_guild_id(&self, guild_id: Id<GuildMarker>) -> Directory<'_> {
         Directory {
             dir: PathBuf::from(format!("{}{guild_id}/", Self::GUILDS)),
             names: &self.names,
@@ -129,6 +129,24 @@ impl Storage {
         }
     }
 
+    /// Get ids of the guilds that have a storage directory on disk.
+    pub fn guild_ids(&self) -> AnyResult<Vec<Id<GuildMarker>>> {
+        let entries = match fs::read_dir(Self::GUILDS) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e).context("Failed to read guilds dir"),
+        };
+
+        let mut ids = Vec::new();
+        for entry in entries {
+            let entry = entry.context("Failed to read guilds dir")?;
+            if let Some(id) = entry.file_name().to_str().and_then(|n| n.parse().ok()) {
+                ids.push(id);
+            }
+        }
+        Ok(ids)
+    }
+
     /// Bind a type to a config name.
     ///
     /// # Errors
@@ -221,6 +239,29 @@ impl Directory<'_> {
         Ok(path)
     }
 
+    /// Read a type value from its config file, without keeping it in memory.
+    pub fn read<T>(&self) -> AnyResult<T>
+    where
+        T: Storable,
+    {
+        Config::read(&self.path::<T>()?)
+    }
+
+    /// Replace a type value in memory without writing config.
+    /// Returns the previous value, if it was in memory.
+    pub fn replace<T>(&mut self, value: T) -> Option<T>
+    where
+        T: Storable,
+    {
+        let id = TypeId::of::<T>();
+        self.data
+            .entry(self.dir.to_owned())
+            .or_default()
+            .insert(id, Box::new(value))
+            .and_then(|old| (old as Box<dyn Any>).downcast().ok())
+            .map(|old| *old)
+    }
+
     /// Save a type value and write config.
     pub fn save<T>(&mut self, value: T) -> AnyResult<()>
     where
@@ -0,0 +1,165 @@
+//! Confirm and cancel button prompts.
+//!
+//! A prompt is posted with two buttons and its state is kept in memory, keyed by the message id.
+//! Button presses are routed here by the component handler, which resolves the waiting prompt.
+//! Either way, the buttons are disabled once the prompt is answered or has timed out.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use tokio::sync::oneshot;
+use twilight_model::application::interaction::message_component::MessageComponentInteractionData;
+use twilight_model::application::interaction::Interaction;
+use twilight_model::channel::message::component::{Button, ButtonStyle};
+use twilight_model::channel::message::{Component, MessageFlags};
+use twilight_model::http::interaction::{
+    InteractionResponse, InteractionResponseData, InteractionResponseType,
+};
+use twilight_model::id::marker::{ChannelMarker, MessageMarker, UserMarker};
+use twilight_model::id::Id;
+
+use crate::components::{self, ComponentsBuilder};
+use crate::utils::prelude::*;
+use crate::Context;
+
+/// Custom id prefix of the confirm buttons.
+pub const PREFIX: &str = "confirm";
+
+/// State of a prompt waiting for an answer.
+#[derive(Debug)]
+struct Pending {
+    owner: Id<UserMarker>,
+    answer: oneshot::Sender<bool>,
+}
+
+/// Prompts waiting for an answer.
+#[derive(Debug, Default)]
+pub struct Confirms {
+    pending: Mutex<HashMap<Id<MessageMarker>, Pending>>,
+}
+
+impl Confirms {
+    /// Confirm and cancel buttons, optionally disabled.
+    pub fn buttons(disabled: bool) -> Vec<Component> {
+        let button = |id: &str, label: &str, style| Button {
+            disabled,
+            ..components::button(format!("{PREFIX}:{id}"), label, style)
+        };
+
+        ComponentsBuilder::new()
+            .buttons([
+                button("yes", "✅ Confirm", ButtonStyle::Success),
+                button("no", "❌ Cancel", ButtonStyle::Danger),
+            ])
+            .expect("Confirm buttons should fit in a row")
+            .build()
+    }
+}
+
+/// Post a prompt and wait for `owner` to confirm or cancel it.
+/// Returns `false` if the prompt was cancelled or timed out.
+pub async fn confirm_buttons(
+    ctx: &Context,
+    channel_id: Id<ChannelMarker>,
+    prompt: &str,
+    owner: Id<UserMarker>,
+    timeout: Duration,
+) -> AnyResult<bool> {
+    let msg = ctx
+        .http
+        .create_message(channel_id)
+        .content(prompt)?
+        .components(&Confirms::buttons(false))?
+        .send()
+        .await
+        .context("Failed to post confirm prompt")?;
+
+    let (tx, rx) = oneshot::channel();
+    ctx.confirms
+        .pending
+        .lock()
+        .unwrap()
+        .insert(msg.id, Pending { owner, answer: tx });
+
+    let answer = ctx.until_shutdown(tokio::time::timeout(timeout, rx));
+    if let Some(Ok(Ok(answer))) = answer.await {
+        return Ok(answer); // Buttons were disabled by the component handler.
+    }
+
+    // Timed out or shutting down, so nobody else will answer it.
+    ctx.confirms.pending.lock().unwrap().remove(&msg.id);
+
+    ctx.http
+        .update_message(channel_id, msg.id)
+        .content(Some(&format!("{prompt}\n*Timed out.*")))?
+        .components(Some(&Confirms::buttons(true)))?
+        .await
+        .context("Failed to disable confirm buttons")?;
+
+    Ok(false)
+}
+
+/// Handle a confirm button press.
+pub async fn handle_component(
+    ctx: &Context,
+    inter: &Interaction,
+    data: &MessageComponentInteractionData,
+) -> AnyResult<()> {
+    let message = inter
+        .message
+        .as_ref()
+        .context("Component interaction without a message")?;
+
+    let answer = {
+        let mut pending = ctx.confirms.pending.lock().unwrap();
+        match pending.get(&message.id) {
+            None => None,
+            Some(p) if Some(p.owner) != inter.author_id() => Some(Err(())),
+            Some(_) => {
+                let p = pending.remove(&message.id).expect("Entry should exist");
+                let answer = data.custom_id.split_once(':').map(|(_, a)| a) == Some("yes");
+                let _ = p.answer.send(answer);
+                Some(Ok(answer))
+            },
+        }
+    };
+
+    let data = match answer {
+        Some(Ok(answer)) => InteractionResponseData {
+            content: Some(format!(
+                "{}\n*{}.*",
+                message.content,
+                if answer { "Confirmed" } else { "Cancelled" }
+            )),
+            components: Some(Confirms::buttons(true)),
+            ..Default::default()
+        },
+        Some(Err(())) => InteractionResponseData {
+            content: Some("Only the command user can answer this.".to_string()),
+            flags: Some(MessageFlags::EPHEMERAL),
+            ..Default::default()
+        },
+        None => InteractionResponseData {
+            content: Some("This prompt has expired.".to_string()),
+            flags: Some(MessageFlags::EPHEMERAL),
+            ..Default::default()
+        },
+    };
+
+    let kind = if data.flags.is_some() {
+        InteractionResponseType::ChannelMessageWithSource
+    } else {
+        InteractionResponseType::UpdateMessage
+    };
+
+    ctx.interaction()
+        .create_response(inter.id, &inter.token, &InteractionResponse {
+            kind,
+            data: Some(data),
+        })
+        .await
+        .context("Failed to respond to confirm button")?;
+
+    Ok(())
+}
@@ -0,0 +1,273 @@
+//! Interactive help messages, navigated with buttons through subcommands and groups.
+//!
+//! A help message keeps the shown command path in memory, keyed by the message id.
+//! Each button opens a subcommand or group of the current path and the message is edited
+//! to show its help. After some inactivity the state is dropped and the buttons disabled.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use tokio::time::Instant;
+use twilight_model::application::interaction::message_component::MessageComponentInteractionData;
+use twilight_model::application::interaction::Interaction;
+use twilight_model::channel::message::component::{ActionRow, Button, ButtonStyle};
+use twilight_model::channel::message::{Component, MessageFlags};
+use twilight_model::http::interaction::{
+    InteractionResponse, InteractionResponseData, InteractionResponseType,
+};
+use twilight_model::id::marker::{ChannelMarker, MessageMarker, UserMarker};
+use twilight_model::id::Id;
+
+use crate::commands::{Commands, Lookup};
+use crate::components::{MAX_BUTTONS, MAX_ROWS};
+use crate::utils::prelude::*;
+use crate::{components, Context};
+
+/// Custom id prefix of the help buttons.
+pub const PREFIX: &str = "help";
+
+/// Time after which an unused help message is no longer navigable.
+const INACTIVITY_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+
+/// State of a help message.
+#[derive(Debug, Clone)]
+struct MenuState {
+    channel_id: Id<ChannelMarker>,
+    path: Vec<&'static str>,
+    /// Only this user can navigate, anyone can if `None`.
+    owner: Option<Id<UserMarker>>,
+    last_used: Instant,
+}
+
+/// Store of interactive help messages.
+#[derive(Debug, Default)]
+pub struct HelpMenus {
+    states: Mutex<HashMap<Id<MessageMarker>, MenuState>>,
+}
+
+/// Help text and navigation buttons of a command path, `None` if the path does not resolve.
+fn view(commands: &Commands, path: &[&str], disabled: bool) -> Option<(String, Vec<Component>)> {
+    let resolved = commands.resolve(path)?;
+
+    let children: Vec<_> = match resolved.target() {
+        Lookup::Command(c) => c
+            .options
+            .iter()
+            .filter_map(Lookup::from_option)
+            .map(|l| l.name())
+            .collect(),
+        Lookup::Group(g) => g.subs.iter().map(|s| s.name).collect(),
+    };
+
+    let button = |id: String, label: &str, style, disabled| Button {
+        disabled,
+        ..components::button(format!("{PREFIX}:{id}"), label, style)
+    };
+
+    // Last row is kept for navigation.
+    let mut rows: Vec<_> = children
+        .chunks(MAX_BUTTONS)
+        .take(MAX_ROWS - 1)
+        .map(|chunk| {
+            Component::ActionRow(ActionRow {
+                components: chunk
+                    .iter()
+                    .map(|name| {
+                        Component::Button(button(
+                            format!("open:{name}"),
+                            name,
+                            ButtonStyle::Primary,
+                            disabled,
+                        ))
+                    })
+                    .collect(),
+            })
+        })
+        .collect();
+
+    if path.len() > 1 {
+        rows.push(Component::ActionRow(ActionRow {
+            components: vec![Component::Button(button(
+                "back".to_string(),
+                "◀ Back",
+                ButtonStyle::Secondary,
+                disabled,
+            ))],
+        }));
+    }
+
+    Some((resolved.generate_help(), rows))
+}
+
+/// Edit a deferred interaction response to show the help of a command path,
+/// with buttons to its subcommands and groups.
+/// Navigation is restricted to `owner`, or allowed for anyone if `None`.
+pub async fn update_response(
+    ctx: &Context,
+    token: &str,
+    path: Vec<&'static str>,
+    owner: Option<Id<UserMarker>>,
+) -> AnyResult<()> {
+    let (content, components) =
+        view(&ctx.commands, &path, false).context("Help path does not resolve")?;
+
+    let msg = ctx
+        .interaction()
+        .update_response(token)
+        .content(Some(&content))?
+        .components(Some(&components))?
+        .send()
+        .await?;
+
+    if !components.is_empty() {
+        register(ctx, msg.id, msg.channel_id, path, owner);
+    }
+
+    Ok(())
+}
+
+/// Handle a help button press.
+pub async fn handle_component(
+    ctx: &Context,
+    inter: &Interaction,
+    data: &MessageComponentInteractionData,
+) -> AnyResult<()> {
+    let message_id = inter
+        .message
+        .as_ref()
+        .map(|m| m.id)
+        .context("Component interaction without a message")?;
+    let user_id = inter.author_id();
+
+    let view = {
+        let mut states = ctx.help_menus.states.lock().unwrap();
+        match states.get_mut(&message_id) {
+            None => None,
+            Some(state) if state.owner.is_some_and(|o| Some(o) != user_id) => Some(Err(())),
+            Some(state) => {
+                let mut path = state.path.to_owned();
+                match data.custom_id.split_once(':').map(|(_, a)| a) {
+                    Some("back") if path.len() > 1 => {
+                        path.pop();
+                    },
+                    Some(action) => {
+                        let child = action.strip_prefix("open:").and_then(|name| {
+                            let resolved = ctx.commands.resolve(&path)?;
+                            let child = resolved.target().child(name)?;
+                            Some(child.name())
+                        });
+                        match child {
+                            Some(name) => path.push(name),
+                            None => debug!("Unknown help action: {action:?}"),
+                        }
+                    },
+                    None => debug!("Unknown help action: {:?}", data.custom_id),
+                }
+
+                let view = view(&ctx.commands, &path, false);
+                if view.is_some() {
+                    state.path = path;
+                }
+                state.last_used = Instant::now();
+                view.map(Ok)
+            },
+        }
+    };
+
+    let data = match view {
+        Some(Ok((content, components))) => InteractionResponseData {
+            content: Some(content),
+            components: Some(components),
+            ..Default::default()
+        },
+        Some(Err(())) => InteractionResponseData {
+            content: Some("Only the command user can navigate this help.".to_string()),
+            flags: Some(MessageFlags::EPHEMERAL),
+            ..Default::default()
+        },
+        None => InteractionResponseData {
+            content: Some("This help has expired.".to_string()),
+            flags: Some(MessageFlags::EPHEMERAL),
+            ..Default::default()
+        },
+    };
+
+    let kind = if data.flags.is_some() {
+        InteractionResponseType::ChannelMessageWithSource
+    } else {
+        InteractionResponseType::UpdateMessage
+    };
+
+    ctx.interaction()
+        .create_response(inter.id, &inter.token, &InteractionResponse {
+            kind,
+            data: Some(data),
+        })
+        .await
+        .context("Failed to respond to help navigation")?;
+
+    Ok(())
+}
+
+/// Store the state of a help message and expire it after inactivity.
+fn register(
+    ctx: &Context,
+    message_id: Id<MessageMarker>,
+    channel_id: Id<ChannelMarker>,
+    path: Vec<&'static str>,
+    owner: Option<Id<UserMarker>>,
+) {
+    ctx.help_menus
+        .states
+        .lock()
+        .unwrap()
+        .insert(message_id, MenuState {
+            channel_id,
+            path,
+            owner,
+            last_used: Instant::now(),
+        });
+
+    let ctx = ctx.to_owned();
+    tokio::spawn(async move {
+        loop {
+            let deadline = match ctx.help_menus.states.lock().unwrap().get(&message_id) {
+                Some(state) => state.last_used + INACTIVITY_TIMEOUT,
+                None => return,
+            };
+
+            if Instant::now() < deadline {
+                tokio::time::sleep_until(deadline).await;
+                continue;
+            }
+
+            let Some(state) = ctx.help_menus.states.lock().unwrap().remove(&message_id) else {
+                return;
+            };
+
+            if let Err(e) = disable_buttons(&ctx, message_id, &state).await {
+                debug!("Could not disable expired help buttons: {e}");
+            }
+
+            return;
+        }
+    });
+}
+
+/// Disable the buttons of an expired help message.
+async fn disable_buttons(
+    ctx: &Context,
+    message_id: Id<MessageMarker>,
+    state: &MenuState,
+) -> AnyResult<()> {
+    let Some((_, components)) = view(&ctx.commands, &state.path, true) else {
+        return Ok(());
+    };
+
+    ctx.http
+        .update_message(state.channel_id, message_id)
+        .components(Some(&components))?
+        .await?;
+    Ok(())
+}
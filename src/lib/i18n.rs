@@ -0,0 +1,127 @@
+//! Minimal localization of standard bot responses.
+//!
+//! Translations are loaded from `./data/lang/<locale>.json` files,
+//! each one being an object of `"key": "template"` pairs.
+//! Templates can contain `{name}` placeholders. Missing keys fall back to English.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use crate::utils::prelude::*;
+
+/// Locale used when none is configured.
+pub const DEFAULT_LOCALE: &str = "en";
+
+/// Directory of the translation files.
+pub const LANG_DIR: &str = "./data/lang/";
+
+/// Built-in English templates.
+const ENGLISH: &[(&str, &str)] = &[
+    (
+        "error",
+        "The bot has encountered an error executing the command! 😕",
+    ),
+    ("access-denied", "Rekt, you cannot use that. :melting_face:"),
+    ("command-disabled", "That command is not available here."),
+    ("on-cooldown", "Slow down, try again in {seconds} seconds."),
+    (
+        "bot-missing-permissions",
+        "I cannot do that here, I am missing permissions: {permissions}",
+    ),
+    (
+        "maintenance",
+        "The bot is under maintenance, try again later. 🛠️",
+    ),
+    (
+        "mention-help",
+        "Try `/about` or `{prefix}about` for general info, or `/help` or `{prefix}help` for \
+         commands.",
+    ),
+];
+
+/// Response templates by locale and key.
+#[derive(Debug, Default)]
+pub struct Locales {
+    map: HashMap<String, HashMap<String, String>>,
+}
+
+impl Locales {
+    /// Load translations from the language directory, if it exists.
+    pub fn load() -> AnyResult<Self> {
+        let mut locales = Self::default();
+        let dir = Path::new(LANG_DIR);
+
+        if !dir.is_dir() {
+            return Ok(locales);
+        }
+
+        for entry in fs::read_dir(dir).context("Failed to read language directory")? {
+            let path = entry?.path();
+            if path.extension().is_none_or(|e| e != "json") {
+                continue;
+            }
+
+            let Some(locale) = path.file_stem().and_then(|s| s.to_str()) else {
+                continue;
+            };
+
+            let text = fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read '{}'", path.display()))?;
+            let templates = serde_json::from_str(&text)
+                .with_context(|| format!("Failed to parse '{}'", path.display()))?;
+
+            locales.map.insert(locale.to_lowercase(), templates);
+        }
+
+        Ok(locales)
+    }
+
+    /// Get a response by `key` in `locale`, with placeholders replaced by `args`.
+    /// Falls back to English, or the key itself if it is unknown.
+    pub fn text(&self, locale: Option<&str>, key: &str, args: &[(&str, &str)]) -> String {
+        let locale = locale.unwrap_or(DEFAULT_LOCALE).to_lowercase();
+
+        let lookup = |locale: &str| {
+            self.map
+                .get(locale)
+                .and_then(|t| t.get(key))
+                .map(String::as_str)
+        };
+
+        let template = lookup(&locale)
+            .or_else(|| lookup(DEFAULT_LOCALE))
+            .or_else(|| ENGLISH.iter().find(|(k, _)| *k == key).map(|(_, t)| *t))
+            .unwrap_or(key);
+
+        args.iter()
+            .fold(template.to_string(), |text, (name, value)| {
+                text.replace(&format!("{{{name}}}"), value)
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn text_fallback() {
+        let mut locales = Locales::default();
+        locales.map.insert(
+            "fi".to_string(),
+            HashMap::from([("access-denied".to_string(), "Ei käy, {name}.".to_string())]),
+        );
+
+        assert_eq!(
+            locales.text(Some("FI"), "access-denied", &[("name", "kaveri")]),
+            "Ei käy, kaveri."
+        );
+        assert_eq!(locales.text(Some("fi"), "error", &[]), ENGLISH[0].1);
+        assert_eq!(locales.text(Some("xx"), "error", &[]), ENGLISH[0].1);
+        assert_eq!(locales.text(None, "unknown-key", &[]), "unknown-key");
+        assert!(locales
+            .text(None, "mention-help", &[("prefix", "!")])
+            .contains("`!about`"));
+    }
+}
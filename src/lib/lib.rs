@@ -6,8 +6,11 @@
 
 use std::env;
 use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 
+use derive_more::Display;
 use tokio::sync::mpsc::UnboundedSender;
 use twilight_cache_inmemory::InMemoryCache;
 use twilight_gateway::stream::ShardRef;
@@ -16,26 +19,56 @@ use twilight_gateway::{
 };
 use twilight_http::client::InteractionClient;
 use twilight_http::Client;
-use twilight_model::channel::Channel;
+use twilight_model::channel::message::AllowedMentions;
+use twilight_model::channel::{Channel, Message};
 use twilight_model::gateway::payload::incoming::{ChannelUpdate, RoleUpdate};
 use twilight_model::gateway::payload::outgoing::update_presence::UpdatePresencePayload;
 use twilight_model::gateway::presence::{ActivityType, MinimalActivity, Status};
 use twilight_model::gateway::Intents;
-use twilight_model::guild::Role;
+use twilight_model::guild::{Permissions, Role};
 use twilight_model::id::marker::{ChannelMarker, GuildMarker, RoleMarker, UserMarker};
 use twilight_model::id::Id;
 use twilight_model::oauth::Application;
-use twilight_model::user::CurrentUser;
+use twilight_model::user::{CurrentUser, User};
 use twilight_standby::Standby;
+use twilight_util::permission_calculator::PermissionCalculator;
 
+use crate::commands::cache::ResultCache;
 use crate::commands::Commands;
 use crate::config::BotConfig;
+use crate::confirm::Confirms;
+use crate::help::HelpMenus;
+use crate::i18n::Locales;
+use crate::modals::Modals;
+use crate::paginator::Paginator;
+use crate::reconcile::Reconciler;
+use crate::reminders::Reminders;
+use crate::snipe::Snipes;
+use crate::tasks::{DelayedTasks, RunningTasks};
+use crate::users::FetchedUsers;
 use crate::utils::prelude::*;
 
+pub mod activity;
 pub mod commands;
+pub mod components;
 pub mod config;
+pub mod confirm;
+pub mod help;
+pub mod i18n;
+pub mod modals;
+pub mod paginator;
 pub mod parser;
+pub mod reconcile;
+pub mod reminders;
+pub mod snipe;
+pub mod tasks;
+pub mod time;
+pub mod users;
 pub mod utils;
+pub mod verify;
+
+#[cfg(test)]
+mod test_utils;
 
 pub type BotEventSender = UnboundedSender<BotEvent>;
 
@@ -65,6 +98,32 @@ pub struct Context {
     pub cache: Arc<InMemoryCache>,
     /// Standby twilight event system.
     pub standby: Arc<Standby>,
+    /// Localized response templates.
+    pub locales: Arc<Locales>,
+    /// Paginated messages.
+    pub pages: Arc<Paginator>,
+    /// Prompts waiting for confirmation.
+    pub confirms: Arc<Confirms>,
+    /// Modals waiting for a submission.
+    pub modals: Arc<Modals>,
+    /// Interactive help messages.
+    pub help_menus: Arc<HelpMenus>,
+    /// Users fetched from the client.
+    pub fetched_users: Arc<FetchedUsers>,
+    /// Recently deleted messages.
+    pub snipes: Arc<Snipes>,
+    /// Pending reminders of each user.
+    pub reminders: Arc<Reminders>,
+    /// Startup work scheduler.
+    pub reconciler: Arc<Reconciler>,
+    /// Cached command replies.
+    pub results: Arc<ResultCache>,
+    /// Maintenance mode, where only the owners can use commands.
+    pub maintenance: Arc<AtomicBool>,
+    /// Event handlers that are still running.
+    pub running: Arc<RunningTasks>,
+    /// Tasks waiting to run later, cancelled on shutdown.
+    pub delayed: Arc<DelayedTasks>,
     /// Shard associated with the event.
     pub shard: Option<PartialShard>,
     /// Songbird voice manager.
@@ -75,9 +134,10 @@ pub struct Context {
 impl Context {
     pub async fn new(
         events_tx: BotEventSender,
+        config: BotConfig,
         commands: Commands,
     ) -> AnyResult<(Self, Vec<Shard>)> {
-        let config = Arc::new(BotConfig::new()?);
+        let config = Arc::new(config);
         let commands = Arc::new(commands);
         let token = env::var("DISCORD_TOKEN").expect("Expected a token in the environment");
         let http = Arc::new(Client::new(token.to_owned()));
@@ -85,6 +145,30 @@ impl Context {
         let user = Arc::new(http.current_user().send().await?);
         let cache = Arc::new(InMemoryCache::new());
         let standby = Arc::new(Standby::new());
+        let locales = Arc::new(Locales::load().unwrap_or_else(|e| {
+            warn!("Failed to load translations: {}", e.oneliner());
+            Locales::default()
+        }));
+        let pages = Arc::new(Paginator::default());
+        let confirms = Arc::new(Confirms::default());
+        let modals = Arc::new(Modals::default());
+        let help_menus = Arc::new(HelpMenus::default());
+        let fetched_users = Arc::new(FetchedUsers::default());
+        let snipes = Arc::new(Snipes::default());
+        let reminders = Arc::new(Reminders::default());
+        let reconciler = Arc::new(Reconciler::default());
+        let results = Arc::new(ResultCache::default());
+        let maintenance = Arc::new(AtomicBool::new(false));
+        let running = Arc::new(RunningTasks::default());
+        let delayed = Arc::new(DelayedTasks::default());
+
+        // Take any sessions stored on previous shutdown, they are only valid once.
+        let sessions = {
+            let mut global = config.global();
+            let sessions = global.sessions()?.to_owned();
+            global.save_sessions(Default::default())?;
+            sessions
+        };
 
         let shards = stream::create_recommended(
             &http,
@@ -102,7 +186,13 @@ impl Context {
                     Status::Online,
                 )?)
                 .build(),
-            |_, builder| builder.build(),
+            |id, builder| match sessions.get(&id.number()) {
+                Some(session) => {
+                    info!("Resuming session on shard {id}");
+                    builder.session(session.to_owned()).build()
+                },
+                None => builder.build(),
+            },
         )
         .await?
         .collect::<Vec<_>>();
@@ -130,6 +220,19 @@ impl Context {
                 user,
                 cache,
                 standby,
+                locales,
+                pages,
+                confirms,
+                modals,
+                help_menus,
+                fetched_users,
+                snipes,
+                reminders,
+                reconciler,
+                results,
+                maintenance,
+                running,
+                delayed,
                 shard: None,
                 #[cfg(feature = "voice")]
                 voice,
@@ -146,6 +249,9 @@ impl Context {
     ) where
         Fut: Future<Output = AnyResult<()>> + Send + 'static,
     {
+        // Keep deleted messages before the cache forgets them.
+        self.snipes.hold(&self.cache, &event);
+
         // Update the cache with the event.
         self.cache.update(&event);
 
@@ -157,11 +263,9 @@ impl Context {
         let processed = self.standby.process(&event);
         log_processed(processed);
 
-        // Handle event.
-        tokio::spawn(handler(
-            self.clone().with_shard(shard.id(), shard.sender()),
-            event,
-        ));
+        // Handle event, it is waited for on shutdown.
+        let handler = handler(self.clone().with_shard(shard.id(), shard.sender()), event);
+        self.running.spawn(handler);
     }
 
     /// Get role objects with `ids` from cache or fetch from client.
@@ -206,6 +310,146 @@ impl Context {
         }
     }
 
+    /// Get a channel like [`Self::channel_from`], but only if it belongs to the guild.
+    pub async fn guild_channel_from(
+        &self,
+        guild_id: Id<GuildMarker>,
+        channel_id: Id<ChannelMarker>,
+    ) -> AnyResult<Channel> {
+        let chan = self.channel_from(channel_id).await?;
+        ensure_guild_channel(&chan, guild_id)?;
+        Ok(chan)
+    }
+
+    /// Post a prompt with confirm and cancel buttons, and wait for `owner` to answer.
+    /// Returns `false` if cancelled, or if not answered within `timeout`.
+    pub async fn confirm_buttons(
+        &self,
+        channel_id: Id<ChannelMarker>,
+        prompt: &str,
+        owner: Id<UserMarker>,
+        timeout: Duration,
+    ) -> AnyResult<bool> {
+        confirm::confirm_buttons(self, channel_id, prompt, owner, timeout).await
+    }
+
+    /// Calculate guild level permissions of a member with `roles`.
+    pub async fn member_permissions(
+        &self,
+        guild_id: Id<GuildMarker>,
+        user_id: Id<UserMarker>,
+        roles: &[Id<RoleMarker>],
+    ) -> AnyResult<Permissions> {
+        // `@everyone` role id is the same as the guild's id.
+        let everyone = self
+            .roles_from(guild_id, &[guild_id.cast()])
+            .await?
+            .pop()
+            .context("'@everyone' role not found")?
+            .permissions;
+
+        let roles: Vec<_> = self
+            .roles_from(guild_id, roles)
+            .await?
+            .into_iter()
+            .map(|r| (r.id, r.permissions))
+            .collect();
+
+        let owner_id = self.guild_owner(guild_id).await?;
+
+        Ok(
+            PermissionCalculator::new(guild_id, user_id, everyone, &roles)
+                .owner_id(owner_id)
+                .root(),
+        )
+    }
+
+    /// Get the owner of the guild from cache or fetch from client.
+    pub async fn guild_owner(&self, guild_id: Id<GuildMarker>) -> AnyResult<Id<UserMarker>> {
+        match self.cache.guild(guild_id) {
+            Some(g) => Ok(g.owner_id()),
+            None => Ok(self.http.guild(guild_id).send().await?.owner_id),
+        }
+    }
+
+    /// Get the highest role of a guild member, `None` if the member has no roles.
+    pub async fn member_top_role(
+        &self,
+        guild_id: Id<GuildMarker>,
+        user_id: Id<UserMarker>,
+    ) -> AnyResult<Option<Role>> {
+        let role_ids = match self.cache.member(guild_id, user_id) {
+            Some(m) => m.roles().to_vec(),
+            None => {
+                self.http
+                    .guild_member(guild_id, user_id)
+                    .send()
+                    .await?
+                    .roles
+            },
+        };
+
+        Ok(self
+            .roles_from(guild_id, &role_ids)
+            .await?
+            .into_iter()
+            .max())
+    }
+
+    /// Check if `actor` is high enough in the role hierarchy to manage the role.
+    /// Only the hierarchy is checked, not whether the actor has permissions to manage roles.
+    pub async fn manageable_role(
+        &self,
+        guild_id: Id<GuildMarker>,
+        actor: Id<UserMarker>,
+        role_id: Id<RoleMarker>,
+    ) -> AnyResult<bool> {
+        let role = self
+            .roles_from(guild_id, &[role_id])
+            .await?
+            .pop()
+            .with_context(|| format!("Role '{role_id}' not found in guild '{guild_id}'"))?;
+
+        let is_owner = self.guild_owner(guild_id).await? == actor;
+        let top_role = self.member_top_role(guild_id, actor).await?;
+
+        Ok(can_manage_role(
+            guild_id,
+            is_owner,
+            top_role.as_ref(),
+            &role,
+        ))
+    }
+
+    /// Get the user object from cache or fetch from client.
+    pub async fn user_from(&self, user_id: Id<UserMarker>) -> AnyResult<User> {
+        match self.cached_user(user_id) {
+            Some(user) => Ok(user),
+            None => self.fetch_user(user_id).await,
+        }
+    }
+
+    /// Get user objects with `ids` from cache or fetch from client, a few at a time.
+    /// Users are in the order of `ids`, `None` for users that could not be fetched.
+    pub async fn users_from(&self, ids: &[Id<UserMarker>]) -> Vec<Option<User>> {
+        users::lookup_or_fetch(ids, |id| self.cached_user(id), |id| self.fetch_user(id)).await
+    }
+
+    /// Get a user from the gateway cache or from the recently fetched users.
+    fn cached_user(&self, user_id: Id<UserMarker>) -> Option<User> {
+        self.cache
+            .user(user_id)
+            .map(|u| u.to_owned())
+            .or_else(|| self.fetched_users.get(user_id))
+    }
+
+    /// Fetch a user from client and keep it for a while.
+    async fn fetch_user(&self, user_id: Id<UserMarker>) -> AnyResult<User> {
+        let user = self.http.user(user_id).send().await?;
+        self.fetched_users.insert(user.clone());
+        Ok(user)
+    }
+
     /// Search for a voice channel that a user is connected to in a guild.
     pub async fn user_voice_channel(
         &self,
@@ -229,6 +473,89 @@ impl Context {
         })
     }
 
+    /// Get a localized response by `key`, in the locale of the guild, if any.
+    /// See [`Locales::text`](Locales::text).
+    pub fn text(
+        &self,
+        guild_id: Option<Id<GuildMarker>>,
+        key: &str,
+        args: &[(&str, &str)],
+    ) -> String {
+        let locale = guild_id.and_then(|id| {
+            self.config
+                .guild(id)
+                .locale()
+                .map_err(|e| debug!("{e}"))
+                .ok()
+                .flatten()
+        });
+
+        self.locales.text(locale.as_deref(), key, args)
+    }
+
+    /// Wait for `fut`, unless the bot starts shutting down first, which gives `None`.
+    /// Waits for user input should use this, so that they do not hold up the shutdown.
+    pub async fn until_shutdown<F: Future>(&self, fut: F) -> Option<F::Output> {
+        tokio::select! {
+            // Delayed tasks are cancelled when the shutdown starts.
+            () = self.delayed.cancelled() => None,
+            output = fut => Some(output),
+        }
+    }
+
+    /// Post a message to the guild moderation log channel, if one is set.
+    /// Nobody mentioned in the message is pinged.
+    pub async fn mod_log(&self, guild_id: Id<GuildMarker>, content: &str) -> AnyResult<()> {
+        let Some(channel_id) = self.config.guild(guild_id).mod_log()? else {
+            return Ok(());
+        };
+
+        self.http
+            .create_message(channel_id)
+            .allowed_mentions(Some(&AllowedMentions::default()))
+            .content(content)?
+            .await
+            .with_context(|| format!("Failed to post to mod log in guild '{guild_id}'"))?;
+
+        Ok(())
+    }
+
+    /// Send a direct message to a user, opening the private channel if needed.
+    /// Returns `None` if the user does not accept direct messages from the bot.
+    pub async fn dm_user(
+        &self,
+        user_id: Id<UserMarker>,
+        content: &str,
+    ) -> AnyResult<Option<Message>> {
+        let dm = async {
+            let channel = self.http.create_private_channel(user_id).send().await?;
+            self.http
+                .create_message(channel.id)
+                .content(content)?
+                .send()
+                .await
+        };
+
+        match dm.await {
+            Ok(msg) => Ok(Some(msg)),
+            Err(e) if utils::api_error_code(&e) == Some(CANNOT_DM) => {
+                debug!("User '{user_id}' does not accept direct messages");
+                Ok(None)
+            },
+            Err(e) => Err(e).with_context(|| format!("Failed to send DM to user '{user_id}'")),
+        }
+    }
+
+    /// Returns `true` if the bot is in maintenance mode.
+    pub fn in_maintenance(&self) -> bool {
+        self.maintenance.load(Ordering::Relaxed)
+    }
+
+    /// Set maintenance mode on or off.
+    pub fn set_maintenance(&self, enabled: bool) {
+        self.maintenance.store(enabled, Ordering::Relaxed);
+    }
+
     /// This context with the provided shard id.
     pub fn with_shard(mut self, id: ShardId, sender: MessageSender) -> Self {
         self.shard = Some(PartialShard { id, sender });
@@ -236,14 +563,84 @@ impl Context {
     }
 
     /// Shortcut for `self.http.interaction(self.application.id)`.
-    pub fn interaction(&self) -> InteractionClient {
+    pub fn interaction(&self) -> InteractionClient<'_> {
         self.http.interaction(self.application.id)
     }
+
+    /// Replace the content of the original interaction response, with the default allowed mentions.
+    pub async fn update_response(&self, token: &str, content: &str) -> AnyResult<Message> {
+        self.interaction()
+            .update_response(token)
+            .allowed_mentions(Some(&utils::default_allowed_mentions()))
+            .content(Some(content))?
+            .send()
+            .await
+            .context("Failed to update interaction response")
+    }
+
+    /// Send a followup message to an interaction, with the default allowed mentions.
+    pub async fn followup(&self, token: &str, content: &str) -> AnyResult<Message> {
+        self.interaction()
+            .create_followup(token)
+            .allowed_mentions(Some(&utils::default_allowed_mentions()))
+            .content(content)?
+            .send()
+            .await
+            .context("Failed to send interaction followup")
+    }
 }
 
 #[derive(Debug)]
 pub enum BotEvent {
-    Shutdown,
+    Shutdown {
+        reason: ShutdownReason,
+    },
+    /// Close the connections and setup the bot again, without exiting the process.
+    Restart,
+}
+
+/// Why the bot is shutting down.
+#[derive(Debug, Clone, Display)]
+pub enum ShutdownReason {
+    /// Interrupted from the terminal.
+    #[display("ctrl-c")]
+    CtrlC,
+    /// Bot owner used the shutdown command.
+    #[display("owner command by user '{_0}'")]
+    Owner(Id<UserMarker>),
+    /// Unrecoverable error.
+    #[display("fatal error: {_0}")]
+    Fatal(String),
+}
+
+impl ShutdownReason {
+    /// Returns `true` if the gateway sessions are worth storing for resuming.
+    pub const fn can_resume(&self) -> bool {
+        !matches!(self, Self::Fatal(_))
+    }
+}
+
+/// Check that the channel belongs to the guild.
+fn ensure_guild_channel(chan: &Channel, guild_id: Id<GuildMarker>) -> AnyResult<()> {
+    if chan.guild_id != Some(guild_id) {
+        anyhow::bail!("Channel '{}' is not in guild '{guild_id}'", chan.id);
+    }
+    Ok(())
+}
+
+/// Check if a role is below `top_role` in the hierarchy, or the actor owns the guild.
+/// The `@everyone` role can never be managed.
+pub fn can_manage_role(
+    guild_id: Id<GuildMarker>,
+    is_owner: bool,
+    top_role: Option<&Role>,
+    role: &Role,
+) -> bool {
+    // `@everyone` role id is the same as the guild's id.
+    if role.id == guild_id.cast() {
+        return false;
+    }
+    is_owner || top_role.is_some_and(|top| top > role)
 }
 
 fn log_processed(p: twilight_standby::ProcessResults) {
@@ -259,7 +656,9 @@ fn log_processed(p: twilight_standby::ProcessResults) {
 }
 
 /// Discord permission intents.
-fn intents() -> Intents {
+///
+/// Only the intents of enabled features are requested, commands that need more fail validation.
+pub fn intents() -> Intents {
     #[cfg(feature = "all-intents")]
     {
         Intents::all()
@@ -267,15 +666,19 @@ fn intents() -> Intents {
 
     #[cfg(not(feature = "all-intents"))]
     {
-        Intents::MESSAGE_CONTENT
+        let intents = Intents::MESSAGE_CONTENT
             | Intents::GUILDS
             | Intents::GUILD_MESSAGES
             | Intents::GUILD_MESSAGE_REACTIONS
             | Intents::GUILD_MEMBERS
-            | Intents::GUILD_PRESENCES
-            | Intents::GUILD_VOICE_STATES
             | Intents::DIRECT_MESSAGES
-            | Intents::DIRECT_MESSAGE_REACTIONS
+            | Intents::DIRECT_MESSAGE_REACTIONS;
+
+        // Voice connections and the voice moderation commands.
+        #[cfg(any(feature = "voice", feature = "admin"))]
+        let intents = intents | Intents::GUILD_VOICE_STATES;
+
+        intents
     }
 }
 
@@ -286,3 +689,57 @@ fn event_type_flags() -> EventTypeFlags {
         - EventTypeFlags::DIRECT_MESSAGE_TYPING
         - EventTypeFlags::GUILD_MESSAGE_TYPING
 }
+
+/// Discord error code for a user that does not accept direct messages.
+const CANNOT_DM: u64 = 50007;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn foreign_channel() {
+        let chan: Channel = serde_json::from_value(serde_json::json!({
+            "id": "100",
+            "type": 0,
+            "guild_id": "1",
+        }))
+        .unwrap();
+
+        assert!(ensure_guild_channel(&chan, Id::new(1)).is_ok());
+        assert!(ensure_guild_channel(&chan, Id::new(2)).is_err());
+    }
+
+    #[test]
+    fn role_hierarchy() {
+        let role = |id: u64, position: i64| -> Role {
+            serde_json::from_value(serde_json::json!({
+                "id": id.to_string(),
+                "name": format!("role {id}"),
+                "color": 0,
+                "hoist": false,
+                "managed": false,
+                "mentionable": false,
+                "permissions": "0",
+                "position": position,
+                "flags": 0,
+            }))
+            .unwrap()
+        };
+        let guild_id = Id::new(1);
+        let everyone = role(1, 0);
+        let below = role(10, 1);
+        let actor = role(20, 2);
+        let above = role(30, 3);
+
+        assert!(can_manage_role(guild_id, false, Some(&actor), &below));
+        assert!(!can_manage_role(guild_id, false, Some(&actor), &actor));
+        assert!(!can_manage_role(guild_id, false, Some(&actor), &above));
+        assert!(!can_manage_role(guild_id, false, None, &below));
+        assert!(!can_manage_role(guild_id, false, Some(&actor), &everyone));
+
+        // Owner is above every role, except `@everyone`.
+        assert!(can_manage_role(guild_id, true, None, &above));
+        assert!(!can_manage_role(guild_id, true, None, &everyone));
+    }
+}
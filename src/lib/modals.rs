@@ -0,0 +1,298 @@
+//! Modal forms that continue a command with more input.
+//!
+//! A command shows a modal with [`Response::modal`] and gives a continuation for it,
+//! which is kept in memory, keyed by the custom id of the modal. Submissions are routed here
+//! by the interaction handler, which calls the continuation with the submitted values.
+//! A closed modal is never submitted, so continuations expire after a timeout,
+//! and the expired ones are dropped whenever a new modal is shown.
+//!
+//! [`Response::modal`]: crate::commands::Response::modal
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use twilight_model::application::interaction::modal::ModalInteractionData;
+use twilight_model::application::interaction::Interaction;
+use twilight_model::channel::message::component::TextInput;
+use twilight_model::channel::message::MessageFlags;
+use twilight_model::http::interaction::{
+    InteractionResponse, InteractionResponseData, InteractionResponseType,
+};
+use twilight_model::id::marker::UserMarker;
+use twilight_model::id::Id;
+
+use crate::commands::{handle, AsyncResponse, ResponseFuture};
+use crate::components::{ComponentError, ComponentsBuilder};
+use crate::utils::prelude::*;
+use crate::Context;
+
+/// Custom id prefix of the modals.
+pub const PREFIX: &str = "modal";
+
+/// Default time to wait for a modal to be submitted.
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(15 * 60);
+
+type Continuation = Box<dyn FnOnce(Context, ModalRequest) -> AsyncResponse + Send>;
+
+/// A modal form with text inputs.
+#[derive(Debug, Clone)]
+pub struct Modal {
+    title: String,
+    inputs: ComponentsBuilder,
+    timeout: Duration,
+}
+
+impl Modal {
+    pub fn new(title: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            inputs: ComponentsBuilder::new(),
+            timeout: DEFAULT_TIMEOUT,
+        }
+    }
+
+    /// Add a text input, see [`components::text_input`](crate::components::text_input).
+    /// A modal has room for up to 5 inputs.
+    pub fn input(mut self, input: TextInput) -> Result<Self, ComponentError> {
+        self.inputs = self.inputs.text_input(input)?;
+        Ok(self)
+    }
+
+    /// Set how long to wait for a submission, default is [`DEFAULT_TIMEOUT`].
+    pub const fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+}
+
+/// Submission of a modal, given to its continuation.
+#[derive(Debug, Clone)]
+pub struct ModalRequest {
+    pub interaction: Arc<Interaction>,
+    pub data: Arc<ModalInteractionData>,
+}
+
+impl ModalRequest {
+    /// Submitted value of a text input, `None` if it was left empty.
+    pub fn value(&self, custom_id: &str) -> Option<&str> {
+        self.data
+            .components
+            .iter()
+            .flat_map(|row| &row.components)
+            .find(|c| c.custom_id == custom_id)
+            .and_then(|c| c.value.as_deref())
+            .filter(|v| !v.is_empty())
+    }
+}
+
+/// State of a modal waiting for a submission.
+struct Pending {
+    owner: Id<UserMarker>,
+    ephemeral: bool,
+    expires: Instant,
+    continuation: Continuation,
+}
+
+/// Modals waiting for a submission.
+#[derive(Default)]
+pub struct Modals {
+    pending: Mutex<HashMap<String, Pending>>,
+}
+
+impl Modals {
+    /// Keep a continuation until its modal is submitted, dropping the expired ones.
+    fn insert(&self, custom_id: String, pending: Pending) {
+        let now = Instant::now();
+        let mut map = self.pending.lock().unwrap();
+        map.retain(|_, p| p.expires > now);
+        map.insert(custom_id, pending);
+    }
+
+    /// Take the continuation of a modal, if it has not expired.
+    /// Returns `Some(Err(()))` if the modal was shown to another user, which leaves it in place.
+    fn take(
+        &self,
+        custom_id: &str,
+        user_id: Option<Id<UserMarker>>,
+    ) -> Option<Result<Pending, ()>> {
+        let mut map = self.pending.lock().unwrap();
+        match map.get(custom_id) {
+            None => None,
+            Some(p) if p.expires <= Instant::now() => {
+                map.remove(custom_id);
+                None
+            },
+            Some(p) if Some(p.owner) != user_id => Some(Err(())),
+            Some(_) => map.remove(custom_id).map(Ok),
+        }
+    }
+
+    fn remove(&self, custom_id: &str) {
+        self.pending.lock().unwrap().remove(custom_id);
+    }
+}
+
+/// Show a modal as the response to an interaction, and keep the continuation
+/// until the modal is submitted or times out.
+/// The interaction must not be acknowledged before this.
+pub async fn show<F, Fut>(
+    ctx: &Context,
+    inter: &Interaction,
+    ephemeral: bool,
+    modal: Modal,
+    continuation: F,
+) -> AnyResult<()>
+where
+    F: FnOnce(Context, ModalRequest) -> Fut + Send + 'static,
+    Fut: ResponseFuture + 'static,
+{
+    let owner = inter.author_id().context("Interaction without an author")?;
+    let components = modal.inputs.build();
+    if components.is_empty() {
+        return Err(ComponentError::Empty).context("Modal without inputs");
+    }
+
+    // Kept before responding, so that a quick submission finds it.
+    let custom_id = format!("{PREFIX}:{}", inter.id);
+    ctx.modals.insert(custom_id.to_owned(), Pending {
+        owner,
+        ephemeral,
+        expires: Instant::now() + modal.timeout,
+        continuation: Box::new(move |ctx, req| Box::pin(continuation(ctx, req))),
+    });
+
+    let resp = InteractionResponse {
+        kind: InteractionResponseType::Modal,
+        data: Some(InteractionResponseData {
+            custom_id: Some(custom_id.to_owned()),
+            title: Some(modal.title),
+            components: Some(components),
+            ..Default::default()
+        }),
+    };
+
+    if let Err(e) = ctx
+        .interaction()
+        .create_response(inter.id, &inter.token, &resp)
+        .await
+    {
+        ctx.modals.remove(&custom_id);
+        return Err(e).context("Failed to show modal");
+    }
+
+    Ok(())
+}
+
+/// Handle a modal submission by calling its continuation.
+pub async fn handle_submit(
+    ctx: &Context,
+    inter: Interaction,
+    data: ModalInteractionData,
+) -> AnyResult<()> {
+    let pending = match ctx.modals.take(&data.custom_id, inter.author_id()) {
+        Some(Ok(pending)) => pending,
+        other => {
+            let content = match other {
+                Some(_) => "Only the command user can submit this.",
+                None => "This form has expired, use the command again.",
+            };
+            let resp = InteractionResponse {
+                kind: InteractionResponseType::ChannelMessageWithSource,
+                data: Some(InteractionResponseData {
+                    content: Some(content.to_string()),
+                    flags: Some(MessageFlags::EPHEMERAL),
+                    ..Default::default()
+                }),
+            };
+            ctx.interaction()
+                .create_response(inter.id, &inter.token, &resp)
+                .await
+                .context("Failed to respond to modal submit")?;
+
+            return Ok(());
+        },
+    };
+
+    if pending.ephemeral {
+        handle::ephemeral_acknowledge(ctx, inter.id, &inter.token).await?;
+    } else {
+        handle::public_acknowledge(ctx, inter.id, &inter.token).await?;
+    }
+
+    let inter = Arc::new(inter);
+    let req = ModalRequest {
+        interaction: Arc::clone(&inter),
+        data: Arc::new(data),
+    };
+
+    let result = match (pending.continuation)(ctx.to_owned(), req).await {
+        Ok(response) => response.await,
+        Err(e) => Err(e),
+    };
+
+    // Clear the loading state and tell what went wrong, like on a failed command.
+    if let Err(e) = result {
+        let denial = handle::denial_text(ctx, inter.guild_id, &e);
+        let content = denial
+            .clone()
+            .unwrap_or_else(|| ctx.text(inter.guild_id, "error", &[]));
+
+        if let Err(e) = ctx.interaction().delete_response(&inter.token).await {
+            debug!("Could not clear deferred response: {e}");
+        }
+
+        let flags = if pending.ephemeral {
+            MessageFlags::EPHEMERAL
+        } else {
+            MessageFlags::empty()
+        };
+        ctx.interaction()
+            .create_followup(&inter.token)
+            .flags(flags)
+            .content(&content)?
+            .await
+            .context("Failed to send error message")?;
+
+        // A denial is not an error of the continuation.
+        if denial.is_some() {
+            return Ok(());
+        }
+
+        return Err(anyhow::Error::from(e).context("Modal continuation failed"));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::Response;
+
+    fn pending(owner: u64, expires: Instant) -> Pending {
+        Pending {
+            owner: Id::new(owner),
+            ephemeral: false,
+            expires,
+            continuation: Box::new(|_, _| Box::pin(async { Ok(Response::none()) })),
+        }
+    }
+
+    #[test]
+    fn expire_pending_modals() {
+        let modals = Modals::default();
+        let now = Instant::now();
+        let later = now + Duration::from_secs(60);
+
+        modals.insert("modal:1".to_string(), pending(1, now));
+        modals.insert("modal:2".to_string(), pending(1, later));
+        // The first one had expired when the second one was added.
+        assert_eq!(modals.pending.lock().unwrap().len(), 1);
+
+        assert!(modals.take("modal:1", Some(Id::new(1))).is_none());
+        assert!(modals.take("modal:2", Some(Id::new(2))).unwrap().is_err());
+        assert!(modals.take("modal:2", Some(Id::new(1))).unwrap().is_ok());
+        assert!(modals.take("modal:2", Some(Id::new(1))).is_none());
+    }
+}
@@ -0,0 +1,255 @@
+//! Paginated embed messages, navigated with buttons.
+//!
+//! A paginated message keeps its state in memory, keyed by the message id.
+//! Button presses are routed here by the component handler, which then edits the message
+//! to show the requested page. After some inactivity the state is dropped and the buttons disabled.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use tokio::time::Instant;
+use twilight_model::application::interaction::message_component::MessageComponentInteractionData;
+use twilight_model::application::interaction::Interaction;
+use twilight_model::channel::message::component::{ActionRow, Button, ButtonStyle};
+use twilight_model::channel::message::{Component, Embed, MessageFlags};
+use twilight_model::http::interaction::{
+    InteractionResponse, InteractionResponseData, InteractionResponseType,
+};
+use twilight_model::id::marker::{ChannelMarker, MessageMarker, UserMarker};
+use twilight_model::id::Id;
+
+use crate::utils::prelude::*;
+use crate::{components, Context};
+
+/// Custom id prefix of the pagination buttons.
+pub const PREFIX: &str = "page";
+
+/// Time after which an unused paginated message is no longer navigable.
+const INACTIVITY_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+
+/// State of a paginated message.
+#[derive(Debug, Clone)]
+struct PageState {
+    channel_id: Id<ChannelMarker>,
+    pages: Vec<Embed>,
+    current: usize,
+    /// Only this user can navigate, anyone can if `None`.
+    owner: Option<Id<UserMarker>>,
+    last_used: Instant,
+}
+
+/// Store of paginated messages.
+#[derive(Debug, Default)]
+pub struct Paginator {
+    states: Mutex<HashMap<Id<MessageMarker>, PageState>>,
+}
+
+impl Paginator {
+    /// Create navigation buttons for the pages.
+    pub fn buttons(current: usize, total: usize, disabled: bool) -> Vec<Component> {
+        let button = |id: &str, label: String, disabled: bool| Button {
+            disabled,
+            ..components::button(format!("{PREFIX}:{id}"), label, ButtonStyle::Secondary)
+        };
+
+        vec![Component::ActionRow(ActionRow {
+            components: vec![
+                Component::Button(button("prev", "◀".to_string(), disabled || current == 0)),
+                Component::Button(button("count", format!("{}/{total}", current + 1), true)),
+                Component::Button(button(
+                    "next",
+                    "▶".to_string(),
+                    disabled || current + 1 >= total,
+                )),
+            ],
+        })]
+    }
+}
+
+/// Post pages as a new message in a channel.
+/// Navigation is restricted to `owner`, or allowed for anyone if `None`.
+pub async fn create_message(
+    ctx: &Context,
+    channel_id: Id<ChannelMarker>,
+    pages: Vec<Embed>,
+    owner: Option<Id<UserMarker>>,
+) -> AnyResult<()> {
+    anyhow::ensure!(!pages.is_empty(), "No pages to display");
+
+    let components = buttons_for(&pages);
+    let msg = ctx
+        .http
+        .create_message(channel_id)
+        .embeds(&pages[..1])?
+        .components(&components)?
+        .send()
+        .await?;
+
+    register(ctx, msg.id, channel_id, pages, owner);
+    Ok(())
+}
+
+/// Edit a deferred interaction response to show the pages.
+/// Navigation is restricted to `owner`, or allowed for anyone if `None`.
+pub async fn update_response(
+    ctx: &Context,
+    token: &str,
+    pages: Vec<Embed>,
+    owner: Option<Id<UserMarker>>,
+) -> AnyResult<()> {
+    anyhow::ensure!(!pages.is_empty(), "No pages to display");
+
+    let components = buttons_for(&pages);
+    let msg = ctx
+        .interaction()
+        .update_response(token)
+        .embeds(Some(&pages[..1]))?
+        .components(Some(&components))?
+        .send()
+        .await?;
+
+    register(ctx, msg.id, msg.channel_id, pages, owner);
+    Ok(())
+}
+
+/// Handle a pagination button press.
+pub async fn handle_component(
+    ctx: &Context,
+    inter: &Interaction,
+    data: &MessageComponentInteractionData,
+) -> AnyResult<()> {
+    let message_id = inter
+        .message
+        .as_ref()
+        .map(|m| m.id)
+        .context("Component interaction without a message")?;
+    let user_id = inter.author_id();
+
+    let page = {
+        let mut states = ctx.pages.states.lock().unwrap();
+        match states.get_mut(&message_id) {
+            None => None,
+            Some(state) if state.owner.is_some_and(|o| Some(o) != user_id) => Some(Err(())),
+            Some(state) => {
+                match data.custom_id.split_once(':').map(|(_, a)| a) {
+                    Some("prev") => state.current = state.current.saturating_sub(1),
+                    Some("next") => state.current = (state.current + 1).min(state.pages.len() - 1),
+                    other => debug!("Unknown pagination action: {other:?}"),
+                }
+                state.last_used = Instant::now();
+                Some(Ok((
+                    state.pages[state.current].to_owned(),
+                    Paginator::buttons(state.current, state.pages.len(), false),
+                )))
+            },
+        }
+    };
+
+    let data = match page {
+        Some(Ok((embed, components))) => InteractionResponseData {
+            embeds: Some(vec![embed]),
+            components: Some(components),
+            ..Default::default()
+        },
+        Some(Err(())) => InteractionResponseData {
+            content: Some("Only the command user can change pages.".to_string()),
+            flags: Some(MessageFlags::EPHEMERAL),
+            ..Default::default()
+        },
+        None => InteractionResponseData {
+            content: Some("These pages have expired.".to_string()),
+            flags: Some(MessageFlags::EPHEMERAL),
+            ..Default::default()
+        },
+    };
+
+    let kind = if data.flags.is_some() {
+        InteractionResponseType::ChannelMessageWithSource
+    } else {
+        InteractionResponseType::UpdateMessage
+    };
+
+    ctx.interaction()
+        .create_response(inter.id, &inter.token, &InteractionResponse {
+            kind,
+            data: Some(data),
+        })
+        .await
+        .context("Failed to respond to page change")?;
+
+    Ok(())
+}
+
+/// Navigation buttons for a new paginated message, none for a single page.
+fn buttons_for(pages: &[Embed]) -> Vec<Component> {
+    if pages.len() > 1 {
+        Paginator::buttons(0, pages.len(), false)
+    } else {
+        Vec::new()
+    }
+}
+
+/// Store the state of a paginated message and expire it after inactivity.
+fn register(
+    ctx: &Context,
+    message_id: Id<MessageMarker>,
+    channel_id: Id<ChannelMarker>,
+    pages: Vec<Embed>,
+    owner: Option<Id<UserMarker>>,
+) {
+    if pages.len() < 2 {
+        return; // Nothing to navigate.
+    }
+
+    ctx.pages
+        .states
+        .lock()
+        .unwrap()
+        .insert(message_id, PageState {
+            channel_id,
+            pages,
+            current: 0,
+            owner,
+            last_used: Instant::now(),
+        });
+
+    let ctx = ctx.to_owned();
+    tokio::spawn(async move {
+        loop {
+            let deadline = match ctx.pages.states.lock().unwrap().get(&message_id) {
+                Some(state) => state.last_used + INACTIVITY_TIMEOUT,
+                None => return,
+            };
+
+            if Instant::now() < deadline {
+                tokio::time::sleep_until(deadline).await;
+                continue;
+            }
+
+            let Some(state) = ctx.pages.states.lock().unwrap().remove(&message_id) else {
+                return;
+            };
+
+            if let Err(e) = disable_buttons(&ctx, message_id, &state).await {
+                debug!("Could not disable expired page buttons: {e}");
+            }
+
+            return;
+        }
+    });
+}
+
+/// Disable the navigation buttons of an expired paginated message.
+async fn disable_buttons(
+    ctx: &Context,
+    message_id: Id<MessageMarker>,
+    state: &PageState,
+) -> AnyResult<()> {
+    let components = Paginator::buttons(state.current, state.pages.len(), true);
+    ctx.http
+        .update_message(state.channel_id, message_id)
+        .components(Some(&components))?
+        .await?;
+    Ok(())
+}
@@ -54,10 +54,21 @@ pub fn split_once_whitespace(text: &str) -> (&str, Option<&str>) {
 
 /// Try to parse string-slice into arg parts.
 /// For more details about individual argument parsing, see [`maybe_quoted_arg`](maybe_quoted_arg)
-pub fn parse_args(mut input: &str) -> Result<Vec<&str>, ParseError> {
+pub fn parse_args(input: &str) -> Result<Vec<&str>, ParseError> {
+    parse_args_with_limit(input, consts::MAX_ARGS)
+}
+
+/// Same as [`parse_args`](parse_args), but fails if there are more than `max` args.
+pub fn parse_args_with_limit(mut input: &str, max: usize) -> Result<Vec<&str>, ParseError> {
     let mut args = Vec::new();
 
     loop {
+        if args.len() >= max && !input.trim().is_empty() {
+            return Err(ParseError::UnexpectedArgs(format!(
+                "Too many arguments, up to {max} allowed"
+            )));
+        }
+
         match maybe_quoted_arg(input) {
             Ok((arg, Some(rest))) => {
                 input = rest;
@@ -83,6 +94,7 @@ pub fn parse_args(mut input: &str) -> Result<Vec<&str>, ParseError> {
 ///
 /// # Notes
 /// - Escape characters are **not** handled.
+/// - A quoted argument ends at the first closing delimiter of its pair, quotes are not nested.
 /// - If a non-quoted argument contains any delimiters before any whitespace,
 ///   those characters (and everything upto a whitespace or the end) will be in the `arg`.
 /// - If a quoted argument is followed by any character (whitespace or not),
@@ -91,34 +103,43 @@ pub fn maybe_quoted_arg(input: &str) -> Result<(&str, Option<&str>), ParseError>
     // First trim off any leading whitespace.
     let input = input.trim_start();
 
-    // Indexing a string is in bytes, so enumerate the bytes.
-    let mut bytes = input.bytes().enumerate();
+    // Indexing a string is in bytes, so enumerate the chars with their byte positions.
+    let mut chars = input.char_indices();
 
-    // Get the first byte or return an error for a missing argument.
-    let (_, initial) = bytes.next().ok_or(ParseError::MissingArgs)?;
+    // Get the first char or return an error for a missing argument.
+    let (_, initial) = chars.next().ok_or(ParseError::MissingArgs)?;
 
-    // Check if the first byte is a delimiter character (assuming all delimiter characters are one byte wide utf-8).
-    if consts::DELIMITERS.contains(&(initial as char)) {
+    // Check if the first char is a delimiter character.
+    if let Some(closing) = closing_delimiter(initial) {
         // Find the matching pair.
-        let idx = bytes
-            .filter(|(i, _)| input.is_char_boundary(*i))
-            .find_map(|(i, b)| (b == initial).then_some(i))
+        let idx = chars
+            .find_map(|(i, c)| (c == closing).then_some(i))
             .ok_or_else(|| {
                 let input = utils::escape_discord_chars(input);
                 ParseError::Other(anyhow::anyhow!(
-                    "Missing matching delimiter: '{input}', expected one of: {}.",
-                    utils::nice_list(consts::DELIMITERS)
+                    "Missing matching delimiter: '{input}', expected '{closing}'."
                 ))
             })?;
 
         // Return everything between the two and then everything after, if any.
-        Ok((&input[1..idx], input.get(idx + 1..)))
+        Ok((
+            &input[initial.len_utf8()..idx],
+            input.get(idx + closing.len_utf8()..),
+        ))
     } else {
         // Did not start with a delimiter, try to split by whitespace instead.
         Ok(split_once_whitespace(input))
     }
 }
 
+/// Returns the closing delimiter for an opening delimiter,
+/// or `None` if `open` is not a delimiter.
+pub fn closing_delimiter(open: char) -> Option<char> {
+    consts::DELIMITER_PAIRS
+        .iter()
+        .find_map(|&(o, c)| (o == open).then_some(c))
+}
+
 /// Returns a string-slice without delimiters, or returns ´input´ if no delimiters are found or can be stripped.
 pub fn strip_delimits<P>(input: &str, delimits: P) -> &str
 where
@@ -191,6 +212,54 @@ mod tests {
         assert_eq!(Ok(vec![]), parse_args(s));
     }
 
+    #[test]
+    fn too_many_arguments() {
+        let s = "a ".repeat(consts::MAX_ARGS);
+        assert_eq!(consts::MAX_ARGS, parse_args(&s).unwrap().len());
+
+        let s = "a ".repeat(consts::MAX_ARGS + 1);
+        assert_eq!(
+            Err(ParseError::UnexpectedArgs(String::new())),
+            parse_args(&s)
+        );
+
+        let s = r#""a" "#.repeat(10_000);
+        assert_eq!(
+            Err(ParseError::UnexpectedArgs(String::new())),
+            parse_args_with_limit(&s, 3)
+        );
+    }
+
+    #[test]
+    fn smart_quoted_arguments() {
+        let s = "“foo bar” baz “ä ö”";
+        assert_eq!(Ok(vec!["foo bar", "baz", "ä ö"]), parse_args(s));
+
+        let s = "“foo”bar";
+        assert_eq!(Ok(("foo", Some("bar"))), maybe_quoted_arg(s));
+
+        let s = "“foo bar“";
+        assert!(maybe_quoted_arg(s).is_err());
+    }
+
+    #[test]
+    fn paired_delimiters() {
+        // Closing char of another pair does not close.
+        let s = "“foo’ bar";
+        assert!(maybe_quoted_arg(s).is_err());
+
+        let s = "‘foo” bar’ baz";
+        assert_eq!(Ok(vec!["foo” bar", "baz"]), parse_args(s));
+
+        // Other quotes inside are kept as is, the first matching close ends the arg.
+        let s = "“say ‘hi’ now” ‘a “b’ c”";
+        assert_eq!(Ok(vec!["say ‘hi’ now", "a “b", "c”"]), parse_args(s));
+
+        // Same char delimiters still work.
+        let s = r#""“a”" `b`"#;
+        assert_eq!(Ok(vec!["“a”", "b"]), parse_args(s));
+    }
+
     #[test]
     fn parse_one_arg() {
         let s = r#"    foo    bar"#;
@@ -0,0 +1,75 @@
+//! Scheduling of per-guild startup work.
+//!
+//! After `Ready`, every guild arrives in a burst of `GuildCreate` events.
+//! Work done for each of them (whitelist checks, nicknames, etc.) is queued here,
+//! so that only a few guilds are handled at once, with a small delay in between.
+
+use std::future::Future;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+use tokio::sync::Semaphore;
+use twilight_model::id::marker::GuildMarker;
+use twilight_model::id::Id;
+
+use crate::utils::prelude::*;
+
+/// Maximum number of guilds reconciled at the same time.
+pub const CONCURRENCY: usize = 2;
+
+/// Delay after each reconciled guild.
+pub const SPACING: Duration = Duration::from_millis(500);
+
+/// Startup reconciliation queue.
+#[derive(Debug)]
+pub struct Reconciler {
+    permits: Semaphore,
+    expected: AtomicUsize,
+    done: AtomicUsize,
+}
+
+impl Default for Reconciler {
+    fn default() -> Self {
+        Self {
+            permits: Semaphore::new(CONCURRENCY),
+            expected: AtomicUsize::new(0),
+            done: AtomicUsize::new(0),
+        }
+    }
+}
+
+impl Reconciler {
+    /// Expect `guilds` amount of more guilds to be reconciled, eg. from a shard's `Ready`.
+    pub fn start(&self, guilds: usize) {
+        self.expected.fetch_add(guilds, Ordering::Relaxed);
+        info!("Reconciling {guilds} guilds");
+    }
+
+    /// Run the work for a guild once there is room for it, failures are only logged.
+    pub async fn run<Fut>(&self, guild_id: Id<GuildMarker>, work: Fut) -> AnyResult<()>
+    where
+        Fut: Future<Output = AnyResult<()>>,
+    {
+        let _permit = self.permits.acquire().await?;
+
+        let result = work.await;
+
+        let done = self.done.fetch_add(1, Ordering::Relaxed) + 1;
+        let expected = self.expected.load(Ordering::Relaxed).max(done);
+        match &result {
+            Ok(()) => debug!("Reconciled guild '{guild_id}' ({done}/{expected})"),
+            Err(e) => warn!(
+                "Failed to reconcile guild '{guild_id}' ({done}/{expected}): {}",
+                e.oneliner()
+            ),
+        }
+        if done == expected {
+            info!("Reconciled all {done} expected guilds");
+        }
+
+        // Hold on to the permit for a while, to spread out the requests.
+        tokio::time::sleep(SPACING).await;
+
+        Ok(())
+    }
+}
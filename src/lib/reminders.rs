@@ -0,0 +1,90 @@
+//! Pending reminders of each user.
+//!
+//! Reminders are only kept in memory as sleeping tasks, so the number of them
+//! is limited per user, to keep anyone from filling the memory with them.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use twilight_model::id::marker::UserMarker;
+use twilight_model::id::Id;
+
+/// Most reminders a user can have pending at once.
+pub const MAX_PER_USER: usize = 10;
+
+/// Number of pending reminders by user.
+#[derive(Debug, Default)]
+pub struct Reminders {
+    pending: Mutex<HashMap<Id<UserMarker>, usize>>,
+}
+
+impl Reminders {
+    /// Mark a reminder of `user_id` as pending until the returned guard is dropped.
+    /// Returns `None` if the user already has [`MAX_PER_USER`] reminders.
+    pub fn start(self: &Arc<Self>, user_id: Id<UserMarker>) -> Option<ReminderGuard> {
+        let mut pending = self.pending.lock().unwrap();
+        let count = pending.entry(user_id).or_default();
+        if *count >= MAX_PER_USER {
+            return None;
+        }
+        *count += 1;
+
+        Some(ReminderGuard {
+            reminders: Arc::clone(self),
+            user_id,
+        })
+    }
+
+    /// Number of pending reminders of a user.
+    pub fn count(&self, user_id: Id<UserMarker>) -> usize {
+        self.pending
+            .lock()
+            .unwrap()
+            .get(&user_id)
+            .copied()
+            .unwrap_or_default()
+    }
+}
+
+/// Marks a reminder as pending while alive.
+#[derive(Debug)]
+pub struct ReminderGuard {
+    reminders: Arc<Reminders>,
+    user_id: Id<UserMarker>,
+}
+
+impl Drop for ReminderGuard {
+    fn drop(&mut self) {
+        let mut pending = self.reminders.pending.lock().unwrap();
+        if let Some(count) = pending.get_mut(&self.user_id) {
+            *count -= 1;
+            if *count == 0 {
+                pending.remove(&self.user_id);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn limit_pending_reminders() {
+        let reminders = Arc::new(Reminders::default());
+        let user = Id::new(1);
+
+        let mut guards: Vec<_> = (0..MAX_PER_USER)
+            .map(|_| reminders.start(user).unwrap())
+            .collect();
+        assert!(reminders.start(user).is_none());
+        assert!(reminders.start(Id::new(2)).is_some()); // Other users are separate.
+
+        guards.pop();
+        assert_eq!(reminders.count(user), MAX_PER_USER - 1);
+        assert!(reminders.start(user).is_some());
+
+        drop(guards);
+        assert_eq!(reminders.count(user), 0);
+    }
+}
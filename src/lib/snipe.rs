@@ -0,0 +1,201 @@
+//! Recently deleted messages, for sniping and message logs.
+//!
+//! The cache forgets or overwrites a message before the delete or update event reaches the handler,
+//! so a copy of it is held aside while the cache is updated. The handler then decides
+//! whether to keep it, only the last deleted message of each channel is kept for a few minutes.
+//!
+//! Guilds can also opt in to a message buffer, for when the cache does not have the message.
+//! It keeps the last [`BUFFER_SIZE`] messages of each channel in memory only, so it is lost
+//! on restart, and it can be cleared at any time. Messages are only buffered with content,
+//! which requires the `MESSAGE_CONTENT` intent.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use tokio::time::Instant;
+use twilight_cache_inmemory::InMemoryCache;
+use twilight_gateway::Event;
+use twilight_model::channel::Message;
+use twilight_model::id::marker::{ChannelMarker, GuildMarker, MessageMarker, UserMarker};
+use twilight_model::id::Id;
+
+/// Time after which a deleted message can no longer be sniped.
+pub const EXPIRY: Duration = Duration::from_secs(5 * 60);
+
+/// Number of recent messages buffered per channel.
+pub const BUFFER_SIZE: usize = 50;
+
+/// Copy of a message before it was deleted or edited.
+#[derive(Debug, Clone)]
+pub struct MessageCopy {
+    pub author_id: Id<UserMarker>,
+    pub content: String,
+    /// Unix timestamp in seconds of when the message was sent.
+    pub sent_at: i64,
+    held_at: Instant,
+}
+
+impl MessageCopy {
+    fn new(author_id: Id<UserMarker>, content: String, sent_at: i64) -> Self {
+        Self {
+            author_id,
+            content,
+            sent_at,
+            held_at: Instant::now(),
+        }
+    }
+
+    /// Check if the message is too old to be sniped.
+    pub fn is_expired(&self) -> bool {
+        self.held_at.elapsed() >= EXPIRY
+    }
+}
+
+/// Store of recently deleted and edited messages.
+#[derive(Debug, Default)]
+pub struct Snipes {
+    /// Deleted or edited messages waiting for the event handler.
+    held: Mutex<HashMap<Id<MessageMarker>, MessageCopy>>,
+    /// Last deleted message of each channel.
+    channels: Mutex<HashMap<Id<ChannelMarker>, MessageCopy>>,
+    /// Recent messages of each channel, in guilds that opted in.
+    buffer: Mutex<HashMap<Id<ChannelMarker>, Buffered>>,
+}
+
+/// Recent messages of a channel, oldest first.
+#[derive(Debug)]
+struct Buffered {
+    guild_id: Id<GuildMarker>,
+    messages: VecDeque<(Id<MessageMarker>, MessageCopy)>,
+}
+
+impl Snipes {
+    /// Hold copies of cached or buffered messages that the event is about to delete or edit.
+    /// Must be called before the cache is updated with the event.
+    pub fn hold(&self, cache: &InMemoryCache, event: &Event) {
+        let (channel_id, ids) = match event {
+            Event::MessageDelete(md) => (md.channel_id, std::slice::from_ref(&md.id)),
+            Event::MessageDeleteBulk(mdb) => (mdb.channel_id, mdb.ids.as_slice()),
+            Event::MessageUpdate(mu) if mu.content.is_some() => {
+                (mu.channel_id, std::slice::from_ref(&mu.id))
+            },
+            _ => return,
+        };
+
+        let mut held = self.held.lock().unwrap();
+        let mut buffer = self.buffer.lock().unwrap();
+        let buffered = buffer.get_mut(&channel_id);
+
+        for id in ids {
+            let copy = match cache.message(*id) {
+                Some(msg) => MessageCopy::new(
+                    msg.author(),
+                    msg.content().to_string(),
+                    msg.timestamp().as_secs(),
+                ),
+                None => match buffered
+                    .as_ref()
+                    .and_then(|b| b.messages.iter().find(|(m, _)| m == id))
+                {
+                    Some((_, msg)) => {
+                        MessageCopy::new(msg.author_id, msg.content.clone(), msg.sent_at)
+                    },
+                    None => continue,
+                },
+            };
+
+            // Nothing to show for messages without content.
+            if copy.content.is_empty() {
+                continue;
+            }
+
+            held.insert(*id, copy);
+        }
+
+        // Keep the buffer up to date with the event.
+        let Some(buffered) = buffered else {
+            return;
+        };
+
+        match event {
+            Event::MessageUpdate(mu) => {
+                if let Some((_, msg)) = buffered.messages.iter_mut().find(|(m, _)| *m == mu.id) {
+                    msg.content = mu.content.clone().unwrap_or_default();
+                }
+            },
+            _ => buffered.messages.retain(|(m, _)| !ids.contains(m)),
+        }
+    }
+
+    /// Add a new message to the buffer of its channel, dropping the oldest if full.
+    pub fn buffer(&self, guild_id: Id<GuildMarker>, msg: &Message) {
+        if msg.content.is_empty() {
+            return; // Missing the message content intent, or nothing to keep.
+        }
+
+        let mut buffer = self.buffer.lock().unwrap();
+        let buffered = buffer.entry(msg.channel_id).or_insert_with(|| Buffered {
+            guild_id,
+            messages: VecDeque::with_capacity(BUFFER_SIZE),
+        });
+
+        if buffered.messages.len() >= BUFFER_SIZE {
+            buffered.messages.pop_front();
+        }
+
+        buffered.messages.push_back((
+            msg.id,
+            MessageCopy::new(msg.author.id, msg.content.clone(), msg.timestamp.as_secs()),
+        ));
+    }
+
+    /// Forget the buffered and sniped messages of a guild.
+    /// Returns the number of buffered messages removed.
+    pub fn forget(&self, cache: &InMemoryCache, guild_id: Id<GuildMarker>) -> usize {
+        let mut removed = 0;
+        let mut cleared = Vec::new();
+        self.buffer.lock().unwrap().retain(|channel_id, b| {
+            if b.guild_id == guild_id {
+                removed += b.messages.len();
+                cleared.push(*channel_id);
+                return false;
+            }
+            true
+        });
+
+        // Sniped messages do not know their guild, so look up its channels as well.
+        if let Some(channels) = cache.guild_channels(guild_id) {
+            cleared.extend(channels.iter().copied());
+        }
+
+        self.channels
+            .lock()
+            .unwrap()
+            .retain(|c, _| !cleared.contains(c));
+
+        removed
+    }
+
+    /// Take a held message, if it was cached.
+    pub fn take_held(&self, message_id: Id<MessageMarker>) -> Option<MessageCopy> {
+        self.held.lock().unwrap().remove(&message_id)
+    }
+
+    /// Set the last deleted message of a channel.
+    pub fn insert(&self, channel_id: Id<ChannelMarker>, msg: MessageCopy) {
+        let mut channels = self.channels.lock().unwrap();
+        channels.retain(|_, m| !m.is_expired());
+        channels.insert(channel_id, msg);
+    }
+
+    /// Get the last deleted message of a channel, if not expired.
+    pub fn get(&self, channel_id: Id<ChannelMarker>) -> Option<MessageCopy> {
+        self.channels
+            .lock()
+            .unwrap()
+            .get(&channel_id)
+            .filter(|m| !m.is_expired())
+            .cloned()
+    }
+}
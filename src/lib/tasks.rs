@@ -0,0 +1,203 @@
+//! Tracking of running event handler tasks.
+//!
+//! Handlers are spawned as detached tasks, so on shutdown they are waited for here,
+//! to let commands that are still running finish before the connections are closed.
+//! Any that are still running after that are aborted, so that they do not outlive a restart.
+//! Delayed tasks are not worth waiting for, so they are cancelled on shutdown instead,
+//! as are the waits for user input, see [`Context::until_shutdown`](crate::Context::until_shutdown).
+
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::sync::Notify;
+
+/// Number of running tasks, with a notification when none are left.
+#[derive(Debug, Default)]
+pub struct RunningTasks {
+    count: AtomicUsize,
+    idle: Notify,
+    aborted: AtomicBool,
+    abort: Notify,
+}
+
+impl RunningTasks {
+    /// Run a task until it is done or the tasks are aborted.
+    pub fn spawn<F>(self: &Arc<Self>, task: F)
+    where
+        F: Future + Send + 'static,
+    {
+        let tasks = Arc::clone(self);
+        let guard = self.start();
+        tokio::spawn(async move {
+            let _guard = guard;
+            tokio::select! {
+                () = tasks.aborted() => (),
+                _ = task => (),
+            }
+        });
+    }
+
+    /// Abort all spawned tasks, and any that are spawned later.
+    pub fn abort(&self) {
+        self.aborted.store(true, Ordering::SeqCst);
+        self.abort.notify_waiters();
+    }
+
+    /// Wait until the tasks are aborted.
+    async fn aborted(&self) {
+        loop {
+            // Created before checking, so that a notification in between is not missed.
+            let abort = self.abort.notified();
+            if self.aborted.load(Ordering::SeqCst) {
+                return;
+            }
+            abort.await;
+        }
+    }
+
+    /// Mark a task as running until the returned guard is dropped.
+    pub fn start(self: &Arc<Self>) -> TaskGuard {
+        self.count.fetch_add(1, Ordering::SeqCst);
+        TaskGuard(Arc::clone(self))
+    }
+
+    /// Number of running tasks.
+    pub fn count(&self) -> usize {
+        self.count.load(Ordering::SeqCst)
+    }
+
+    /// Wait until no tasks are running.
+    pub async fn wait(&self) {
+        loop {
+            // Created before checking, so that a notification in between is not missed.
+            let idle = self.idle.notified();
+            if self.count() == 0 {
+                return;
+            }
+            idle.await;
+        }
+    }
+}
+
+/// Marks a task as running while alive.
+#[derive(Debug)]
+pub struct TaskGuard(Arc<RunningTasks>);
+
+impl Drop for TaskGuard {
+    fn drop(&mut self) {
+        if self.0.count.fetch_sub(1, Ordering::SeqCst) == 1 {
+            self.0.idle.notify_waiters();
+        }
+    }
+}
+
+/// Tasks that run after a delay, unless they are cancelled before that.
+#[derive(Debug, Default)]
+pub struct DelayedTasks {
+    cancelled: AtomicBool,
+    cancel: Notify,
+}
+
+impl DelayedTasks {
+    /// Run a task after `delay`, if the tasks are not cancelled by then.
+    pub fn spawn<F>(self: &Arc<Self>, delay: Duration, task: F)
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        let tasks = Arc::clone(self);
+        tokio::spawn(async move {
+            tokio::select! {
+                () = tasks.cancelled() => (),
+                () = tokio::time::sleep(delay) => task.await,
+            }
+        });
+    }
+
+    /// Cancel all waiting tasks, and any that are spawned later.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+        self.cancel.notify_waiters();
+    }
+
+    /// Returns `true` if the tasks have been cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// Wait until the tasks are cancelled.
+    pub async fn cancelled(&self) {
+        loop {
+            // Created before checking, so that a notification in between is not missed.
+            let cancel = self.cancel.notified();
+            if self.is_cancelled() {
+                return;
+            }
+            cancel.await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn wait_for_running_tasks() {
+        let tasks = Arc::new(RunningTasks::default());
+        tasks.wait().await; // Nothing running.
+
+        let guard = tasks.start();
+        let second = tasks.start();
+        assert_eq!(tasks.count(), 2);
+
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            drop(guard);
+            drop(second);
+        });
+
+        tokio::time::timeout(Duration::from_secs(1), tasks.wait())
+            .await
+            .unwrap();
+        assert_eq!(tasks.count(), 0);
+    }
+
+    #[tokio::test]
+    async fn abort_running_tasks() {
+        let tasks = Arc::new(RunningTasks::default());
+        tasks.spawn(std::future::pending::<()>());
+        tasks.spawn(std::future::pending::<()>());
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        assert_eq!(tasks.count(), 2);
+
+        tasks.abort();
+        tokio::time::timeout(Duration::from_secs(1), tasks.wait())
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn cancel_delayed_tasks() {
+        let tasks = Arc::new(DelayedTasks::default());
+        let done = Arc::new(AtomicBool::new(false));
+
+        let ran = Arc::clone(&done);
+        tasks.spawn(Duration::from_millis(10), async move {
+            ran.store(true, Ordering::SeqCst);
+        });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(done.swap(false, Ordering::SeqCst));
+
+        let ran = Arc::clone(&done);
+        tasks.spawn(Duration::from_millis(10), async move {
+            ran.store(true, Ordering::SeqCst);
+        });
+        tasks.cancel();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(!done.load(Ordering::SeqCst));
+    }
+}
@@ -0,0 +1,30 @@
+//! Fixtures shared by the tests of the library and the bot commands.
+
+use twilight_model::channel::Message;
+
+/// Message in channel `1` by user `author`, in reply to `replied` if given.
+pub fn chat_message(id: u64, author: u64, content: &str, replied: Option<Message>) -> Message {
+    serde_json::from_value(serde_json::json!({
+        "attachments": [],
+        "author": {
+            "id": author.to_string(),
+            "username": "user",
+            "discriminator": "0",
+            "avatar": null,
+        },
+        "channel_id": "1",
+        "content": content,
+        "edited_timestamp": null,
+        "embeds": [],
+        "id": id.to_string(),
+        "mention_everyone": false,
+        "mention_roles": [],
+        "mentions": [],
+        "pinned": false,
+        "referenced_message": replied,
+        "timestamp": "2024-01-01T00:00:00.000000+00:00",
+        "tts": false,
+        "type": if replied.is_some() { 19 } else { 0 },
+    }))
+    .unwrap()
+}
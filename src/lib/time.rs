@@ -0,0 +1,272 @@
+//! Parsing of natural-language time expressions.
+//!
+//! Supported forms, all in UTC:
+//! - Durations: `10m`, `2h30m`, `in 2 hours`, `in an hour and 15 minutes`.
+//! - Days: `tomorrow`, `today at 17:30`, `next monday`, `friday at 9am`, `at noon`.
+//! - Anything else accepted by [`dateparser`], such as `2024-06-01 12:00`.
+
+use chrono::{DateTime, Datelike, Duration, NaiveTime, Utc, Weekday};
+use thiserror::Error;
+
+/// Example expressions shown in errors.
+pub const EXAMPLES: &str = "`10m`, `2h30m`, `in 2 hours`, `tomorrow at 9am`, `next monday`";
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum TimeError {
+    #[error("Could not understand time '{0}', try for example: {EXAMPLES}")]
+    Invalid(String),
+
+    #[error("Time '{0}' is in the past")]
+    Past(String),
+}
+
+/// Parse a time expression relative to `now`. The result is always in the future.
+/// Durations too large to represent are invalid.
+pub fn parse_time(input: &str, now: DateTime<Utc>) -> Result<DateTime<Utc>, TimeError> {
+    let text = input.trim().to_lowercase();
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    let invalid = || TimeError::Invalid(input.trim().to_string());
+
+    let time = if let Some(duration) = duration(text.strip_prefix("in ").unwrap_or(&text)) {
+        now.checked_add_signed(duration).ok_or_else(invalid)?
+    } else if let Some(time) = day_and_clock(&text, now) {
+        time
+    } else {
+        dateparser::parse_with(&text, &Utc, now.time()).map_err(|_| invalid())?
+    };
+
+    if time <= now {
+        return Err(TimeError::Past(input.trim().to_string()));
+    }
+
+    Ok(time)
+}
+
+/// Parse a duration such as `5m`, `2h30m` or `2 hours and 30 minutes`.
+pub fn parse_duration(input: &str) -> Result<Duration, TimeError> {
+    let text = input.trim().to_lowercase();
+    duration(&text).ok_or_else(|| TimeError::Invalid(input.trim().to_string()))
+}
+
+/// Format a duration compactly, eg. `1h 30m` or `10s`.
+pub fn format_duration(duration: Duration) -> String {
+    let secs = duration.num_seconds().max(0);
+    let parts = [
+        (secs / 86400, "d"),
+        (secs / 3600 % 24, "h"),
+        (secs / 60 % 60, "m"),
+        (secs % 60, "s"),
+    ];
+
+    let text = parts
+        .iter()
+        .filter(|(n, _)| *n > 0)
+        .map(|(n, unit)| format!("{n}{unit}"))
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    if text.is_empty() {
+        "0s".to_string()
+    } else {
+        text
+    }
+}
+
+/// Parse a duration such as `2h30m` or `2 hours and 30 minutes`.
+/// Returns `None` if the duration does not fit in a [`Duration`].
+fn duration(text: &str) -> Option<Duration> {
+    let mut total = Duration::zero();
+    let mut tokens = text
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|t| !t.is_empty() && *t != "and")
+        .peekable();
+
+    tokens.peek()?;
+
+    while let Some(token) = tokens.next() {
+        if let Some(d) = shorthand(token) {
+            total = total.checked_add(&d)?;
+            continue;
+        }
+
+        let amount = match token {
+            "a" | "an" => 1,
+            _ => token.parse().ok()?,
+        };
+        total = total.checked_add(&unit(tokens.next()?, amount)?)?;
+    }
+
+    Some(total)
+}
+
+/// Parse compact durations like `10m` or `1d12h`.
+fn shorthand(token: &str) -> Option<Duration> {
+    let mut total = Duration::zero();
+    let mut rest = token;
+
+    while !rest.is_empty() {
+        let digits = rest.find(|c: char| !c.is_ascii_digit())?;
+        let letters = rest[digits..]
+            .find(|c: char| c.is_ascii_digit())
+            .map_or(rest.len(), |i| digits + i);
+
+        let amount = rest[..digits].parse().ok()?;
+        total = total.checked_add(&unit(&rest[digits..letters], amount)?)?;
+        rest = &rest[letters..];
+    }
+
+    Some(total)
+}
+
+fn unit(name: &str, amount: i64) -> Option<Duration> {
+    let duration = match name.trim_end_matches('s') {
+        "" | "sec" | "second" => Duration::try_seconds,
+        "m" | "min" | "minute" => Duration::try_minutes,
+        "h" | "hr" | "hour" => Duration::try_hours,
+        "d" | "day" => Duration::try_days,
+        "w" | "week" => Duration::try_weeks,
+        _ => return None,
+    };
+    duration(amount)
+}
+
+/// Parse expressions like `tomorrow at 9am`, `next friday` or `at 17:30`.
+fn day_and_clock(text: &str, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    let split = text
+        .strip_prefix("at ")
+        .map(|clock| ("", clock))
+        .or_else(|| text.split_once(" at "));
+
+    let (day, clock) = match split {
+        Some((day, clock)) => (day.trim(), Some(self::clock(clock)?)),
+        None => match self::clock(text) {
+            Some(clock) => ("", Some(clock)),
+            None => (text, None),
+        },
+    };
+
+    let today = now.date_naive();
+    let date = match day {
+        "" => {
+            // Next occurrence of the given time.
+            let clock = clock?;
+            if clock > now.time() {
+                today
+            } else {
+                today.succ_opt()?
+            }
+        },
+        "today" => today,
+        "tomorrow" => today.succ_opt()?,
+        _ => {
+            let weekday: Weekday = day.strip_prefix("next ").unwrap_or(day).parse().ok()?;
+            let ahead =
+                (weekday.num_days_from_monday() + 7 - today.weekday().num_days_from_monday()) % 7;
+            today + Duration::days(if ahead == 0 { 7 } else { ahead.into() })
+        },
+    };
+
+    Some(date.and_time(clock.unwrap_or_else(|| now.time())).and_utc())
+}
+
+/// Parse a time of day like `9am`, `9:30 pm`, `17:30` or `noon`.
+fn clock(text: &str) -> Option<NaiveTime> {
+    let text = text.replace(' ', "");
+    match text.as_str() {
+        "noon" => return NaiveTime::from_hms_opt(12, 0, 0),
+        "midnight" => return NaiveTime::from_hms_opt(0, 0, 0),
+        _ => (),
+    }
+
+    let (text, offset) = if let Some(t) = text.strip_suffix("am") {
+        (t, Some(0))
+    } else if let Some(t) = text.strip_suffix("pm") {
+        (t, Some(12))
+    } else {
+        (text.as_str(), None)
+    };
+
+    let (hour, minute) = text.split_once(':').unwrap_or((text, "0"));
+    let (hour, minute): (u32, u32) = (hour.parse().ok()?, minute.parse().ok()?);
+
+    let hour = match offset {
+        Some(offset) if (1..=12).contains(&hour) => hour % 12 + offset,
+        Some(_) => return None,
+        // Plain numbers are not times, unless written as `hh:mm`.
+        None if !text.contains(':') => return None,
+        None => hour,
+    };
+
+    NaiveTime::from_hms_opt(hour, minute, 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    #[test]
+    fn natural_times() {
+        // A wednesday.
+        let now = Utc.with_ymd_and_hms(2024, 5, 15, 12, 0, 0).unwrap();
+        let at = |d, h, m| Utc.with_ymd_and_hms(2024, 5, d, h, m, 0).unwrap();
+
+        assert_eq!(parse_time("10m", now), Ok(at(15, 12, 10)));
+        assert_eq!(parse_time("1h30m", now), Ok(at(15, 13, 30)));
+        assert_eq!(parse_time("in 2 hours", now), Ok(at(15, 14, 0)));
+        assert_eq!(
+            parse_time("in an hour and 5 minutes", now),
+            Ok(at(15, 13, 5))
+        );
+        assert_eq!(parse_time("tomorrow", now), Ok(at(16, 12, 0)));
+        assert_eq!(parse_time("Tomorrow at 9am", now), Ok(at(16, 9, 0)));
+        assert_eq!(parse_time("today at 5:30 pm", now), Ok(at(15, 17, 30)));
+        assert_eq!(parse_time("at 11:00", now), Ok(at(16, 11, 0)));
+        assert_eq!(parse_time("next monday", now), Ok(at(20, 12, 0)));
+        assert_eq!(parse_time("wednesday at noon", now), Ok(at(22, 12, 0)));
+        assert_eq!(parse_time("2024-05-20 08:00", now), Ok(at(20, 8, 0)));
+
+        assert!(matches!(
+            parse_time("today at 9am", now),
+            Err(TimeError::Past(_))
+        ));
+        assert!(matches!(
+            parse_time("someday", now),
+            Err(TimeError::Invalid(_))
+        ));
+        assert!(matches!(
+            parse_time("in 5 parsecs", now),
+            Err(TimeError::Invalid(_))
+        ));
+    }
+
+    #[test]
+    fn durations() {
+        assert_eq!(parse_duration("10s"), Ok(Duration::seconds(10)));
+        assert_eq!(parse_duration(" 5M "), Ok(Duration::minutes(5)));
+        assert_eq!(
+            parse_duration("1 hour and 30 minutes"),
+            Ok(Duration::minutes(90))
+        );
+        assert!(parse_duration("soon").is_err());
+
+        // Too large to represent.
+        assert!(parse_duration("9999999999999w").is_err());
+        assert!(parse_duration("9223372036854775807s").is_err());
+        assert!(parse_duration("100000000000d 100000000000d").is_err());
+        assert!(matches!(
+            parse_time("9999999999999w", Utc::now()),
+            Err(TimeError::Invalid(_))
+        ));
+        assert!(matches!(
+            parse_time("in 100000000 days", Utc::now()),
+            Err(TimeError::Invalid(_))
+        ));
+
+        assert_eq!(format_duration(Duration::seconds(10)), "10s");
+        assert_eq!(format_duration(Duration::seconds(5400)), "1h 30m");
+        assert_eq!(format_duration(Duration::seconds(90061)), "1d 1h 1m 1s");
+        assert_eq!(format_duration(Duration::zero()), "0s");
+    }
+}
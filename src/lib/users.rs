@@ -0,0 +1,129 @@
+//! Users fetched from the client.
+//!
+//! The gateway cache only learns about users from events, so users fetched by id
+//! are kept here for a while instead, to avoid fetching them again for every command.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use futures::stream::{self, StreamExt};
+use twilight_model::id::marker::UserMarker;
+use twilight_model::id::Id;
+use twilight_model::user::User;
+
+use crate::utils::prelude::*;
+
+/// Maximum number of users fetched at the same time.
+pub const CONCURRENCY: usize = 4;
+
+/// Time to keep a fetched user.
+const TTL: Duration = Duration::from_secs(10 * 60);
+
+/// Store of recently fetched users.
+#[derive(Debug, Default)]
+pub struct FetchedUsers {
+    users: Mutex<HashMap<Id<UserMarker>, (Instant, User)>>,
+}
+
+impl FetchedUsers {
+    /// Get a user that has not expired yet.
+    pub fn get(&self, user_id: Id<UserMarker>) -> Option<User> {
+        let users = self.users.lock().unwrap();
+        users
+            .get(&user_id)
+            .filter(|(expires, _)| *expires > Instant::now())
+            .map(|(_, user)| user.to_owned())
+    }
+
+    /// Store a fetched user.
+    pub fn insert(&self, user: User) {
+        let mut users = self.users.lock().unwrap();
+        let now = Instant::now();
+
+        // Drop the expired ones while at it.
+        users.retain(|_, (expires, _)| *expires > now);
+        users.insert(user.id, (now + TTL, user));
+    }
+}
+
+/// Get the users with `ids` by `cached`, and `fetch` the rest, up to [`CONCURRENCY`] at once.
+/// Results are in the order of `ids`, with `None` for users that could not be fetched.
+pub async fn lookup_or_fetch<C, F, Fut>(
+    ids: &[Id<UserMarker>],
+    cached: C,
+    fetch: F,
+) -> Vec<Option<User>>
+where
+    C: Fn(Id<UserMarker>) -> Option<User>,
+    F: Fn(Id<UserMarker>) -> Fut,
+    Fut: Future<Output = AnyResult<User>>,
+{
+    let mut users: Vec<_> = ids.iter().map(|&id| cached(id)).collect();
+
+    let misses: Vec<_> = users
+        .iter()
+        .enumerate()
+        .filter(|(_, u)| u.is_none())
+        .map(|(i, _)| i)
+        .collect();
+
+    let fetched: Vec<_> = stream::iter(misses)
+        .map(|i| {
+            let fut = fetch(ids[i]);
+            async move { (i, fut.await) }
+        })
+        .buffer_unordered(CONCURRENCY)
+        .collect()
+        .await;
+
+    for (i, result) in fetched {
+        match result {
+            Ok(user) => users[i] = Some(user),
+            Err(e) => debug!("Could not fetch user '{}': {e}", ids[i]),
+        }
+    }
+
+    users
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn user(id: u64, name: &str) -> User {
+        serde_json::from_value(serde_json::json!({
+            "id": id.to_string(),
+            "username": name,
+            "discriminator": "0",
+            "avatar": null,
+        }))
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn cached_and_fetched_users() {
+        let ids = [Id::new(1), Id::new(2), Id::new(3), Id::new(4)];
+        let cached = |id: Id<UserMarker>| (id.get() % 2 == 1).then(|| user(id.get(), "cached"));
+        let fetch = |id: Id<UserMarker>| async move {
+            match id.get() {
+                4 => anyhow::bail!("Unknown user"),
+                id => Ok(user(id, "fetched")),
+            }
+        };
+
+        let users = lookup_or_fetch(&ids, cached, fetch).await;
+        let names: Vec<_> = users
+            .iter()
+            .map(|u| u.as_ref().map(|u| (u.id.get(), u.name.as_str())))
+            .collect();
+
+        assert_eq!(names, [
+            Some((1, "cached")),
+            Some((2, "fetched")),
+            Some((3, "cached")),
+            None,
+        ]);
+    }
+}
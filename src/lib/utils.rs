@@ -1,25 +1,31 @@
 use std::borrow::Cow;
 use std::fmt::Display;
+use std::io::{self, Read, Seek, SeekFrom};
 
 use serde::Serialize;
+use twilight_http::api_error::ApiError;
+use twilight_http::error::ErrorType;
 use twilight_http::request::application::command::{SetGlobalCommands, SetGuildCommands};
 use twilight_http::request::application::interaction::{CreateFollowup, UpdateResponse};
 use twilight_http::request::channel::message::{
     CreateMessage, GetChannelMessages, GetChannelMessagesConfigured, GetMessage, UpdateMessage,
 };
 use twilight_http::request::channel::GetChannel;
-use twilight_http::request::guild::emoji::GetEmojis;
+use twilight_http::request::guild::emoji::{CreateEmoji, GetEmojis};
 use twilight_http::request::guild::member::GetMember;
 use twilight_http::request::guild::role::GetGuildRoles;
 use twilight_http::request::guild::{GetGuild, GetGuildChannels};
-use twilight_http::request::user::{GetCurrentUser, GetCurrentUserGuildMember, GetUser};
+use twilight_http::request::user::{
+    CreatePrivateChannel, GetCurrentUser, GetCurrentUserGuildMember, GetUser,
+};
 use twilight_http::request::GetUserApplicationInfo;
 use twilight_model::application::command::Command;
-use twilight_model::channel::message::ReactionType;
+use twilight_model::channel::message::{AllowedMentions, MentionType, ReactionType};
 use twilight_model::channel::{Attachment, Channel, Message};
-use twilight_model::guild::{Emoji, Guild, Member, Role};
+use twilight_model::guild::{Emoji, Guild, Member, Permissions, Role};
 use twilight_model::id::marker::{
-    AttachmentMarker, ChannelMarker, EmojiMarker, MessageMarker, RoleMarker, UserMarker,
+    AttachmentMarker, ChannelMarker, EmojiMarker, GuildMarker, MessageMarker, RoleMarker,
+    UserMarker,
 };
 use twilight_model::id::Id;
 use twilight_model::oauth::Application;
@@ -44,7 +50,15 @@ pub mod prelude {
 /// Universal constants.
 pub mod consts {
     pub const EVERYONE: &str = "@everyone";
-    pub const DELIMITERS: &[char] = &['\'', '"', '`'];
+    /// Quote characters as `(open, close)` pairs.
+    pub const DELIMITER_PAIRS: &[(char, char)] =
+        &[('\'', '\''), ('"', '"'), ('`', '`'), ('“', '”'), ('‘', '’')];
+    /// Default limit of arguments parsed from a single message.
+    pub const MAX_ARGS: usize = 64;
+    /// Default limit of messages deleted by a single purge.
+    pub const MAX_PURGE: usize = 1000;
+    /// Default seconds to wait for the bot to shut down cleanly.
+    pub const SHUTDOWN_TIMEOUT: u64 = 10;
 }
 
 pub trait ErrorExt {
@@ -81,8 +95,10 @@ macro impl_exec_model_ext($req:ty, $val:ty) {
     }
 }
 
+impl_exec_model_ext!(CreateEmoji<'_>, Emoji);
 impl_exec_model_ext!(CreateFollowup<'_>, Message);
 impl_exec_model_ext!(CreateMessage<'_>, Message);
+impl_exec_model_ext!(CreatePrivateChannel<'_>, Channel);
 impl_exec_model_ext!(GetChannel<'_>, Channel);
 impl_exec_model_ext!(GetChannelMessages<'_>, Vec<Message>);
 impl_exec_model_ext!(GetChannelMessagesConfigured<'_>, Vec<Message>);
@@ -228,6 +244,194 @@ pub fn escape_discord_chars(text: &str) -> Cow<'_, str> {
     Cow::Owned(out)
 }
 
+/// Make text safe to show inside a code block.
+/// Backticks are separated with zero-width spaces, so they cannot close the block.
+pub fn escape_code_block(text: &str) -> Cow<'_, str> {
+    if !text.contains('`') {
+        return Cow::Borrowed(text);
+    }
+
+    Cow::Owned(text.replace('`', "`\u{200B}"))
+}
+
+/// Shorten text to at most `max` characters, marking the cut with an ellipsis.
+pub fn truncate(text: &str, max: usize) -> Cow<'_, str> {
+    match text.char_indices().nth(max) {
+        None => Cow::Borrowed(text),
+        Some(_) => {
+            let end = text
+                .char_indices()
+                .nth(max.saturating_sub(1))
+                .map_or(0, |(i, _)| i);
+            Cow::Owned(format!("{}…", &text[..end]))
+        },
+    }
+}
+
+/// List of notable permissions in a human readable form.
+pub fn notable_permissions(perms: Permissions) -> String {
+    const NOTABLE: &[(Permissions, &str)] = &[
+        (Permissions::MANAGE_GUILD, "Manage Server"),
+        (Permissions::MANAGE_ROLES, "Manage Roles"),
+        (Permissions::MANAGE_CHANNELS, "Manage Channels"),
+        (Permissions::MANAGE_MESSAGES, "Manage Messages"),
+        (Permissions::MANAGE_WEBHOOKS, "Manage Webhooks"),
+        (Permissions::KICK_MEMBERS, "Kick Members"),
+        (Permissions::BAN_MEMBERS, "Ban Members"),
+        (Permissions::MODERATE_MEMBERS, "Timeout Members"),
+        (Permissions::MENTION_EVERYONE, "Mention Everyone"),
+    ];
+
+    if perms.contains(Permissions::ADMINISTRATOR) {
+        return "Administrator (all permissions)".to_string();
+    }
+
+    let list: Vec<_> = NOTABLE
+        .iter()
+        .filter(|(p, _)| perms.contains(*p))
+        .map(|(_, name)| *name)
+        .collect();
+
+    if list.is_empty() {
+        "-".to_string()
+    } else {
+        list.join(", ")
+    }
+}
+
+/// Milliseconds from the Unix epoch to the start of 2015, which snowflake ids count from.
+const DISCORD_EPOCH: u64 = 1_420_070_400_000;
+
+/// Creation time of a snowflake id, in seconds since the Unix epoch.
+pub const fn snowflake_secs<T>(id: Id<T>) -> u64 {
+    ((id.get() >> 22) + DISCORD_EPOCH) / 1000
+}
+
+/// Split text into parts of at most `max` characters, at line breaks when possible.
+/// Line breaks that a part was split at are left out.
+pub fn split_message(text: &str, max: usize) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut rest = text;
+
+    while let Some((end, _)) = rest.char_indices().nth(max) {
+        let (part, next) = match rest[..end].rfind('\n') {
+            Some(i) if i > 0 => (&rest[..i], &rest[i + 1..]),
+            _ => rest.split_at(end),
+        };
+        parts.push(part);
+        rest = next;
+    }
+    parts.push(rest);
+
+    parts
+}
+
+/// Read the last `lines` lines of a file, looking at most `max_bytes` from its end.
+pub fn tail_lines<R: Read + Seek>(
+    mut reader: R,
+    lines: usize,
+    max_bytes: u64,
+) -> io::Result<String> {
+    let len = reader.seek(SeekFrom::End(0))?;
+    let start = len.saturating_sub(max_bytes);
+    reader.seek(SeekFrom::Start(start))?;
+
+    let mut buf = Vec::new();
+    reader.read_to_end(&mut buf)?;
+    let text = String::from_utf8_lossy(&buf);
+
+    // The first line is likely partial, unless reading from the start.
+    let skip = usize::from(start > 0);
+    let all: Vec<_> = text.lines().skip(skip).collect();
+    Ok(all[all.len().saturating_sub(lines)..].join("\n"))
+}
+
+/// Host of the Discord CDN, where attachments and emojis are served from.
+pub const DISCORD_CDN: &str = "cdn.discordapp.com";
+
+/// Returns `true` if the URL points to the Discord CDN over https.
+pub fn is_discord_cdn(url: &str) -> bool {
+    reqwest::Url::parse(url)
+        .is_ok_and(|u| u.scheme() == "https" && u.host_str() == Some(DISCORD_CDN))
+}
+
+/// Download a file from the Discord CDN, failing if it is larger than `max_bytes`.
+/// Other URLs are refused, as are redirects away from them.
+pub async fn download(url: &str, max_bytes: usize) -> AnyResult<Vec<u8>> {
+    if !is_discord_cdn(url) {
+        anyhow::bail!("Only files from {DISCORD_CDN} can be downloaded");
+    }
+
+    let mut resp = reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .build()?
+        .get(url)
+        .send()
+        .await
+        .and_then(reqwest::Response::error_for_status)
+        .with_context(|| format!("Failed to download '{url}'"))?;
+
+    let too_large = || anyhow::anyhow!("File is larger than {max_bytes} bytes");
+    if resp
+        .content_length()
+        .is_some_and(|len| len > max_bytes as u64)
+    {
+        return Err(too_large());
+    }
+
+    // The length is not always known beforehand, so stop reading once there is too much.
+    let mut bytes = Vec::new();
+    while let Some(chunk) = resp.chunk().await.context("Failed to read download")? {
+        if bytes.len() + chunk.len() > max_bytes {
+            return Err(too_large());
+        }
+        bytes.extend_from_slice(&chunk);
+    }
+
+    Ok(bytes)
+}
+
+/// Detect the MIME type of png, jpeg, gif or webp image data.
+pub fn image_mime(data: &[u8]) -> Option<&'static str> {
+    match data {
+        [0x89, b'P', b'N', b'G', ..] => Some("image/png"),
+        [0xFF, 0xD8, 0xFF, ..] => Some("image/jpeg"),
+        [b'G', b'I', b'F', b'8', ..] => Some("image/gif"),
+        [b'R', b'I', b'F', b'F', _, _, _, _, b'W', b'E', b'B', b'P', ..] => Some("image/webp"),
+        _ => None,
+    }
+}
+
+/// Encode image data as a data URI, as used by Discord for uploads.
+pub fn image_data_uri(data: &[u8]) -> Option<String> {
+    use base64::Engine;
+
+    image_mime(data).map(|mime| {
+        format!(
+            "data:{mime};base64,{}",
+            base64::engine::general_purpose::STANDARD.encode(data)
+        )
+    })
+}
+
+/// Returns the best display name for a user: global display name, otherwise the username.
+/// Legacy usernames are shown with their discriminator, unless it is the migrated `#0`.
+pub fn display_name(user: &User) -> Cow<'_, str> {
+    match (&user.global_name, user.discriminator) {
+        (Some(global), _) => Cow::Borrowed(global),
+        (None, 0) => Cow::Borrowed(&user.name),
+        (None, _) => Cow::Owned(format!("{}#{}", user.name, user.discriminator())),
+    }
+}
+
+/// Returns the best display name for a guild member: nickname, otherwise same as `display_name`.
+pub fn display_name_member(member: &Member) -> Cow<'_, str> {
+    member
+        .nick
+        .as_deref()
+        .map_or_else(|| display_name(&member.user), Cow::Borrowed)
+}
+
 /// Display reaction in discord emoji format.
 /// Returns `Err(id)` *(id as string)* if emoji name is unavailable.
 pub fn display_reaction_emoji(reaction: &ReactionType) -> Result<String, String> {
@@ -247,6 +451,37 @@ pub fn display_reaction_emoji(reaction: &ReactionType) -> Result<String, String>
     }
 }
 
+/// Mentions that bot messages are allowed to ping: users, but not roles or everyone.
+pub fn default_allowed_mentions() -> AllowedMentions {
+    AllowedMentions {
+        parse: vec![MentionType::Users],
+        replied_user: true,
+        ..Default::default()
+    }
+}
+
+/// Link to a message, `guild_id` is `None` for messages in DMs.
+pub fn message_link(
+    guild_id: Option<Id<GuildMarker>>,
+    channel_id: Id<ChannelMarker>,
+    message_id: Id<MessageMarker>,
+) -> String {
+    let guild = guild_id.map_or_else(|| "@me".to_string(), |id| id.to_string());
+    format!("https://discord.com/channels/{guild}/{channel_id}/{message_id}")
+}
+
+/// Parse a message link or a plain message id into `(channel, message)` ids.
+/// The channel is `None` for a plain id.
+pub fn parse_message_link(text: &str) -> Option<(Option<Id<ChannelMarker>>, Id<MessageMarker>)> {
+    let mut parts = text.trim().trim_end_matches('/').rsplit('/');
+    let message_id = parts.next()?.parse().ok()?;
+
+    match parts.next() {
+        None => Some((None, message_id)),
+        Some(channel) => Some((Some(channel.parse().ok()?), message_id)),
+    }
+}
+
 /// Format `obj` with a pretty json formatter with 4 space indent.
 /// # Panics
 /// This will panic if serialization failed or output is invalid utf-8.
@@ -282,3 +517,190 @@ impl<'a> From<&'a ReactionType> for Shenanigans<'a> {
 pub fn reaction_type_eq(this: &ReactionType, other: &ReactionType) -> bool {
     Shenanigans::from(this) == Shenanigans::from(other)
 }
+
+/// Discord error code of a failed request, if the error is from an http response.
+pub fn api_error_code(error: &anyhow::Error) -> Option<u64> {
+    match error.downcast_ref::<twilight_http::Error>()?.kind() {
+        ErrorType::Response { error, .. } => general_error_code(error),
+        _ => None,
+    }
+}
+
+fn general_error_code(error: &ApiError) -> Option<u64> {
+    match error {
+        ApiError::General(e) => Some(e.code),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use twilight_model::guild::MemberFlags;
+    use twilight_model::util::Timestamp;
+
+    use super::*;
+
+    fn user(name: &str, global_name: Option<&str>, discriminator: u16) -> User {
+        User {
+            accent_color: None,
+            avatar: None,
+            avatar_decoration: None,
+            banner: None,
+            bot: false,
+            discriminator,
+            email: None,
+            flags: None,
+            global_name: global_name.map(ToString::to_string),
+            id: Id::new(1),
+            locale: None,
+            mfa_enabled: None,
+            name: name.to_string(),
+            premium_type: None,
+            public_flags: None,
+            system: None,
+            verified: None,
+        }
+    }
+
+    fn member(user: User, nick: Option<&str>) -> Member {
+        Member {
+            avatar: None,
+            communication_disabled_until: None,
+            deaf: false,
+            flags: MemberFlags::empty(),
+            joined_at: Timestamp::from_secs(0).unwrap(),
+            mute: false,
+            nick: nick.map(ToString::to_string),
+            pending: false,
+            premium_since: None,
+            roles: Vec::new(),
+            user,
+        }
+    }
+
+    #[test]
+    fn display_name_precedence() {
+        assert_eq!(display_name(&user("name", Some("Global"), 0)), "Global");
+        assert_eq!(display_name(&user("name", Some("Global"), 1234)), "Global");
+        assert_eq!(display_name(&user("name", None, 0)), "name");
+        assert_eq!(display_name(&user("name", None, 42)), "name#0042");
+    }
+
+    #[test]
+    fn display_name_member_precedence() {
+        let with_global = || user("name", Some("Global"), 0);
+        assert_eq!(
+            display_name_member(&member(with_global(), Some("Nick"))),
+            "Nick"
+        );
+        assert_eq!(display_name_member(&member(with_global(), None)), "Global");
+        assert_eq!(
+            display_name_member(&member(user("name", None, 0), None)),
+            "name"
+        );
+    }
+
+    #[test]
+    fn snowflake_creation_time() {
+        let id: Id<UserMarker> = Id::new(175928847299117063);
+        assert_eq!(snowflake_secs(id), 1462015105);
+    }
+
+    #[test]
+    fn split_long_messages() {
+        assert_eq!(split_message("short", 5), ["short"]);
+        assert_eq!(split_message("one\ntwo\nthree", 8), ["one\ntwo", "three"]);
+        assert_eq!(split_message("abcdefgh", 3), ["abc", "def", "gh"]);
+        assert_eq!(split_message("äöåäö", 2), ["äö", "åä", "ö"]);
+        assert_eq!(split_message("\nabcdef", 4), ["\nabc", "def"]);
+    }
+
+    #[test]
+    fn truncate_chars() {
+        assert_eq!(truncate("short", 5), "short");
+        assert_eq!(truncate("longer", 5), "long…");
+        assert_eq!(truncate("äöåäö!", 4), "äöå…");
+    }
+
+    #[test]
+    fn tail_of_file() {
+        let text = "one\ntwo\nthree\nfour\n";
+        let tail = |lines, max| tail_lines(io::Cursor::new(text), lines, max).unwrap();
+
+        assert_eq!(tail(2, 100), "three\nfour");
+        assert_eq!(tail(10, 100), "one\ntwo\nthree\nfour");
+        // Partial first line is dropped.
+        assert_eq!(tail(10, 13), "three\nfour");
+    }
+
+    #[test]
+    fn image_data() {
+        assert_eq!(image_mime(b"\x89PNG\r\n"), Some("image/png"));
+        assert_eq!(image_mime(b"RIFF\0\0\0\0WEBPVP8"), Some("image/webp"));
+        assert_eq!(image_mime(b"<html>"), None);
+        assert_eq!(
+            image_data_uri(b"GIF89a").as_deref(),
+            Some("data:image/gif;base64,R0lGODlh")
+        );
+    }
+
+    #[test]
+    fn discord_cdn_urls() {
+        assert!(is_discord_cdn("https://cdn.discordapp.com/emojis/1.png"));
+        assert!(!is_discord_cdn("http://cdn.discordapp.com/emojis/1.png"));
+        assert!(!is_discord_cdn(
+            "https://cdn.discordapp.com.example.com/1.png"
+        ));
+        assert!(!is_discord_cdn(
+            "https://example.com/cdn.discordapp.com/1.png"
+        ));
+        assert!(!is_discord_cdn("https://user@example.com"));
+        assert!(!is_discord_cdn("not a url"));
+    }
+
+    #[test]
+    fn message_links() {
+        assert_eq!(
+            message_link(Some(Id::new(1)), Id::new(2), Id::new(3)),
+            "https://discord.com/channels/1/2/3"
+        );
+        assert_eq!(
+            message_link(None, Id::new(2), Id::new(3)),
+            "https://discord.com/channels/@me/2/3"
+        );
+    }
+
+    #[test]
+    fn message_link_parsing() {
+        let link = message_link(Some(Id::new(1)), Id::new(2), Id::new(3));
+        assert_eq!(
+            parse_message_link(&link),
+            Some((Some(Id::new(2)), Id::new(3)))
+        );
+        assert_eq!(parse_message_link(" 3 "), Some((None, Id::new(3))));
+        assert_eq!(
+            parse_message_link("https://discord.com/channels/1/x/3"),
+            None
+        );
+        assert_eq!(parse_message_link("three"), None);
+    }
+
+    #[test]
+    fn api_error_codes() {
+        let api_error = |json: &str| serde_json::from_str::<ApiError>(json).unwrap();
+
+        assert_eq!(
+            general_error_code(&api_error(
+                r#"{"code": 50007, "message": "Cannot send messages to this user"}"#
+            )),
+            Some(50007)
+        );
+        assert_eq!(
+            general_error_code(&api_error(
+                r#"{"global": false, "message": "You are being rate limited.", "retry_after": 1.0}"#
+            )),
+            None
+        );
+        assert_eq!(api_error_code(&anyhow::anyhow!("Network error")), None);
+    }
+}
@@ -0,0 +1,143 @@
+//! Join-gate for new members.
+//!
+//! New members get the unverified role, which is swapped for the verified role once they press
+//! the button of the verification message. The message is posted again if it has gone missing.
+
+use twilight_model::application::interaction::message_component::MessageComponentInteractionData;
+use twilight_model::application::interaction::Interaction;
+use twilight_model::channel::message::component::ButtonStyle;
+use twilight_model::channel::message::MessageFlags;
+use twilight_model::guild::Member;
+use twilight_model::http::interaction::{
+    InteractionResponse, InteractionResponseData, InteractionResponseType,
+};
+use twilight_model::id::marker::GuildMarker;
+use twilight_model::id::Id;
+
+use crate::components::{self, ComponentsBuilder};
+use crate::utils::prelude::*;
+use crate::{utils, Context};
+
+/// Custom id prefix of the verification button.
+pub const PREFIX: &str = "verify";
+
+/// Discord error code for a message that does not exist.
+const UNKNOWN_MESSAGE: u64 = 10008;
+
+/// Give the unverified role to a new member, unless they already have either role.
+pub async fn member_added(
+    ctx: &Context,
+    guild_id: Id<GuildMarker>,
+    member: &Member,
+) -> AnyResult<()> {
+    let Some(settings) = ctx.config.guild(guild_id).verification()? else {
+        return Ok(());
+    };
+
+    let has = |role| member.roles.contains(&role);
+    if has(settings.unverified_role) || settings.verified_role.is_some_and(has) {
+        return Ok(());
+    }
+
+    ctx.http
+        .add_guild_member_role(guild_id, member.user.id, settings.unverified_role)
+        .await
+        .with_context(|| format!("Failed to add unverified role in guild '{guild_id}'"))?;
+
+    Ok(())
+}
+
+/// Make sure the verification message exists, posting a new one if needed.
+pub async fn ensure_message(ctx: &Context, guild_id: Id<GuildMarker>) -> AnyResult<()> {
+    let Some(settings) = ctx.config.guild(guild_id).verification()? else {
+        return Ok(());
+    };
+
+    // Only a message that is known to be gone is replaced, not on any failure to fetch it.
+    if let Some(message_id) = settings.message {
+        let message = ctx.http.message(settings.channel, message_id).await;
+        match message.map_err(anyhow::Error::from) {
+            Ok(_) => return Ok(()),
+            Err(e) if utils::api_error_code(&e) == Some(UNKNOWN_MESSAGE) => (),
+            Err(e) => {
+                return Err(e).with_context(|| {
+                    format!("Failed to get verification message in guild '{guild_id}'")
+                });
+            },
+        }
+    }
+
+    let buttons = ComponentsBuilder::new()
+        .buttons([components::button(
+            format!("{PREFIX}:click"),
+            "✅ Verify",
+            ButtonStyle::Success,
+        )])?
+        .build();
+
+    let msg = ctx
+        .http
+        .create_message(settings.channel)
+        .content("Press the button to get access to the server.")?
+        .components(&buttons)?
+        .send()
+        .await
+        .with_context(|| format!("Failed to post verification message in guild '{guild_id}'"))?;
+
+    ctx.config
+        .guild(guild_id)
+        .set_verification_message(msg.id)?;
+
+    Ok(())
+}
+
+/// Handle a verification button press.
+pub async fn handle_component(
+    ctx: &Context,
+    inter: &Interaction,
+    _data: &MessageComponentInteractionData,
+) -> AnyResult<()> {
+    let (Some(guild_id), Some(member)) = (inter.guild_id, inter.member.as_ref()) else {
+        return Ok(());
+    };
+    let user_id = inter
+        .author_id()
+        .context("Component interaction without a user")?;
+
+    let settings = ctx.config.guild(guild_id).verification()?;
+    let content = match settings {
+        None => "Verification is not enabled.",
+        Some(settings) if !member.roles.contains(&settings.unverified_role) => {
+            "You are already verified."
+        },
+        Some(settings) => {
+            if let Some(role_id) = settings.verified_role {
+                ctx.http
+                    .add_guild_member_role(guild_id, user_id, role_id)
+                    .await
+                    .context("Failed to add verified role")?;
+            }
+            ctx.http
+                .remove_guild_member_role(guild_id, user_id, settings.unverified_role)
+                .await
+                .context("Failed to remove unverified role")?;
+
+            debug!("User '{user_id}' verified in guild '{guild_id}'");
+            "You are now verified, welcome!"
+        },
+    };
+
+    ctx.interaction()
+        .create_response(inter.id, &inter.token, &InteractionResponse {
+            kind: InteractionResponseType::ChannelMessageWithSource,
+            data: Some(InteractionResponseData {
+                content: Some(content.to_string()),
+                flags: Some(MessageFlags::EPHEMERAL),
+                ..Default::default()
+            }),
+        })
+        .await
+        .context("Failed to respond to verification button")?;
+
+    Ok(())
+}
@@ -2,29 +2,57 @@
 #![allow(clippy::significant_drop_in_scrutinee)]
 
 use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use std::{env, fs};
 
 use riveting_bot::commands::{handle, CommandError};
+use riveting_bot::config::{BotConfig, Sessions};
+use riveting_bot::snipe::MessageCopy;
 use riveting_bot::utils::prelude::*;
 use riveting_bot::utils::{self};
-use riveting_bot::{BotEvent, BotEventSender, Context};
+use riveting_bot::{activity, verify, BotEvent, BotEventSender, Context, ShutdownReason};
 use tokio::sync::mpsc;
 use tracing::Level;
 use tracing_subscriber::EnvFilter;
 use twilight_gateway::stream::ShardEventStream;
 use twilight_gateway::{CloseFrame, Event};
-use twilight_model::application::interaction::{Interaction, InteractionData};
+use twilight_http::request::Request;
+use twilight_http::response::marker::ListBody;
+use twilight_http::routing::Route;
+use twilight_mention::Mention;
+use twilight_model::application::command::Command;
+use twilight_model::application::interaction::{Interaction, InteractionData, InteractionType};
+use twilight_model::channel::message::AllowedMentions;
 use twilight_model::channel::Message;
 use twilight_model::gateway::payload::incoming::{
     Hello, MessageDelete, MessageDeleteBulk, MessageUpdate, Ready,
 };
 use twilight_model::gateway::GatewayReaction;
 use twilight_model::guild::Guild;
+use twilight_model::id::marker::{ChannelMarker, GuildMarker, MessageMarker};
 use twilight_model::id::Id;
 use twilight_model::voice::VoiceState;
+use twilight_util::builder::embed::{
+    EmbedAuthorBuilder, EmbedBuilder, EmbedFieldBuilder, EmbedFooterBuilder,
+};
 
 mod bot;
 
+/// Library test fixtures, not all of them are used with every feature.
+#[cfg(test)]
+#[allow(unused)]
+#[path = "lib/test_utils.rs"]
+mod test_utils;
+
+/// Initial delay before restarting, doubled on each consecutive restart.
+const RESTART_BACKOFF_MIN: Duration = Duration::from_secs(5);
+
+/// Longest delay before restarting.
+const RESTART_BACKOFF_MAX: Duration = Duration::from_secs(5 * 60);
+
+/// Run time after which a restart is not considered consecutive.
+const RESTART_BACKOFF_RESET: Duration = Duration::from_secs(10 * 60);
+
 #[tracing::instrument]
 #[tokio::main]
 async fn main() -> AnyResult<()> {
@@ -63,28 +91,89 @@ async fn main() -> AnyResult<()> {
     // Spawn ctrl-c shutdown task.
     tokio::spawn(shutdown_task(events_tx.clone()));
 
-    let (ctx, mut shards) = Context::new(events_tx, bot::create_commands()?).await?;
+    let mut backoff = RESTART_BACKOFF_MIN;
+
+    loop {
+        let started = Instant::now();
+
+        match run(events_tx.clone(), &mut events_rx).await? {
+            Exit::Shutdown(reason) => {
+                info!("Shut down: {reason}");
+                break;
+            },
+            Exit::Restart => {
+                // Reset the backoff if the bot ran for a while since the last restart.
+                if started.elapsed() >= RESTART_BACKOFF_RESET {
+                    backoff = RESTART_BACKOFF_MIN;
+                }
+
+                info!("Restarting in {} seconds", backoff.as_secs());
+
+                tokio::select! {
+                    _ = tokio::time::sleep(backoff) => (),
+                    Some(BotEvent::Shutdown { reason }) = events_rx.recv() => {
+                        info!("Shut down while waiting to restart: {reason}");
+                        break;
+                    },
+                }
+
+                backoff = (backoff * 2).min(RESTART_BACKOFF_MAX);
+            },
+        }
+    }
+
+    Ok(())
+}
+
+/// How the bot event loop ended.
+enum Exit {
+    Shutdown(ShutdownReason),
+    Restart,
+}
+
+/// Setup the bot and run the event loop until shutdown or restart.
+async fn run(
+    events_tx: BotEventSender,
+    events_rx: &mut mpsc::UnboundedReceiver<BotEvent>,
+) -> AnyResult<Exit> {
+    let config = BotConfig::new()?;
+    let commands = bot::create_commands(&config)?;
+
+    let (ctx, mut shards) = Context::new(events_tx, config, commands).await?;
+
+    // Rotate bot activities in the background, if configured.
+    let senders = shards.iter().map(|s| s.sender()).collect();
+    let rotation = ctx
+        .config
+        .global()
+        .activity_settings()?
+        .to_owned()
+        .map(|settings| tokio::spawn(activity::rotate(ctx.clone(), senders, settings)));
 
     // Create an infinite stream over the shards' events.
     let mut stream = ShardEventStream::new(shards.iter_mut());
 
-    loop {
+    let exit = loop {
         use futures::prelude::*;
 
         let (shard, event) = tokio::select! {
             Some(twilight_event) = stream.next() => twilight_event,
-            Some(BotEvent::Shutdown) = events_rx.recv() => break,
-            else => break,
+            Some(event) = events_rx.recv() => match event {
+                BotEvent::Shutdown { reason } => break Exit::Shutdown(reason),
+                BotEvent::Restart => break Exit::Restart,
+            },
+            else => {
+                break Exit::Shutdown(ShutdownReason::Fatal("Event streams closed".into()));
+            },
         };
 
         // Process each event as they come in.
         let event = match event {
             Ok(event) => event,
             Err(source) => {
-                eprintln!("Error receiving event: {:?}", source);
                 if source.is_fatal() {
                     error!(?source, "Error receiving event");
-                    break;
+                    break Exit::Shutdown(ShutdownReason::Fatal(source.to_string()));
                 } else {
                     warn!(?source, "Error receiving event");
                     continue;
@@ -93,18 +182,71 @@ async fn main() -> AnyResult<()> {
         };
 
         ctx.handle(shard, event, handle_event).await;
-    }
+    };
 
     drop(stream);
 
-    for shard in shards.iter_mut() {
-        let _ = shard
-            .close(CloseFrame::NORMAL)
-            .await
-            .map_err(|e| warn!("{e}"));
+    if let Some(rotation) = rotation {
+        rotation.abort();
     }
 
-    Ok(())
+    // Sessions are not stored after a fatal error, they are likely unusable.
+    let can_resume = match &exit {
+        Exit::Shutdown(reason) => reason.can_resume(),
+        Exit::Restart => true,
+    };
+    let resume = ctx.config.global().bot_settings()?.resume_sessions && can_resume;
+    let frame = if resume {
+        CloseFrame::RESUME
+    } else {
+        CloseFrame::NORMAL
+    };
+
+    // Delayed tasks are not waited for.
+    ctx.delayed.cancel();
+
+    // Let running commands finish and close the shards, but do not hang on them.
+    let deadline = tokio::time::Instant::now() + ctx.config.global().shutdown_timeout()?;
+
+    if tokio::time::timeout_at(deadline, ctx.running.wait())
+        .await
+        .is_err()
+    {
+        warn!(
+            "Aborting {} event handlers that are still running",
+            ctx.running.count()
+        );
+    }
+    ctx.running.abort();
+
+    let closing = futures::future::join_all(shards.iter_mut().map(|shard| {
+        let frame = frame.clone();
+        async move { (shard.id().number(), shard.close(frame).await) }
+    }));
+
+    let mut sessions = Sessions::new();
+
+    match tokio::time::timeout_at(deadline, closing).await {
+        Ok(closed) => {
+            for (id, result) in closed {
+                match result {
+                    Ok(Some(session)) if resume => {
+                        sessions.insert(id, session);
+                    },
+                    Ok(_) => (),
+                    Err(e) => warn!("{e}"),
+                }
+            }
+        },
+        Err(_) => warn!("Timed out closing shards, sessions are not stored"),
+    }
+
+    if resume {
+        info!("Storing {} sessions for resuming", sessions.len());
+        ctx.config.global().save_sessions(sessions)?;
+    }
+
+    Ok(exit)
 }
 
 /// Ctrl-C shutdown task.
@@ -112,15 +254,22 @@ async fn shutdown_task(events_tx: BotEventSender) -> AnyResult<()> {
     tokio::signal::ctrl_c()
         .await
         .expect("Could not register ctrl+c handler");
-    info!("Shutting down by ctrl-c");
-    events_tx.send(BotEvent::Shutdown)?;
-    println!("Ctrl-C");
+    events_tx.send(BotEvent::Shutdown {
+        reason: ShutdownReason::CtrlC,
+    })?;
+    info!("Ctrl-C");
     Ok(())
 }
 
 /// Main events handler.
 #[tracing::instrument(name = "events", skip_all, fields(event = event.kind().name()))]
 async fn handle_event(ctx: Context, event: Event) -> AnyResult<()> {
+    // Errors of commands can be reported to the guild they came from.
+    let guild_id = match &event {
+        Event::InteractionCreate(_) | Event::MessageCreate(_) => event.guild_id(),
+        _ => None,
+    };
+
     let result = match event {
         Event::Ready(r) => handle_ready(&ctx, *r).await,
         Event::GuildCreate(g) => handle_guild_create(&ctx, g.0).await,
@@ -129,6 +278,7 @@ async fn handle_event(ctx: Context, event: Event) -> AnyResult<()> {
         Event::MessageUpdate(mu) => handle_message_update(&ctx, *mu).await,
         Event::MessageDelete(md) => handle_message_delete(&ctx, md).await,
         Event::MessageDeleteBulk(mdb) => handle_message_delete_bulk(&ctx, mdb).await,
+        Event::MemberAdd(m) => verify::member_added(&ctx, m.guild_id, &m.member).await,
         Event::ReactionAdd(r) => handle_reaction_add(&ctx, r.0).await,
         Event::ReactionRemove(r) => handle_reaction_remove(&ctx, r.0).await,
         Event::VoiceStateUpdate(v) => handle_voice_state(&ctx, v.0).await,
@@ -160,7 +310,6 @@ async fn handle_event(ctx: Context, event: Event) -> AnyResult<()> {
 
         // Other events here...
         event => {
-            println!("Event: {:?}", event.kind());
             debug!("Event: {:?}", event.kind());
             Ok(())
         },
@@ -168,14 +317,43 @@ async fn handle_event(ctx: Context, event: Event) -> AnyResult<()> {
 
     if let Err(e) = result {
         let chain = e.oneliner();
-        eprintln!("Event error: {e:?}");
+        debug!("Event error: {e:?}");
         error!("Event error: {chain}");
 
-        if let Ok(id) = env::var("DISCORD_BOTDEV_CHANNEL") {
+        // Only failed commands are reported to the guild, unknown commands are not failures.
+        let command_error = e
+            .downcast_ref::<CommandError>()
+            .is_some_and(|c| !matches!(c, CommandError::NotFound(_)));
+
+        let guild_channel = match guild_id {
+            Some(guild_id) if command_error => ctx.config.guild(guild_id).error_channel()?,
+            _ => None,
+        };
+
+        if let Some((channel_id, verbose)) = guild_channel {
+            // Send error as message on the guild's error channel, without internal details.
+            let text = if verbose {
+                chain
+            } else {
+                e.chain()
+                    .take(2)
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(": ")
+            };
+            // The text can contain user input, so nobody is pinged.
+            ctx.http
+                .create_message(channel_id)
+                .allowed_mentions(Some(&AllowedMentions::default()))
+                .content(&utils::truncate(&format!("⚠️ {text}"), 2000))?
+                .send()
+                .await?;
+        } else if let Ok(id) = env::var("DISCORD_BOTDEV_CHANNEL") {
             // Send error as message on bot dev channel.
             let bot_dev = Id::new(id.parse()?);
             ctx.http
                 .create_message(bot_dev)
+                .allowed_mentions(Some(&AllowedMentions::default()))
                 .content(&format!("{e:?}"))?
                 .send()
                 .await?;
@@ -198,7 +376,6 @@ async fn handle_hello(ctx: &Context, h: Hello) -> AnyResult<()> {
 }
 
 async fn handle_ready(ctx: &Context, ready: Ready) -> AnyResult<()> {
-    println!("Ready: '{}'", ready.user.name);
     info!("Ready: '{}'", ready.user.name);
 
     let commands = ctx.commands.twilight_commands()?;
@@ -206,31 +383,70 @@ async fn handle_ready(ctx: &Context, ready: Ready) -> AnyResult<()> {
     debug!("Creating {} global commands", commands.len());
 
     // Set global application commands.
+    // Built by hand, as twilight's request would drop the installation types and contexts.
+    let request = Request::builder(&Route::SetGlobalCommands {
+        application_id: ctx.application.id.get(),
+    })
+    .json(&commands)?
+    .build();
     ctx.http
-        .interaction(ctx.application.id)
-        .set_global_commands(&commands)
-        .send()
-        .await?;
+        .request::<ListBody<Command>>(request)
+        .await
+        .context("Failed to set global commands")?;
+
+    // Guilds will be reconciled as they become available.
+    ctx.reconciler.start(ready.guilds.len());
 
     Ok(())
 }
 
 async fn handle_guild_create(ctx: &Context, guild: Guild) -> AnyResult<()> {
-    println!("Guild: {}", guild.name);
     info!("Guild: '{}'", guild.name);
 
+    ctx.reconciler
+        .run(guild.id, reconcile_guild(ctx, &guild))
+        .await
+}
+
+/// Bring the guild in line with the configuration.
+async fn reconcile_guild(ctx: &Context, guild: &Guild) -> AnyResult<()> {
     let whitelist = ctx.config.global().whitelist()?.to_owned();
+    let paused = ctx.config.global().whitelist_paused()?;
 
     // If whitelist is enabled, check if this guild is in it.
     if let Some(whitelist) = whitelist {
         if !whitelist.contains(&guild.id) {
-            info!("Leaving a non-whitelisted guild '{}'", guild.id);
-            ctx.http.leave_guild(guild.id).await?;
+            if paused {
+                warn!(
+                    "Staying in a non-whitelisted guild '{}', because the whitelist is paused",
+                    guild.id
+                );
+            } else {
+                info!("Leaving a non-whitelisted guild '{}'", guild.id);
+                ctx.http.leave_guild(guild.id).await?;
+                return Ok(());
+            }
         } else {
             debug!("Whitelisted guild: '{}'", guild.id)
         }
     }
 
+    // Reapply the configured nickname, if it has changed.
+    let nickname = ctx.config.guild(guild.id).nickname()?;
+    if let Some(nick) = nickname {
+        let current = guild.members.iter().find(|m| m.user.id == ctx.user.id);
+        if current.is_none_or(|m| m.nick.as_deref() != Some(&nick)) {
+            ctx.http
+                .update_current_member(guild.id)
+                .nick(Some(&nick))?
+                .await
+                .with_context(|| format!("Failed to reapply nickname in guild '{}'", guild.id))?;
+        }
+    }
+
+    // Post the verification message again, if it was deleted while offline.
+    verify::ensure_message(ctx, guild.id).await?;
+
     // ctx.http
     //     .interaction(ctx.application.id)
     //     .set_guild_commands(guild.id, &commands)
@@ -241,30 +457,37 @@ async fn handle_guild_create(ctx: &Context, guild: Guild) -> AnyResult<()> {
 }
 
 async fn handle_interaction_create(ctx: &Context, mut inter: Interaction) -> AnyResult<()> {
-    // println!("{:#?}", inter);
+    trace!("{inter:#?}");
 
     // Take interaction data from the interaction,
     // so that both can be passed forward without matching again.
     match inter.data.take() {
+        Some(InteractionData::ApplicationCommand(d))
+            if inter.kind == InteractionType::ApplicationCommandAutocomplete =>
+        {
+            handle::autocomplete(ctx, inter, *d)
+                .await
+                .context("Failed to handle autocomplete")?;
+        },
         Some(InteractionData::ApplicationCommand(d)) => {
-            println!("{d:#?}");
             handle::application_command(ctx, inter, *d)
                 .await
                 .context("Failed to handle application command")?;
         },
         Some(InteractionData::MessageComponent(d)) => {
-            println!("{d:#?}");
-            //
+            handle::message_component(ctx, inter, d)
+                .await
+                .context("Failed to handle message component")?;
         },
         Some(InteractionData::ModalSubmit(d)) => {
-            println!("{d:#?}");
-            //
+            handle::modal_submit(ctx, inter, d)
+                .await
+                .context("Failed to handle modal submit")?;
         },
         Some(d) => {
-            println!("{d:#?}");
-            //
+            debug!("Unhandled interaction data: {d:?}");
         },
-        None => println!("{inter:#?}"),
+        None => debug!("Interaction without data: {:?}", inter.kind),
     }
 
     Ok(())
@@ -273,10 +496,19 @@ async fn handle_interaction_create(ctx: &Context, mut inter: Interaction) -> Any
 async fn handle_message_create(ctx: &Context, msg: Message) -> AnyResult<()> {
     // Ignore bot users.
     if msg.author.bot {
-        trace!("Message sender is a bot '{}'", msg.author.name);
+        trace!(
+            "Message sender is a bot '{}'",
+            utils::display_name(&msg.author)
+        );
         return Ok(());
     }
 
+    if let Some(guild_id) = msg.guild_id {
+        if ctx.config.guild(guild_id).message_buffer_enabled()? {
+            ctx.snipes.buffer(guild_id, &msg);
+        }
+    }
+
     let msg = Arc::new(msg);
 
     match handle::classic_command(ctx, Arc::clone(&msg)).await {
@@ -287,11 +519,8 @@ async fn handle_message_create(ctx: &Context, msg: Message) -> AnyResult<()> {
                 && msg.referenced_message.is_none()
             {
                 // Send bot help message.
-                let about_msg = format!(
-                    "Try `/about` or `{prefix}about` for general info, or `/help` or \
-                     `{prefix}help` for commands.",
-                    prefix = ctx.config.classic_prefix(msg.guild_id)?,
-                );
+                let prefix = ctx.config.classic_prefix(msg.guild_id)?.to_string();
+                let about_msg = ctx.text(msg.guild_id, "mention-help", &[("prefix", &prefix)]);
 
                 ctx.http
                     .create_message(msg.channel_id)
@@ -301,10 +530,15 @@ async fn handle_message_create(ctx: &Context, msg: Message) -> AnyResult<()> {
             }
             Ok(())
         },
-        Err(CommandError::AccessDenied) => {
+        Err(
+            e @ (CommandError::AccessDenied
+            | CommandError::Cooldown(_)
+            | CommandError::BotMissingPermissions(_)),
+        ) => {
+            let content = handle::denial_text(ctx, msg.guild_id, &e).unwrap_or_default();
             ctx.http
                 .create_message(msg.channel_id)
-                .content("Rekt, you cannot use that. :melting_face:")?
+                .content(&content)?
                 .reply(msg.id)
                 .await?;
             Ok(())
@@ -313,39 +547,200 @@ async fn handle_message_create(ctx: &Context, msg: Message) -> AnyResult<()> {
     }
 }
 
-async fn handle_message_update(_ctx: &Context, _mu: MessageUpdate) -> AnyResult<()> {
+async fn handle_message_update(ctx: &Context, mu: MessageUpdate) -> AnyResult<()> {
     // TODO Check if updated message is something that should update content from the bot.
+
+    // Previous version of the message, if it was cached.
+    let Some(old) = ctx.snipes.take_held(mu.id) else {
+        return Ok(());
+    };
+
+    let (Some(guild_id), Some(content)) = (mu.guild_id, mu.content) else {
+        return Ok(());
+    };
+
+    // Ignore updates that did not change the content, like embeds loading.
+    if old.content == content {
+        return Ok(());
+    }
+
+    let Some(log_channel_id) = ctx.config.guild(guild_id).edit_log()? else {
+        return Ok(());
+    };
+
+    let author = ctx.user_from(old.author_id).await?;
+
+    // Ignore bot messages.
+    if author.bot {
+        return Ok(());
+    }
+
+    let embed = EmbedBuilder::new()
+        .author(EmbedAuthorBuilder::new(utils::display_name(&author)))
+        .description(format!(
+            "Message edited in {} ([jump]({}))",
+            mu.channel_id.mention(),
+            utils::message_link(Some(guild_id), mu.channel_id, mu.id)
+        ))
+        .field(EmbedFieldBuilder::new(
+            "Before",
+            utils::truncate(&old.content, 1024),
+        ))
+        .field(EmbedFieldBuilder::new(
+            "After",
+            utils::truncate(&content, 1024),
+        ))
+        .footer(EmbedFooterBuilder::new(format!("User id: {}", author.id)))
+        .color(0xDDAA44)
+        .build();
+
+    ctx.http
+        .create_message(log_channel_id)
+        .embeds(&[embed])?
+        .await
+        .with_context(|| format!("Failed to post to edit log in guild '{guild_id}'"))?;
+
     Ok(())
 }
 
 async fn handle_message_delete(ctx: &Context, md: MessageDelete) -> AnyResult<()> {
     let Some(guild_id) = md.guild_id else {
+        ctx.snipes.take_held(md.id);
         return Ok(());
     };
 
-    // Remove reaction roles mappping, if deleted message was one.
-    ctx.config
-        .guild(guild_id)
-        .remove_reaction_roles(md.channel_id, md.id)?;
+    let Some(msg) = message_deleted(ctx, guild_id, md.channel_id, md.id)? else {
+        return Ok(());
+    };
+
+    let Some(log_channel_id) = ctx.config.guild(guild_id).delete_log()? else {
+        return Ok(());
+    };
+
+    let author = ctx.user_from(msg.author_id).await?;
+
+    // Ignore bot messages.
+    if author.bot {
+        return Ok(());
+    }
+
+    let embed = EmbedBuilder::new()
+        .author(EmbedAuthorBuilder::new(utils::display_name(&author)))
+        .description(format!(
+            "Message deleted in {}\n\n{}",
+            md.channel_id.mention(),
+            utils::truncate(&msg.content, 3800)
+        ))
+        .footer(EmbedFooterBuilder::new(format!("User id: {}", author.id)))
+        .color(0xCC4444)
+        .build();
+
+    ctx.http
+        .create_message(log_channel_id)
+        .embeds(&[embed])?
+        .await
+        .with_context(|| format!("Failed to post to delete log in guild '{guild_id}'"))?;
 
     Ok(())
 }
 
 async fn handle_message_delete_bulk(ctx: &Context, mdb: MessageDeleteBulk) -> AnyResult<()> {
-    let message_delete_with = |id| MessageDelete {
-        channel_id: mdb.channel_id,
-        guild_id: mdb.guild_id,
-        id,
+    let Some(guild_id) = mdb.guild_id else {
+        for id in mdb.ids {
+            ctx.snipes.take_held(id);
+        }
+        return Ok(());
     };
 
-    for id in mdb.ids {
-        // Calls single delete handler for every deletion.
-        handle_message_delete(ctx, message_delete_with(id)).await?;
+    let mut deleted = Vec::new();
+    for id in mdb.ids.iter() {
+        if let Some(msg) = message_deleted(ctx, guild_id, mdb.channel_id, *id)? {
+            deleted.push(msg);
+        }
     }
 
+    let Some(log_channel_id) = ctx.config.guild(guild_id).delete_log()? else {
+        return Ok(());
+    };
+
+    // Summarize the deleted messages into one log entry, as many as fit.
+    let mut content = format!(
+        "{} messages deleted in {}, {} of them cached\n",
+        mdb.ids.len(),
+        mdb.channel_id.mention(),
+        deleted.len()
+    );
+
+    // Authors are fetched once, many of the messages are usually by the same few users.
+    let mut author_ids: Vec<_> = deleted.iter().map(|m| m.author_id).collect();
+    author_ids.sort_unstable();
+    author_ids.dedup();
+    let authors = ctx.users_from(&author_ids).await;
+
+    for msg in deleted {
+        let author = author_ids
+            .binary_search(&msg.author_id)
+            .ok()
+            .and_then(|i| authors[i].as_ref());
+
+        // Ignore bot messages.
+        if author.is_some_and(|a| a.bot) {
+            continue;
+        }
+
+        let name = author.map_or_else(
+            || msg.author_id.mention().to_string(),
+            |a| utils::display_name(a).into_owned(),
+        );
+        let line = format!("\n**{name}**: {}", utils::truncate(&msg.content, 200));
+
+        if content.chars().count() + line.chars().count() > 3800 {
+            content.push_str("\n…");
+            break;
+        }
+
+        content.push_str(&line);
+    }
+
+    let embed = EmbedBuilder::new()
+        .description(content)
+        .color(0xCC4444)
+        .build();
+
+    ctx.http
+        .create_message(log_channel_id)
+        .embeds(&[embed])?
+        .await
+        .with_context(|| format!("Failed to post to delete log in guild '{guild_id}'"))?;
+
     Ok(())
 }
 
+/// Cleanup after a guild message deletion.
+/// Returns the deleted message if it was cached.
+fn message_deleted(
+    ctx: &Context,
+    guild_id: Id<GuildMarker>,
+    channel_id: Id<ChannelMarker>,
+    message_id: Id<MessageMarker>,
+) -> AnyResult<Option<MessageCopy>> {
+    let deleted = ctx.snipes.take_held(message_id);
+
+    // Remember the deleted message, if the guild allows it.
+    if let Some(msg) = &deleted {
+        if ctx.config.guild(guild_id).snipe_enabled()? {
+            ctx.snipes.insert(channel_id, msg.to_owned());
+        }
+    }
+
+    // Remove reaction roles mappping, if deleted message was one.
+    ctx.config
+        .guild(guild_id)
+        .remove_reaction_roles(channel_id, message_id)?;
+
+    Ok(deleted)
+}
+
 async fn handle_reaction_add(ctx: &Context, reaction: GatewayReaction) -> AnyResult<()> {
     let Some(guild_id) = reaction.guild_id else {
         return Ok(());
@@ -389,9 +784,9 @@ async fn handle_reaction_add(ctx: &Context, reaction: GatewayReaction) -> AnyRes
     };
 
     if add_roles.is_empty() {
-        info!("No roles to add for '{}'", user.name);
+        info!("No roles to add for '{}'", utils::display_name(&user));
     } else {
-        info!("Adding roles for '{}'", user.name);
+        info!("Adding roles for '{}'", utils::display_name(&user));
         for role_id in add_roles {
             ctx.http
                 .add_guild_member_role(guild_id, reaction.user_id, role_id)
@@ -445,9 +840,9 @@ async fn handle_reaction_remove(ctx: &Context, reaction: GatewayReaction) -> Any
     };
 
     if remove_roles.is_empty() {
-        info!("No roles to remove for '{}'", user.name);
+        info!("No roles to remove for '{}'", utils::display_name(&user));
     } else {
-        info!("Removing roles for '{}'", user.name);
+        info!("Removing roles for '{}'", utils::display_name(&user));
         for role_id in remove_roles {
             ctx.http
                 .remove_guild_member_role(guild_id, reaction.user_id, role_id)